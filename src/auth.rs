@@ -0,0 +1,313 @@
+/// Result of looking for a grant at one level of a path.
+pub enum Check<E> {
+    /// No grant applies at this level, so the walk moves on.
+    Absent,
+    /// A grant applies and is valid, which authorizes the path.
+    Granted,
+    /// A grant applies but can't be used (e.g. it has expired), which ends the
+    /// walk rather than falling back to any grant further up.
+    Invalid(E),
+}
+
+/// Outcome of walking a path and its ancestors.
+pub enum Outcome<E> {
+    /// A grant at `path` authorizes access, either directly or via `role`.
+    Allowed { path: String, role: Option<String> },
+    /// An explicit denial at `path` takes precedence over any grants at or
+    /// above it.
+    Denied { path: String },
+    /// The most specific grant, at `path`, can't be used.
+    Invalid { path: String, error: E },
+    /// Nothing grants the path or any of its ancestors.
+    NotGranted,
+}
+
+/// Walk a canonical path up to its topmost ancestor, returning at the first
+/// level with a denial or grant, so that the most specific grant wins. Lookups
+/// are left to the given closures, so the walk can run over any storage.
+///
+/// At each level, `denied_at` is checked first, then `direct_at`, and then,
+/// only if there's no direct grant, each role that `roles_at` yields, in
+/// order. The first role granting access wins, while an invalid role grant
+/// only ends the walk if no other role at the level grants access. Grant
+/// lookups are told whether the level is the exact path requested, for grants
+/// that aren't inherited.
+pub fn authorize<E, R>(
+    cannonical_path: &str,
+    mut denied_at: impl FnMut(&str) -> bool,
+    mut direct_at: impl FnMut(&str, bool) -> Check<E>,
+    mut roles_at: impl FnMut(&str, bool) -> R,
+) -> Outcome<E>
+where
+    R: IntoIterator<Item = (String, Check<E>)>,
+{
+    for path in ancestors(cannonical_path) {
+        let is_exact = path == cannonical_path;
+
+        if denied_at(path) {
+            return Outcome::Denied {
+                path: path.to_owned(),
+            };
+        }
+
+        let (role, check) = match direct_at(path, is_exact) {
+            Check::Absent => {
+                let mut first_invalid: Option<(String, Check<E>)> = None;
+                let mut granted: Option<(String, Check<E>)> = None;
+                for (role, check) in roles_at(path, is_exact) {
+                    match check {
+                        Check::Absent => {},
+                        Check::Granted => {
+                            granted = Some((role, check));
+                            break;
+                        },
+                        Check::Invalid(_) => {
+                            first_invalid.get_or_insert((role, check));
+                        },
+                    }
+                }
+                match granted.or(first_invalid) {
+                    Some((role, check)) => (Some(role), check),
+                    None => continue,
+                }
+            },
+            check => (None, check),
+        };
+
+        return match check {
+            Check::Invalid(error) => Outcome::Invalid {
+                path: path.to_owned(),
+                error,
+            },
+            _ => Outcome::Allowed {
+                path: path.to_owned(),
+                role,
+            },
+        };
+    }
+
+    Outcome::NotGranted
+}
+
+/// Iterate over a canonical path and each of its ancestors, most specific
+/// first, in either path style. The root is included only when it's the path
+/// itself, as in the crumb-based walks elsewhere in the ACL.
+pub fn ancestors(cannonical_path: &str) -> impl Iterator<Item = &str> {
+    let mut next = Some(cannonical_path);
+    std::iter::from_fn(move || {
+        let path = next?;
+        next = match path.rfind('/') {
+            Some(i) if i > 0 => Some(&path[..i]),
+            _ => None,
+        };
+        Some(path)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ancestors, authorize, Check, Outcome};
+
+    #[test]
+    fn ancestors_walk_up_from_most_specific() {
+        for (path, expected) in [
+            ("/a/b/c", vec!["/a/b/c", "/a/b", "/a"]),
+            ("/a", vec!["/a"]),
+            ("/", vec!["/"]),
+            ("a/b/c", vec!["a/b/c", "a/b", "a"]),
+            ("a", vec!["a"]),
+        ] {
+            assert_eq!(ancestors(path).collect::<Vec<_>>(), expected, "{}", path);
+        }
+    }
+
+    /// A grant at a path, directly if `role` is None, whose state is "granted",
+    /// "absent", or otherwise the error that makes it invalid.
+    #[derive(Clone, Copy)]
+    struct Grant {
+        path: &'static str,
+        role: Option<&'static str>,
+        state: &'static str,
+        exact: bool,
+    }
+
+    const fn grant(
+        path: &'static str,
+        role: Option<&'static str>,
+        state: &'static str,
+    ) -> Grant {
+        Grant {
+            path,
+            role,
+            state,
+            exact: false,
+        }
+    }
+
+    fn check(
+        grant: &Grant,
+        is_exact: bool,
+    ) -> Check<&'static str> {
+        match grant.state {
+            _ if grant.exact && !is_exact => Check::Absent,
+            "granted" => Check::Granted,
+            "absent" => Check::Absent,
+            error => Check::Invalid(error),
+        }
+    }
+
+    fn run(
+        path: &str,
+        denied: &[&str],
+        grants: &[Grant],
+    ) -> String {
+        let outcome = authorize(
+            path,
+            |at| denied.contains(&at),
+            |at, is_exact| {
+                grants
+                    .iter()
+                    .find(|g| g.path == at && g.role.is_none())
+                    .map_or(Check::Absent, |g| check(g, is_exact))
+            },
+            |at, is_exact| {
+                grants
+                    .iter()
+                    .filter(|g| g.path == at)
+                    .filter_map(|g| g.role.map(|role| (role.to_owned(), check(g, is_exact))))
+                    .collect::<Vec<_>>()
+            },
+        );
+        match outcome {
+            Outcome::Allowed { path, role } => format!("allowed {} {:?}", path, role),
+            Outcome::Denied { path } => format!("denied {}", path),
+            Outcome::Invalid { path, error } => format!("invalid {} {}", path, error),
+            Outcome::NotGranted => "not granted".to_owned(),
+        }
+    }
+
+    #[test]
+    fn authorize_stops_at_most_specific_grant_or_denial() {
+        let exact = Grant {
+            exact: true,
+            ..grant("/a", None, "granted")
+        };
+        let cases = [
+            (
+                "direct grant on an ancestor",
+                "/a/b/c",
+                vec![],
+                vec![grant("/a", None, "granted")],
+                "allowed /a None",
+            ),
+            (
+                "bare path style",
+                "a/b",
+                vec![],
+                vec![grant("a", None, "granted")],
+                "allowed a None",
+            ),
+            (
+                "denial below a grant",
+                "/a/b/c",
+                vec!["/a/b"],
+                vec![grant("/a", None, "granted")],
+                "denied /a/b",
+            ),
+            (
+                "grant below a denial",
+                "/a/b/c",
+                vec!["/a"],
+                vec![grant("/a/b", None, "granted")],
+                "allowed /a/b None",
+            ),
+            (
+                "denial at the granted path",
+                "/a",
+                vec!["/a"],
+                vec![grant("/a", None, "granted")],
+                "denied /a",
+            ),
+            (
+                "direct grant before roles",
+                "/a",
+                vec![],
+                vec![
+                    grant("/a", Some("editors"), "granted"),
+                    grant("/a", None, "granted"),
+                ],
+                "allowed /a None",
+            ),
+            (
+                "first applicable role in order",
+                "/a",
+                vec![],
+                vec![
+                    grant("/a", Some("viewers"), "absent"),
+                    grant("/a", Some("editors"), "granted"),
+                    grant("/a", Some("admins"), "granted"),
+                ],
+                "allowed /a Some(\"editors\")",
+            ),
+            (
+                "invalid grant without falling back",
+                "/a/b",
+                vec![],
+                vec![grant("/a/b", None, "expired"), grant("/a", None, "granted")],
+                "invalid /a/b expired",
+            ),
+            (
+                "invalid role grant",
+                "/a",
+                vec![],
+                vec![grant("/a", Some("editors"), "expired")],
+                "invalid /a expired",
+            ),
+            (
+                "valid role after an invalid one",
+                "/a",
+                vec![],
+                vec![
+                    grant("/a", Some("admins"), "expired"),
+                    grant("/a", Some("editors"), "granted"),
+                ],
+                "allowed /a Some(\"editors\")",
+            ),
+            (
+                "first invalid role when none is valid",
+                "/a",
+                vec![],
+                vec![
+                    grant("/a", Some("admins"), "expired"),
+                    grant("/a", Some("editors"), "pending"),
+                ],
+                "invalid /a expired",
+            ),
+            (
+                "nothing granted",
+                "/a/b",
+                vec![],
+                vec![grant("/c", None, "granted")],
+                "not granted",
+            ),
+            (
+                "exact grant at its own path",
+                "/a",
+                vec![],
+                vec![exact],
+                "allowed /a None",
+            ),
+            (
+                "exact grant not inherited",
+                "/a/b",
+                vec![],
+                vec![exact],
+                "not granted",
+            ),
+        ];
+
+        for (name, path, denied, grants, expected) in cases {
+            assert_eq!(run(path, &denied, &grants), expected, "{}", name);
+        }
+    }
+}
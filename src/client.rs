@@ -1,7 +1,9 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ensure_eq, Addr, Empty, QuerierWrapper, StdError, StdResult};
+use cosmwasm_std::{
+    ensure_eq, from_json, to_json_string, Addr, Empty, QuerierWrapper, StdError, StdResult,
+};
 
 use crate::msg::{IsAllowedParams, QueryMsg, TestRequirement};
 
@@ -9,20 +11,51 @@ use crate::msg::{IsAllowedParams, QueryMsg, TestRequirement};
 pub enum Operator {
     Address(Addr),
     Acl(Addr),
+    /// Any of the given addresses may operate the ACL. The threshold is
+    /// reserved for requiring approval by multiple members in the future.
+    Multi {
+        addrs: Vec<Addr>,
+        threshold: u32,
+    },
+    /// No one may operate the ACL, which is frozen as is. This is only set by
+    /// renouncing the operator and can't be undone.
+    None,
 }
 
+/// Formats the operator as the same JSON used in messages, such as
+/// `{"address":"juno1..."}`, which can be parsed back with `str::parse`.
 impl fmt::Display for Operator {
     fn fmt(
         &self,
         f: &mut fmt::Formatter<'_>,
     ) -> fmt::Result {
-        match self {
-            Self::Address(addr) => write!(f, "{{\"address\": \"{}\"}}", addr.to_string()),
-            Self::Acl(addr) => write!(f, "{{\"acl\": \"{}\"}}", addr.to_string()),
-        }
+        f.write_str(&to_json_string(self).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl FromStr for Operator {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        from_json(s)
     }
 }
 
+/// Prefix of the control path used unless another is set at instantiation.
+pub const DEFAULT_CONTROL_PREFIX: &str = "/acls";
+
+/// Path through which an operator ACL authorizes changes to the given ACL.
+pub fn to_control_path(
+    prefix: &str,
+    acl_addr: &Addr,
+) -> String {
+    format!("{}/{}", prefix.trim_end_matches('/'), acl_addr)
+}
+
+/// Ensure the sender may act on an ACL run by the given operator. An operator
+/// ACL is asked via IsAllowed, which never recurses into operator checks of
+/// its own. Conditional grants may forward the check to other ACLs, but only
+/// up to a bounded number of hops, so a cycle of ACLs fails cleanly.
 pub fn ensure_is_allowed<F>(
     querier: QuerierWrapper<Empty>,
     sender: &Addr,
@@ -41,17 +74,139 @@ where
             )
         },
         Operator::Acl(acl_addr) => {
-            querier.query_wasm_smart(
+            querier.query_wasm_smart::<bool>(
                 acl_addr,
                 &QueryMsg::IsAllowed(IsAllowedParams {
                     paths: vec![path()],
                     principal: sender.to_string(),
                     raise: Some(true),
                     require: Some(TestRequirement::All),
+                    at: None,
+                    negate: None,
+                    expression: None,
+                    hops: None,
                 }),
             )?;
         },
+        Operator::Multi { addrs, .. } => {
+            if !addrs.contains(sender) {
+                return Err(StdError::generic_err(format!(
+                    "{} is not a contract operator",
+                    sender
+                )));
+            }
+        },
+        Operator::None => {
+            return Err(StdError::generic_err(
+                "contract has no operator and can no longer be changed",
+            ));
+        },
     }
 
     Ok(())
 }
+
+/// Non-raising counterpart to ensure_is_allowed, returning whether the given
+/// address may operate the contract.
+pub fn is_operator<F>(
+    querier: QuerierWrapper<Empty>,
+    addr: &Addr,
+    operator: Operator,
+    path: F,
+) -> StdResult<bool>
+where
+    F: Fn() -> String,
+{
+    Ok(match operator {
+        Operator::Address(operator_addr) => *addr == operator_addr,
+        Operator::Acl(acl_addr) => querier.query_wasm_smart::<bool>(
+            acl_addr,
+            &QueryMsg::IsAllowed(IsAllowedParams {
+                paths: vec![path()],
+                principal: addr.to_string(),
+                raise: Some(false),
+                require: Some(TestRequirement::All),
+                at: None,
+                negate: None,
+                expression: None,
+                hops: None,
+            }),
+        )?,
+        Operator::Multi { addrs, .. } => addrs.contains(addr),
+        Operator::None => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::mock_dependencies, Addr, QuerierWrapper};
+
+    use super::{ensure_is_allowed, is_operator, Operator};
+    use crate::{error::ContractError, utils::validate_operator};
+
+    fn multi(threshold: u32) -> Operator {
+        Operator::Multi {
+            addrs: vec![Addr::unchecked("alice"), Addr::unchecked("bob")],
+            threshold,
+        }
+    }
+
+    #[test]
+    fn any_member_of_multi_operator_is_accepted() {
+        let deps = mock_dependencies();
+        let querier = QuerierWrapper::new(&deps.querier);
+        let path = || "/acls/contract".to_owned();
+
+        for member in ["alice", "bob"] {
+            let addr = Addr::unchecked(member);
+            ensure_is_allowed(querier, &addr, multi(2), path).unwrap();
+            assert!(is_operator(querier, &addr, multi(2), path).unwrap());
+        }
+
+        let stranger = Addr::unchecked("carol");
+        assert!(ensure_is_allowed(querier, &stranger, multi(2), path).is_err());
+        assert!(!is_operator(querier, &stranger, multi(2), path).unwrap());
+    }
+
+    #[test]
+    fn multi_operator_threshold_must_be_attainable() {
+        let deps = mock_dependencies();
+        validate_operator(&deps.api, &multi(1)).unwrap();
+        validate_operator(&deps.api, &multi(2)).unwrap();
+        for threshold in [0, 3] {
+            assert!(matches!(
+                validate_operator(&deps.api, &multi(threshold)),
+                Err(ContractError::ValidationError { .. })
+            ));
+        }
+        assert!(matches!(
+            validate_operator(
+                &deps.api,
+                &Operator::Multi {
+                    addrs: vec![],
+                    threshold: 0,
+                }
+            ),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn multi_operator_displays_as_parseable_json() {
+        let operator = multi(1);
+        assert_eq!(operator.to_string().parse::<Operator>().unwrap(), operator);
+    }
+
+    #[test]
+    fn address_and_acl_operators_round_trip_through_strings() {
+        let address = Operator::Address(Addr::unchecked("juno1operator"));
+        assert_eq!(address.to_string(), r#"{"address":"juno1operator"}"#);
+        assert_eq!(address.to_string().parse::<Operator>().unwrap(), address);
+
+        let acl = Operator::Acl(Addr::unchecked("juno1acl"));
+        assert_eq!(acl.to_string(), r#"{"acl":"juno1acl"}"#);
+        assert_eq!(acl.to_string().parse::<Operator>().unwrap(), acl);
+
+        assert!("address:juno1operator".parse::<Operator>().is_err());
+    }
+}
@@ -1,21 +1,70 @@
-use crate::client::ensure_is_allowed;
+use crate::client::{ensure_is_allowed, Operator};
 use crate::error::ContractError;
+use crate::execute::add_to_group::exec_add_to_group;
 use crate::execute::allow::exec_allow;
 use crate::execute::allow_role::exec_allow_role;
+use crate::execute::clone_role::exec_clone_role;
+use crate::execute::consume_access::exec_consume_access;
+use crate::execute::create_from_template::exec_create_from_template;
+use crate::execute::create_group::exec_create_group;
 use crate::execute::create_role::exec_create_role;
 use crate::execute::deny::exec_deny;
+use crate::execute::deny_many_role::exec_deny_many_role;
 use crate::execute::deny_role::exec_deny_role;
+use crate::execute::forbid::exec_forbid;
 use crate::execute::grant_role::exec_grant_role;
+use crate::execute::import::exec_import;
+use crate::execute::make_permanent::exec_make_permanent;
+use crate::execute::prune_expired::exec_prune_expired;
+use crate::execute::remove_alias::exec_remove_alias;
+use crate::execute::remove_from_group::exec_remove_from_group;
+use crate::execute::remove_role::exec_remove_role;
+use crate::execute::remove_sub_operator::exec_remove_sub_operator;
+use crate::execute::remove_superuser::exec_remove_superuser;
+use crate::execute::remove_viewer::exec_remove_viewer;
+use crate::execute::renounce_operator::exec_renounce_operator;
 use crate::execute::revoke_role::exec_revoke_role;
+use crate::execute::set_alias::exec_set_alias;
+use crate::execute::set_config::exec_set_config;
+use crate::execute::set_sub_operator::exec_set_sub_operator;
+use crate::execute::set_superuser::exec_set_superuser;
+use crate::execute::set_viewer::exec_set_viewer;
+use crate::execute::swap_role::exec_swap_role;
+use crate::execute::unforbid::exec_unforbid;
 use crate::execute::{set_operator::exec_set_operator, Context};
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RoleExecuteMsg};
+use crate::models::Config;
+use crate::msg::{
+    ExecuteMsg, GroupExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RoleExecuteMsg,
+};
+use crate::query::access_source::query_access_source;
 use crate::query::acl::query_acl;
+use crate::query::auth_chain::query_auth_chain;
+use crate::query::canonicalize::query_canonicalize;
+use crate::query::consistency_check::query_consistency_check;
+use crate::query::counts::query_counts;
+use crate::query::diff_principals::query_diff_principals;
+use crate::query::expired::query_expired;
+use crate::query::expiring_soon::query_expiring_soon;
+use crate::query::export::query_export;
+use crate::query::glob_paths::query_glob_paths;
 use crate::query::is_allowed::query_is_allowed as query_allowed;
+use crate::query::is_allowed_explain::query_is_allowed_explain;
+use crate::query::is_allowed_subtree::query_is_allowed_subtree;
+use crate::query::is_operator::query_is_operator;
+use crate::query::matrix::query_matrix;
+use crate::query::operator_chain::query_operator_chain;
+use crate::query::path_exists::query_path_exists;
+use crate::query::path_roles::query_path_roles;
 use crate::query::paths::query_paths;
-use crate::query::role::query_role;
+use crate::query::recent_grants::query_recent_grants;
+use crate::query::resolve::query_resolve;
+use crate::query::role::{query_role, query_role_with_paths};
 use crate::query::roles::query_roles;
+use crate::query::roles_by_name::query_roles_by_name;
+use crate::query::roles_under::query_roles_under;
+use crate::query::which_allowed::query_which_allowed;
 use crate::query::ReadonlyContext;
-use crate::state::{self, OP};
+use crate::state::{self, load_control_path, CONFIG, OP, SUB_OPERATORS};
 use cosmwasm_std::{entry_point, to_json_binary};
 use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response};
 use cw2::set_contract_version;
@@ -31,7 +80,7 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(state::init(Context { deps, env, info }, msg)?)
+    state::init(Context { deps, env, info }, msg)
 }
 
 #[entry_point]
@@ -43,22 +92,68 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     // Only allow sender to make changes to ACL if operator. Note that the
     // operator may be either an arbitrary address or an address of another ACL.
-    ensure_is_allowed(deps.querier, &info.sender, OP.load(deps.storage)?, || {
-        format!("/acls/{}", env.contract.address)
-    })?;
+    // A sub-operator may make only the changes within its scoped powers. Once
+    // the operator is renounced, no one may make any changes.
+    let operator = OP.load(deps.storage)?;
+    if operator == Operator::None {
+        return Err(ContractError::NotAuthorized {
+            reason: "ACL is frozen and can no longer be changed".to_owned(),
+        });
+    }
+    let in_sub_operator_scope = SUB_OPERATORS
+        .may_load(deps.storage, &info.sender)?
+        .map(|powers| powers.permits(&msg))
+        .unwrap_or(false);
+    if !in_sub_operator_scope {
+        let control_path = load_control_path(deps.storage, &env.contract.address)?;
+        // The operator ACL's IsAllowed never checks its own operator, so even
+        // a cycle of operator ACLs fails here after a single query.
+        ensure_is_allowed(deps.querier, &info.sender, operator, || {
+            control_path.to_owned()
+        })
+        .map_err(|e| ContractError::NotAuthorized {
+            reason: e.to_string(),
+        })?;
+    }
 
     let ctx = Context { deps, env, info };
 
     match msg {
         ExecuteMsg::SetOperator(operator) => exec_set_operator(ctx, operator),
+        ExecuteMsg::SetConfig(config) => exec_set_config(ctx, config),
         ExecuteMsg::Allow(msg) => exec_allow(ctx, msg),
         ExecuteMsg::Deny(msg) => exec_deny(ctx, msg),
+        ExecuteMsg::ConsumeAccess(msg) => exec_consume_access(ctx, msg),
+        ExecuteMsg::MakePermanent(msg) => exec_make_permanent(ctx, msg),
+        ExecuteMsg::Forbid(msg) => exec_forbid(ctx, msg),
+        ExecuteMsg::Unforbid(msg) => exec_unforbid(ctx, msg),
+        ExecuteMsg::SetAlias(msg) => exec_set_alias(ctx, msg),
+        ExecuteMsg::RemoveAlias(msg) => exec_remove_alias(ctx, msg),
+        ExecuteMsg::SetViewer(msg) => exec_set_viewer(ctx, msg),
+        ExecuteMsg::RemoveViewer(msg) => exec_remove_viewer(ctx, msg),
+        ExecuteMsg::SetSubOperator(msg) => exec_set_sub_operator(ctx, msg),
+        ExecuteMsg::RemoveSubOperator(msg) => exec_remove_sub_operator(ctx, msg),
+        ExecuteMsg::SetSuperuser(msg) => exec_set_superuser(ctx, msg),
+        ExecuteMsg::RemoveSuperuser {} => exec_remove_superuser(ctx),
+        ExecuteMsg::RenounceOperator(msg) => exec_renounce_operator(ctx, msg),
+        ExecuteMsg::PruneExpired(msg) => exec_prune_expired(ctx, msg),
+        ExecuteMsg::Import(msg) => exec_import(ctx, msg),
         ExecuteMsg::Role(msg) => match msg {
             RoleExecuteMsg::Create(msg) => exec_create_role(ctx, msg),
+            RoleExecuteMsg::CreateFromTemplate(msg) => exec_create_from_template(ctx, msg),
+            RoleExecuteMsg::Clone(msg) => exec_clone_role(ctx, msg),
             RoleExecuteMsg::Allow(msg) => exec_allow_role(ctx, msg),
             RoleExecuteMsg::Deny(msg) => exec_deny_role(ctx, msg),
+            RoleExecuteMsg::DenyMany(msg) => exec_deny_many_role(ctx, msg),
             RoleExecuteMsg::Grant(msg) => exec_grant_role(ctx, msg),
             RoleExecuteMsg::Revoke(msg) => exec_revoke_role(ctx, msg),
+            RoleExecuteMsg::Swap(msg) => exec_swap_role(ctx, msg),
+            RoleExecuteMsg::Remove(msg) => exec_remove_role(ctx, msg),
+        },
+        ExecuteMsg::Group(msg) => match msg {
+            GroupExecuteMsg::Create(msg) => exec_create_group(ctx, msg),
+            GroupExecuteMsg::Add(msg) => exec_add_to_group(ctx, msg),
+            GroupExecuteMsg::Remove(msg) => exec_remove_from_group(ctx, msg),
         },
     }
 }
@@ -72,10 +167,89 @@ pub fn query(
     let ctx = ReadonlyContext { deps, env };
     let result = match msg {
         QueryMsg::Acl {} => to_json_binary(&query_acl(ctx)?),
+        QueryMsg::Counts {} => to_json_binary(&query_counts(ctx)?),
         QueryMsg::Roles { principal } => to_json_binary(&query_roles(ctx, principal)?),
         QueryMsg::Role(role) => to_json_binary(&query_role(ctx, role)?),
+        QueryMsg::RoleWithPaths {
+            name,
+            limit,
+            cursor,
+        } => to_json_binary(&query_role_with_paths(ctx, name, limit, cursor)?),
+        QueryMsg::RolesByName { names, strict } => {
+            to_json_binary(&query_roles_by_name(ctx, names, strict)?)
+        },
+        QueryMsg::RolesUnder { prefix } => to_json_binary(&query_roles_under(ctx, prefix)?),
         QueryMsg::Paths(params) => to_json_binary(&query_paths(ctx, params)?),
+        QueryMsg::PathExists { path } => to_json_binary(&query_path_exists(ctx, path)?),
+        QueryMsg::Canonicalize { path } => to_json_binary(&query_canonicalize(ctx, path)?),
+        QueryMsg::GlobPaths {
+            subject,
+            pattern,
+            limit,
+            cursor,
+        } => to_json_binary(&query_glob_paths(ctx, subject, pattern, limit, cursor)?),
+        QueryMsg::RecentGrants {
+            since,
+            until,
+            limit,
+            cursor,
+        } => to_json_binary(&query_recent_grants(ctx, since, until, limit, cursor)?),
+        QueryMsg::Expired {
+            subject,
+            limit,
+            cursor,
+        } => to_json_binary(&query_expired(ctx, subject, limit, cursor)?),
+        QueryMsg::ExpiringSoon {
+            principal,
+            within_secs,
+            limit,
+        } => to_json_binary(&query_expiring_soon(ctx, principal, within_secs, limit)?),
+        QueryMsg::PathRoles {
+            path,
+            inherited,
+            limit,
+            cursor,
+        } => to_json_binary(&query_path_roles(ctx, path, inherited, limit, cursor)?),
         QueryMsg::IsAllowed(msg) => to_json_binary(&query_allowed(ctx, msg)?),
+        QueryMsg::IsAllowedExplain(msg) => to_json_binary(&query_is_allowed_explain(ctx, msg)?),
+        QueryMsg::IsAllowedSubtree { principal, prefix } => {
+            to_json_binary(&query_is_allowed_subtree(ctx, principal, prefix)?)
+        },
+        QueryMsg::IsOperator { address } => to_json_binary(&query_is_operator(ctx, address)?),
+        QueryMsg::OperatorChain { max_depth } => {
+            to_json_binary(&query_operator_chain(ctx, max_depth)?)
+        },
+        QueryMsg::AuthChain { principal, path } => {
+            to_json_binary(&query_auth_chain(ctx, principal, path)?)
+        },
+        QueryMsg::AccessSource { principal, path } => {
+            to_json_binary(&query_access_source(ctx, principal, path)?)
+        },
+        QueryMsg::Resolve { principal, path } => {
+            to_json_binary(&query_resolve(ctx, principal, path)?)
+        },
+        QueryMsg::WhichAllowed { principals, path } => {
+            to_json_binary(&query_which_allowed(ctx, principals, path)?)
+        },
+        QueryMsg::Matrix {
+            principals,
+            paths,
+            viewer,
+        } => to_json_binary(&query_matrix(ctx, principals, paths, viewer)?),
+        QueryMsg::DiffPrincipals {
+            a,
+            b,
+            paths,
+            viewer,
+        } => to_json_binary(&query_diff_principals(ctx, a, b, paths, viewer)?),
+        QueryMsg::ConsistencyCheck { cursor } => {
+            to_json_binary(&query_consistency_check(ctx, cursor)?)
+        },
+        QueryMsg::Export {
+            cursor,
+            limit,
+            viewer,
+        } => to_json_binary(&query_export(ctx, cursor, limit, viewer)?),
     }?;
     Ok(result)
 }
@@ -83,9 +257,157 @@ pub fn query(
 #[entry_point]
 pub fn migrate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _msg: MigrateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // ACLs instantiated before Config was persisted need a default one
+    if CONFIG.may_load(deps.storage)?.is_none() {
+        CONFIG.save(deps.storage, &Config::default())?;
+    }
+
+    state::migrate_auth_records(deps.storage)?;
+    state::migrate_grant_heights(deps.storage, env.block.height)?;
+    state::migrate_role_principals(deps.storage)?;
+    state::migrate_role_count(deps.storage)?;
+    state::migrate_grants_by_time(deps.storage)?;
+    state::migrate_role_indexes(deps.storage)?;
+    state::migrate_role_path_counts(deps.storage)?;
+    state::migrate_role_update_times(deps.storage)?;
+    state::migrate_split_ref_counts(deps.storage)?;
+    state::migrate_path_and_principal_counts(deps.storage)?;
+
     Ok(Response::default())
 }
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{to_json_string, Addr, Uint128};
+    use cw_multi_test::{App, Executor};
+
+    use crate::{
+        client::{to_control_path, Operator, DEFAULT_CONTROL_PREFIX},
+        error::ContractError,
+        models::{Comparator, Condition},
+        msg::{AllowMsg, ExecuteMsg, IsAllowedParams, QueryMsg},
+        testing::{acl_contract, allow_msg, instantiate_acl, is_allowed_params, OPERATOR},
+    };
+
+    #[test]
+    fn operator_acl_cycle_fails_gracefully() {
+        let mut app = App::default();
+        let code_id = app.store_code(acl_contract());
+        let a = instantiate_acl(&mut app, code_id, None);
+        let b = instantiate_acl(&mut app, code_id, Some(Operator::Acl(a.clone())));
+
+        // Let alice operate B through A before closing the loop
+        app.execute_contract(
+            Addr::unchecked(OPERATOR),
+            a.clone(),
+            &ExecuteMsg::Allow(allow_msg(
+                "alice",
+                &to_control_path(DEFAULT_CONTROL_PREFIX, &b),
+            )),
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OPERATOR),
+            a.clone(),
+            &ExecuteMsg::SetOperator(Operator::Acl(b.clone())),
+            &[],
+        )
+        .unwrap();
+
+        let allow_as = |app: &mut App, sender: &str, acl: &Addr| {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                acl.clone(),
+                &ExecuteMsg::Allow(allow_msg("carol", "/docs")),
+                &[],
+            )
+        };
+
+        // Each ACL asks the other once, without recursing
+        for acl in [&a, &b] {
+            let err = allow_as(&mut app, "mallory", acl).unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<ContractError>(),
+                    Some(ContractError::NotAuthorized { .. })
+                ),
+                "{:?}",
+                err
+            );
+        }
+        allow_as(&mut app, "alice", &b).unwrap();
+    }
+
+    /// Allow alice a path on an ACL, conditional on another ACL allowing her a
+    /// path of its own.
+    fn allow_if_allowed(
+        app: &mut App,
+        acl: &Addr,
+        path: &str,
+        other_acl: &Addr,
+        other_path: &str,
+    ) {
+        let msg = to_json_string(&QueryMsg::IsAllowed(IsAllowedParams {
+            raise: Some(true),
+            ..is_allowed_params("{principal}", &[other_path])
+        }))
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OPERATOR),
+            acl.clone(),
+            &ExecuteMsg::Allow(AllowMsg {
+                condition: Some(Condition {
+                    contract: other_acl.clone(),
+                    msg,
+                    field: None,
+                    comparator: Comparator::Eq,
+                    value: Uint128::one(),
+                }),
+                ..allow_msg("alice", path)
+            }),
+            &[],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn conditional_acl_cycle_fails_gracefully() {
+        let mut app = App::default();
+        let code_id = app.store_code(acl_contract());
+        let a = instantiate_acl(&mut app, code_id, None);
+        let b = instantiate_acl(&mut app, code_id, None);
+        let a_control_path = to_control_path(DEFAULT_CONTROL_PREFIX, &a);
+
+        // B lets alice operate A if A allows her /x, which A does if B lets
+        // her operate A
+        allow_if_allowed(&mut app, &b, &a_control_path, &a, "/x");
+        allow_if_allowed(&mut app, &a, "/x", &b, &a_control_path);
+        app.execute_contract(
+            Addr::unchecked(OPERATOR),
+            a.clone(),
+            &ExecuteMsg::SetOperator(Operator::Acl(b.clone())),
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                a.clone(),
+                &ExecuteMsg::Allow(allow_msg("carol", "/docs")),
+                &[],
+            )
+            .unwrap_err();
+        assert!(
+            err.root_cause().to_string().contains("hops between ACLs"),
+            "{:?}",
+            err
+        );
+    }
+}
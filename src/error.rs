@@ -11,6 +11,21 @@ pub enum ContractError {
 
   #[error("ValidationError: {reason:?}")]
   ValidationError { reason: String },
+
+  #[error("RoleNotFound: role {role} does not exist")]
+  RoleNotFound { role: String },
+
+  #[error("GroupNotFound: group {group} does not exist")]
+  GroupNotFound { group: String },
+
+  #[error("RoleExists: role {role} already exists")]
+  RoleExists { role: String },
+
+  #[error("GrantNotFound: {principal} has no grant of {target}")]
+  GrantNotFound { principal: String, target: String },
+
+  #[error("Expired: {principal} access to {target} has expired")]
+  Expired { principal: String, target: String },
 }
 
 impl From<ContractError> for StdError {
@@ -0,0 +1,124 @@
+use crate::{
+    error::ContractError,
+    math::add_u32,
+    msg::GroupMemberMsg,
+    state::{GROUP_INFOS, GROUP_MEMBERS, PRINCIPAL_GROUPS},
+    utils::ensure_valid_principal,
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_add_to_group(
+    ctx: Context,
+    msg: GroupMemberMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let GroupMemberMsg { group, principal } = msg;
+
+    ensure_valid_principal(deps.storage, deps.api, &principal)?;
+
+    let mut info = GROUP_INFOS.may_load(deps.storage, &group)?.ok_or_else(|| {
+        ContractError::GroupNotFound {
+            group: group.clone(),
+        }
+    })?;
+
+    // Only a new member adds to the number of members
+    if !GROUP_MEMBERS.has(deps.storage, (&group, &principal)) {
+        info.n_members = add_u32(info.n_members, 1)?;
+        GROUP_INFOS.save(deps.storage, &group, &info)?;
+    }
+
+    GROUP_MEMBERS.save(deps.storage, (&group, &principal), &0)?;
+    PRINCIPAL_GROUPS.save(deps.storage, (&principal, &group), &0)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_to_group"),
+        attr("group", group),
+        attr("principal", principal),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        msg::{
+            CreateGroupMsg, ExecuteMsg, GroupExecuteMsg, GroupMemberMsg, RemoveGroupMemberMsg,
+            RoleExecuteMsg,
+        },
+        testing::{
+            create_role, exec, grant_role, grant_role_msg, is_allowed, setup, MockDeps, OPERATOR,
+        },
+    };
+
+    fn group_exec(
+        deps: &mut MockDeps,
+        msg: GroupExecuteMsg,
+    ) {
+        exec(deps.as_mut(), OPERATOR, ExecuteMsg::Group(msg)).unwrap();
+    }
+
+    fn member_msg(principal: &str) -> GroupMemberMsg {
+        GroupMemberMsg {
+            group: "team".to_owned(),
+            principal: principal.to_owned(),
+        }
+    }
+
+    #[test]
+    fn members_hold_roles_granted_to_group() {
+        let mut deps = setup();
+        group_exec(
+            &mut deps,
+            GroupExecuteMsg::Create(CreateGroupMsg {
+                name: "team".to_owned(),
+                description: None,
+            }),
+        );
+        group_exec(&mut deps, GroupExecuteMsg::Add(member_msg("alice")));
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "group:team", "editors");
+
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs/a"));
+        assert!(!is_allowed(deps.as_ref(), "bob", "/docs/a"));
+
+        // Membership is resolved at check time, not when the role is granted
+        group_exec(&mut deps, GroupExecuteMsg::Add(member_msg("bob")));
+        assert!(is_allowed(deps.as_ref(), "bob", "/docs/a"));
+
+        group_exec(
+            &mut deps,
+            GroupExecuteMsg::Remove(RemoveGroupMemberMsg {
+                group: "team".to_owned(),
+                principal: "alice".to_owned(),
+                force: None,
+            }),
+        );
+        assert!(!is_allowed(deps.as_ref(), "alice", "/docs/a"));
+    }
+
+    #[test]
+    fn rejects_unknown_groups() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+
+        let result = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Group(GroupExecuteMsg::Add(member_msg("alice"))),
+        );
+        assert!(matches!(result, Err(ContractError::GroupNotFound { .. })));
+
+        let result = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(grant_role_msg(
+                "group:team",
+                "editors",
+            ))),
+        );
+        assert!(matches!(result, Err(ContractError::GroupNotFound { .. })));
+    }
+}
@@ -2,8 +2,12 @@ use crate::{
     error::ContractError,
     models::AuthRecord,
     msg::AllowMsg,
-    state::{PATH_REF_COUNTS, PRINCIPAL_PATH_AUTHORIZATIONS},
-    utils::to_cannonical_path,
+    state::{CONFIG, MAX_METADATA_LEN, ROLE_INFOS},
+    utils::{
+        delay_not_before, ensure_valid_max_uses, ensure_valid_principal, expiry_attributes,
+        expiry_from_ttl, increment_path_ref_count, resolve_ttl, save_grant, to_acl_path,
+        to_cannonical_role, PathRef,
+    },
 };
 use cosmwasm_std::{attr, Response};
 
@@ -13,31 +17,453 @@ pub fn exec_allow(
     ctx: Context,
     msg: AllowMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, env, .. } = ctx;
+    let Context { deps, env, info } = ctx;
     let AllowMsg {
         principal,
         path,
         ttl,
+        ttl_unit,
+        not_before,
+        max_uses,
+        condition,
+        metadata,
+        no_inherit,
+        require_role,
     } = msg;
+    let ttl = resolve_ttl(ttl, ttl_unit)?;
+
+    ensure_valid_principal(deps.storage, deps.api, &principal)?;
+    ensure_valid_max_uses(max_uses)?;
+
+    if let Some(metadata) = &metadata {
+        if metadata.len() > MAX_METADATA_LEN {
+            return Err(ContractError::ValidationError {
+                reason: format!("metadata cannot be longer than {} bytes", MAX_METADATA_LEN),
+            });
+        }
+        if metadata.chars().any(|c| c.is_control()) {
+            return Err(ContractError::ValidationError {
+                reason: "metadata cannot contain control characters".to_owned(),
+            });
+        }
+    }
+
+    if let Some(condition) = &condition {
+        deps.api.addr_validate(condition.contract.as_str())?;
+    }
+
+    let require_role = require_role
+        .map(|role| to_cannonical_role(deps.storage, role))
+        .transpose()?;
+    if let Some(role) = &require_role {
+        if !ROLE_INFOS.has(deps.storage, role) {
+            return Err(ContractError::RoleNotFound {
+                role: role.to_owned(),
+            });
+        }
+    }
+
+    let config = CONFIG.load(deps.storage)?;
 
     let auth = AuthRecord {
-        expires_at: ttl.and_then(|n| Some(env.block.time.plus_seconds(n.into()))),
+        expires_at: expiry_from_ttl(env.block.time, ttl)?,
+        not_before: delay_not_before(&config, env.block.time, not_before),
+        max_uses,
+        condition,
+        metadata,
+        no_inherit: no_inherit.unwrap_or(false),
+        require_role,
+        granted_by: info.sender,
+        granted_at: env.block.time,
+        granted_at_height: env.block.height,
+    };
+
+    let cannonical_path = to_acl_path(deps.storage, &path)?;
+
+    // Only a new grant adds a reference to the path, not an updated one
+    if save_grant(deps.storage, &principal, &cannonical_path, &auth)?.is_none() {
+        increment_path_ref_count(deps.storage, &cannonical_path, PathRef::Principal)?;
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "allow"),
+            attr("principal", principal),
+            attr("path", cannonical_path),
+        ])
+        .add_attributes(expiry_attributes(auth.expires_at)))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_env;
+
+    use crate::{
+        error::ContractError,
+        models::Config,
+        msg::{
+            AllowMsg, AllowRoleMsg, ExecuteMsg, GrantRoleMsg, PathsQueryParams, QueryMsg,
+            RevokeRoleMsg, RoleExecuteMsg, Subject,
+        },
+        responses::{AuthFailureKind, IsAllowedExplainResponse, PathsResponse},
+        state::MAX_METADATA_LEN,
+        testing::{
+            allow, allow_msg, create_role, exec, grant_role, grant_role_msg, is_allowed,
+            is_allowed_params, query_as, setup, setup_with_config, MockDeps, OPERATOR,
+        },
     };
 
-    let cannonical_path = to_cannonical_path(&path);
-
-    PATH_REF_COUNTS.save(deps.storage, &cannonical_path, &0)?;
-    PRINCIPAL_PATH_AUTHORIZATIONS.save(deps.storage, (&principal, &cannonical_path), &auth)?;
-
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "allow"),
-        attr("principal", principal),
-        attr("path", cannonical_path),
-        attr(
-            "expires_at",
-            auth.expires_at
-                .and_then(|t| Some(t.to_string()))
-                .unwrap_or(String::from("null")),
-        ),
-    ]))
+    #[test]
+    fn principals_are_validated_when_configured() {
+        let mut deps = setup_with_config(Config {
+            validate_principals: true,
+            ..Config::default()
+        });
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+
+        for principal in ["alice", "Not An Address"] {
+            let is_valid = principal == "alice";
+            let allowed = exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Allow(allow_msg(principal, "/docs")),
+            );
+            let granted = exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Role(RoleExecuteMsg::Grant(grant_role_msg(principal, "editors"))),
+            );
+            assert_eq!(allowed.is_ok(), is_valid, "allow {}", principal);
+            assert_eq!(granted.is_ok(), is_valid, "grant {}", principal);
+        }
+    }
+
+    #[test]
+    fn any_principal_is_accepted_by_default() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(allow_msg("Not An Address", "/docs")),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn grants_take_effect_at_not_before() {
+        let mut deps = setup();
+        let now = mock_env().block.time;
+        let activation = now.plus_seconds(3600);
+
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                not_before: Some(activation),
+                ..allow_msg("alice", "/projects")
+            }),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                not_before: Some(activation),
+                ..grant_role_msg("bob", "editors")
+            })),
+        )
+        .unwrap();
+
+        for (principal, path) in [("alice", "/projects"), ("bob", "/docs")] {
+            let at = |time| {
+                let mut params = is_allowed_params(principal, &[path]);
+                params.at = Some(time);
+                params
+            };
+
+            let explained: IsAllowedExplainResponse =
+                query_as(deps.as_ref(), QueryMsg::IsAllowedExplain(at(now))).unwrap();
+            assert!(!explained.allowed, "{} now", principal);
+            assert_eq!(
+                explained.paths[0].failure,
+                Some(AuthFailureKind::NotYetActive)
+            );
+            assert!(explained.reason.unwrap().contains("not yet active"));
+
+            let before: bool = query_as(
+                deps.as_ref(),
+                QueryMsg::IsAllowed(at(activation.minus_nanos(1))),
+            )
+            .unwrap();
+            let after: bool = query_as(deps.as_ref(), QueryMsg::IsAllowed(at(activation))).unwrap();
+            assert!(!before && after, "{} at activation", principal);
+        }
+    }
+
+    #[test]
+    fn expiry_is_given_as_nanos_and_rfc3339() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+
+        let allowed = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(3_600),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+        let granted = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(3_600),
+                ..grant_role_msg("alice", "editors")
+            })),
+        )
+        .unwrap();
+
+        for resp in [allowed, granted] {
+            let value = |key: &str| {
+                resp.attributes
+                    .iter()
+                    .find(|a| a.key == key)
+                    .map(|a| a.value.to_owned())
+            };
+            // The mock block time is 2019-10-23T02:23:39.879305533Z
+            assert_eq!(
+                value("expires_at_nanos").as_deref(),
+                Some("1571801019879305533")
+            );
+            assert_eq!(
+                value("expires_at_rfc3339").as_deref(),
+                Some("2019-10-23T03:23:39Z")
+            );
+            assert_eq!(value("expires_at").as_deref(), Some("1571801019.879305533"));
+        }
+    }
+
+    #[test]
+    fn metadata_is_stored_and_reported() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                metadata: Some(r#"{"ticket":"SEC-42"}"#.to_owned()),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+
+        let resp: PathsResponse = query_as(
+            deps.as_ref(),
+            QueryMsg::Paths(PathsQueryParams {
+                subject: Subject::Principal("alice".to_owned()),
+                limit: None,
+                start: None,
+                stop: None,
+                cursor: None,
+                with_refs: None,
+                order_by: None,
+                include_roles: None,
+                expand: None,
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            resp.paths[0].metadata.as_deref(),
+            Some(r#"{"ticket":"SEC-42"}"#)
+        );
+    }
+
+    #[test]
+    fn oversized_or_unprintable_metadata_is_rejected() {
+        let mut deps = setup();
+        for metadata in ["x".repeat(MAX_METADATA_LEN + 1), "line\nbreak".to_owned()] {
+            let result = exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Allow(AllowMsg {
+                    metadata: Some(metadata),
+                    ..allow_msg("alice", "/docs")
+                }),
+            );
+            assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+        }
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                metadata: Some("x".repeat(MAX_METADATA_LEN)),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn grant_with_no_uses_is_rejected() {
+        let mut deps = setup();
+        let allow_with_uses = |deps: &mut MockDeps, max_uses: u32| {
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Allow(AllowMsg {
+                    max_uses: Some(max_uses),
+                    ..allow_msg("alice", "/docs")
+                }),
+            )
+        };
+
+        assert!(matches!(
+            allow_with_uses(&mut deps, 0),
+            Err(ContractError::ValidationError { .. })
+        ));
+        assert!(!is_allowed(deps.as_ref(), "alice", "/docs"));
+
+        allow_with_uses(&mut deps, 1).unwrap();
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs"));
+    }
+
+    #[test]
+    fn no_inherit_grant_covers_only_its_own_path() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                no_inherit: Some(true),
+                ..allow_msg("alice", "/a/b")
+            }),
+        )
+        .unwrap();
+        create_role(deps.as_mut(), "editors", &[]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Allow(AllowRoleMsg {
+                role: "editors".to_owned(),
+                path: "/a/b".to_owned(),
+                ttl: None,
+                no_inherit: Some(true),
+            })),
+        )
+        .unwrap();
+        grant_role(deps.as_mut(), "bob", "editors");
+
+        for principal in ["alice", "bob"] {
+            assert!(is_allowed(deps.as_ref(), principal, "/a/b"));
+            assert!(!is_allowed(deps.as_ref(), principal, "/a/b/c"));
+        }
+
+        // An inheriting grant on an ancestor still covers descendants
+        allow(deps.as_mut(), "alice", "/a");
+        assert!(is_allowed(deps.as_ref(), "alice", "/a/b/c"));
+    }
+
+    #[test]
+    fn grant_requiring_role_applies_only_while_role_is_held() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "mfa", &[]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                require_role: Some("mfa".to_owned()),
+                ..allow_msg("alice", "/vault")
+            }),
+        )
+        .unwrap();
+        assert!(!is_allowed(deps.as_ref(), "alice", "/vault"));
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(60),
+                ..grant_role_msg("alice", "mfa")
+            })),
+        )
+        .unwrap();
+        assert!(is_allowed(deps.as_ref(), "alice", "/vault/keys"));
+
+        // Once the role expires, the grant no longer applies
+        let mut params = is_allowed_params("alice", &["/vault"]);
+        params.at = Some(mock_env().block.time.plus_seconds(60));
+        let allowed: bool = query_as(deps.as_ref(), QueryMsg::IsAllowed(params)).unwrap();
+        assert!(!allowed);
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Revoke(RevokeRoleMsg {
+                principal: "alice".to_owned(),
+                role: "mfa".to_owned(),
+                force: None,
+            })),
+        )
+        .unwrap();
+        assert!(!is_allowed(deps.as_ref(), "alice", "/vault"));
+
+        // The required role must exist
+        let result = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                require_role: Some("hsm".to_owned()),
+                ..allow_msg("alice", "/vault")
+            }),
+        );
+        assert!(matches!(result, Err(ContractError::RoleNotFound { role }) if role == "hsm"));
+    }
+
+    #[test]
+    fn configured_delay_holds_new_grants_pending() {
+        let mut deps = setup_with_config(Config {
+            grant_delay_secs: Some(3600),
+            ..Config::default()
+        });
+        let now = mock_env().block.time;
+        let later = now.plus_seconds(7200);
+
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "bob", "editors");
+        allow(deps.as_mut(), "alice", "/projects");
+        // A later requested activation is kept as is
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                not_before: Some(later),
+                ..allow_msg("carol", "/projects")
+            }),
+        )
+        .unwrap();
+
+        let allowed_at = |principal: &str, path: &str, time| -> bool {
+            let mut params = is_allowed_params(principal, &[path]);
+            params.at = Some(time);
+            query_as(deps.as_ref(), QueryMsg::IsAllowed(params)).unwrap()
+        };
+        let delayed = now.plus_seconds(3600);
+        for (principal, path) in [("alice", "/projects"), ("bob", "/docs")] {
+            assert!(!allowed_at(principal, path, now), "{} now", principal);
+            assert!(
+                !allowed_at(principal, path, delayed.minus_nanos(1)),
+                "{}",
+                principal
+            );
+            assert!(
+                allowed_at(principal, path, delayed),
+                "{} after delay",
+                principal
+            );
+        }
+        assert!(!allowed_at("carol", "/projects", delayed));
+        assert!(allowed_at("carol", "/projects", later));
+    }
 }
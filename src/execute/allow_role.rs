@@ -1,8 +1,13 @@
 use crate::{
     error::ContractError,
+    math::add_u32,
+    models::AuthRecord,
     msg::AllowRoleMsg,
-    state::{PATH_REF_COUNTS, PATH_ROLES, ROLE_PATHS},
-    utils::to_cannonical_path,
+    state::{PATH_ROLES, ROLE_INFOS, ROLE_PATHS},
+    utils::{
+        expiry_attributes, expiry_from_ttl, increment_path_ref_count, to_acl_path,
+        to_cannonical_role, PathRef,
+    },
 };
 use cosmwasm_std::{attr, Response};
 
@@ -12,18 +17,222 @@ pub fn exec_allow_role(
     ctx: Context,
     msg: AllowRoleMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
-    let AllowRoleMsg { role, path } = msg;
+    let Context { deps, env, info } = ctx;
+    let AllowRoleMsg {
+        role,
+        path,
+        ttl,
+        no_inherit,
+    } = msg;
+    let role = to_cannonical_role(deps.storage, role)?;
 
-    let cannonical_path = to_cannonical_path(&path);
+    let mut role_info = ROLE_INFOS
+        .may_load(deps.storage, &role)?
+        .ok_or_else(|| ContractError::RoleNotFound { role: role.clone() })?;
 
-    PATH_REF_COUNTS.save(deps.storage, &cannonical_path, &0)?;
-    ROLE_PATHS.save(deps.storage, (&role, &cannonical_path), &0)?;
+    let auth = AuthRecord {
+        expires_at: expiry_from_ttl(env.block.time, ttl)?,
+        not_before: None,
+        max_uses: None,
+        condition: None,
+        metadata: None,
+        no_inherit: no_inherit.unwrap_or(false),
+        require_role: None,
+        granted_by: info.sender,
+        granted_at: env.block.time,
+        granted_at_height: env.block.height,
+    };
+
+    let cannonical_path = to_acl_path(deps.storage, &path)?;
+
+    // Only a new link adds to the role's paths, not an updated one
+    if !ROLE_PATHS.has(deps.storage, (&role, &cannonical_path)) {
+        increment_path_ref_count(deps.storage, &cannonical_path, PathRef::Role)?;
+        role_info.n_paths = add_u32(role_info.n_paths, 1)?;
+    }
+
+    role_info.updated_at = env.block.time;
+    ROLE_INFOS.save(deps.storage, &role, &role_info)?;
+
+    ROLE_PATHS.save(deps.storage, (&role, &cannonical_path), &auth)?;
     PATH_ROLES.save(deps.storage, (&cannonical_path, &role), &0)?;
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "allow_role"),
-        attr("role", role),
-        attr("path", cannonical_path),
-    ]))
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "allow_role"),
+            attr("role", role),
+            attr("path", cannonical_path),
+        ])
+        .add_attributes(expiry_attributes(auth.expires_at)))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_env, mock_info};
+
+    use crate::{
+        contract::execute,
+        msg::{
+            AllowRoleMsg, CreateRoleMsg, DenyRoleMsg, ExecuteMsg, GrantRoleMsg, QueryMsg,
+            RoleExecuteMsg,
+        },
+        responses::RoleResponse,
+        state::{migrate_role_path_counts, ROLE_INFOS},
+        testing::{
+            create_role, exec, grant_role_msg, is_allowed_params, query_as, role_msg, setup,
+            MockDeps, OPERATOR,
+        },
+    };
+
+    #[test]
+    fn role_path_link_expires_before_role_grant() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/wiki"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Allow(AllowRoleMsg {
+                role: "editors".to_owned(),
+                path: "/docs".to_owned(),
+                ttl: Some(60),
+                no_inherit: None,
+            })),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(3600),
+                ..grant_role_msg("alice", "editors")
+            })),
+        )
+        .unwrap();
+
+        let now = mock_env().block.time;
+        let allowed_at = |path: &str, secs: u64| -> bool {
+            let mut params = is_allowed_params("alice", &[path]);
+            params.at = Some(now.plus_seconds(secs));
+            query_as(deps.as_ref(), QueryMsg::IsAllowed(params)).unwrap()
+        };
+
+        assert!(allowed_at("/docs", 30));
+        assert!(!allowed_at("/docs", 120));
+        // The role grant itself is still in effect
+        assert!(allowed_at("/wiki", 120));
+        assert!(!allowed_at("/wiki", 3600));
+    }
+
+    fn n_paths(deps: &MockDeps) -> u32 {
+        query_as::<RoleResponse>(deps.as_ref(), QueryMsg::Role("editors".to_owned()))
+            .unwrap()
+            .n_paths
+    }
+
+    #[test]
+    fn role_path_count_tracks_links() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs", "/wiki"]);
+        assert_eq!(n_paths(&deps), 2);
+
+        let allow_msg = |path: &str| {
+            ExecuteMsg::Role(RoleExecuteMsg::Allow(AllowRoleMsg {
+                role: "editors".to_owned(),
+                path: path.to_owned(),
+                ttl: None,
+                no_inherit: None,
+            }))
+        };
+        exec(deps.as_mut(), OPERATOR, allow_msg("/blog")).unwrap();
+        assert_eq!(n_paths(&deps), 3);
+
+        // Renewing an existing link doesn't count it again
+        exec(deps.as_mut(), OPERATOR, allow_msg("/blog")).unwrap();
+        assert_eq!(n_paths(&deps), 3);
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Deny(DenyRoleMsg {
+                role: "editors".to_owned(),
+                path: "/docs".to_owned(),
+                strict: None,
+                force: None,
+            })),
+        )
+        .unwrap();
+        assert_eq!(n_paths(&deps), 2);
+
+        // Counts lost to an older version are restored by migration
+        let mut info = ROLE_INFOS
+            .load(&deps.storage, &"editors".to_owned())
+            .unwrap();
+        info.n_paths = 0;
+        ROLE_INFOS
+            .save(&mut deps.storage, &"editors".to_owned(), &info)
+            .unwrap();
+        migrate_role_path_counts(&mut deps.storage).unwrap();
+        assert_eq!(n_paths(&deps), 2);
+    }
+
+    #[test]
+    fn updated_at_advances_while_created_at_stays() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        let created = mock_env().block.time;
+
+        let role = |deps: &MockDeps| -> RoleResponse {
+            query_as(deps.as_ref(), QueryMsg::Role("editors".to_owned())).unwrap()
+        };
+        let exec_later = |deps: &mut MockDeps, secs: u64, msg: RoleExecuteMsg| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(secs);
+            execute(
+                deps.as_mut(),
+                env,
+                mock_info(OPERATOR, &[]),
+                ExecuteMsg::Role(msg),
+            )
+            .unwrap();
+        };
+
+        exec_later(
+            &mut deps,
+            60,
+            RoleExecuteMsg::Allow(AllowRoleMsg {
+                role: "editors".to_owned(),
+                path: "/wiki".to_owned(),
+                ttl: None,
+                no_inherit: None,
+            }),
+        );
+        let resp = role(&deps);
+        assert_eq!(resp.created_at, created);
+        assert_eq!(resp.updated_at, created.plus_seconds(60));
+
+        exec_later(
+            &mut deps,
+            120,
+            RoleExecuteMsg::Deny(DenyRoleMsg {
+                role: "editors".to_owned(),
+                path: "/wiki".to_owned(),
+                strict: None,
+                force: None,
+            }),
+        );
+        assert_eq!(role(&deps).updated_at, created.plus_seconds(120));
+
+        exec_later(
+            &mut deps,
+            180,
+            RoleExecuteMsg::Create(CreateRoleMsg {
+                description: Some("Edits docs".to_owned()),
+                upsert: Some(true),
+                ..role_msg("editors", &[])
+            }),
+        );
+        let resp = role(&deps);
+        assert_eq!(resp.created_at, created);
+        assert_eq!(resp.updated_at, created.plus_seconds(180));
+    }
 }
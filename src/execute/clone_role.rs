@@ -0,0 +1,213 @@
+use crate::{
+    error::ContractError,
+    models::AuthRecord,
+    msg::{CloneRoleMsg, CreateRoleMsg},
+    state::{
+        CONFIG, PATH_ROLES, PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS, ROLE_PATHS, ROLE_PRINCIPALS,
+    },
+    utils::{increment_path_ref_count, increment_principal_ref_count, to_cannonical_role, PathRef},
+};
+use cosmwasm_std::{attr, Order, Response, StdResult};
+
+use super::{create_role::exec_create_role, Context};
+
+pub fn exec_clone_role(
+    ctx: Context,
+    msg: CloneRoleMsg,
+) -> Result<Response, ContractError> {
+    let Context {
+        mut deps,
+        env,
+        info,
+    } = ctx;
+    let CloneRoleMsg {
+        from,
+        to,
+        copy_members,
+    } = msg;
+
+    let from = to_cannonical_role(deps.storage, from)?;
+    let to = to_cannonical_role(deps.storage, to)?;
+
+    let from_info = ROLE_INFOS
+        .may_load(deps.storage, &from)?
+        .ok_or_else(|| ContractError::RoleNotFound { role: from.clone() })?;
+
+    // Create the new role without paths, which are copied below along with
+    // their TTLs and grant metadata.
+    exec_create_role(
+        Context {
+            deps: deps.branch(),
+            env,
+            info,
+        },
+        CreateRoleMsg {
+            name: to.to_owned(),
+            description: from_info.description,
+            paths: None,
+            is_template: None,
+            weight: Some(from_info.weight),
+            members: None,
+            upsert: None,
+        },
+    )?;
+
+    let role_paths: Vec<(String, AuthRecord)> = ROLE_PATHS
+        .prefix(&from)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (path, record) in role_paths.iter() {
+        increment_path_ref_count(deps.storage, path, PathRef::Role)?;
+        ROLE_PATHS.save(deps.storage, (&to, path), record)?;
+        PATH_ROLES.save(deps.storage, (path, &to), &0)?;
+    }
+
+    let mut to_info = ROLE_INFOS.load(deps.storage, &to)?;
+    to_info.n_paths = role_paths.len() as u32;
+
+    let mut n_principals: u32 = 0;
+
+    if copy_members {
+        let principals: Vec<String> = ROLE_PRINCIPALS
+            .prefix(&from)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        if let Some(max) = CONFIG.load(deps.storage)?.max_principals_per_role {
+            if principals.len() > max as usize {
+                return Err(ContractError::ValidationError {
+                    reason: format!(
+                        "role {} cannot be granted to more than {} principals",
+                        to, max
+                    ),
+                });
+            }
+        }
+
+        for principal in principals.iter() {
+            let grant = PRINCIPAL_ROLE_AUTHORIZATIONS.load(deps.storage, (principal, &from))?;
+            PRINCIPAL_ROLE_AUTHORIZATIONS.save(deps.storage, (principal, &to), &grant)?;
+            increment_principal_ref_count(deps.storage, principal)?;
+            ROLE_PRINCIPALS.save(deps.storage, (&to, principal), &0)?;
+        }
+
+        n_principals = principals.len() as u32;
+        to_info.n_principals = n_principals;
+    }
+
+    ROLE_INFOS.save(deps.storage, &to, &to_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "clone_role"),
+        attr("from", from),
+        attr("to", to),
+        attr("n_paths", role_paths.len().to_string()),
+        attr("n_principals", n_principals.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        msg::{CloneRoleMsg, ExecuteMsg, QueryMsg, RevokeRoleMsg, RoleExecuteMsg},
+        responses::RoleResponse,
+        testing::{create_role, exec, grant_role, is_allowed, query_as, setup, MockDeps, OPERATOR},
+    };
+
+    fn setup_editors() -> MockDeps {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs", "/wiki"]);
+        for principal in ["alice", "bob", "carol"] {
+            grant_role(deps.as_mut(), principal, "editors");
+        }
+        deps
+    }
+
+    fn clone(
+        deps: &mut MockDeps,
+        from: &str,
+        copy_members: bool,
+    ) -> Result<(), ContractError> {
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Clone(CloneRoleMsg {
+                from: from.to_owned(),
+                to: "reviewers".to_owned(),
+                copy_members,
+            })),
+        )
+        .map(|_| ())
+    }
+
+    fn reviewers(deps: &MockDeps) -> RoleResponse {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::RoleWithPaths {
+                name: "reviewers".to_owned(),
+                limit: None,
+                cursor: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn clones_paths_without_members() {
+        let mut deps = setup_editors();
+        clone(&mut deps, "editors", false).unwrap();
+
+        let role = reviewers(&deps);
+        assert_eq!(role.n_paths, 2);
+        assert_eq!(role.n_principals, 0);
+        assert_eq!(
+            role.paths,
+            Some(vec!["/docs".to_owned(), "/wiki".to_owned()])
+        );
+
+        grant_role(deps.as_mut(), "dave", "reviewers");
+        assert!(is_allowed(deps.as_ref(), "dave", "/wiki/page"));
+    }
+
+    #[test]
+    fn clones_paths_with_members() {
+        let mut deps = setup_editors();
+        clone(&mut deps, "editors", true).unwrap();
+
+        let role = reviewers(&deps);
+        assert_eq!(role.n_paths, 2);
+        assert_eq!(role.n_principals, 3);
+
+        // Members keep access through the clone after losing the original
+        for principal in ["alice", "bob", "carol"] {
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Role(RoleExecuteMsg::Revoke(RevokeRoleMsg {
+                    principal: principal.to_owned(),
+                    role: "editors".to_owned(),
+                    force: None,
+                })),
+            )
+            .unwrap();
+            assert!(is_allowed(deps.as_ref(), principal, "/docs/a"));
+        }
+    }
+
+    #[test]
+    fn rejects_missing_source_and_existing_target() {
+        let mut deps = setup_editors();
+        assert!(matches!(
+            clone(&mut deps, "ghosts", false),
+            Err(ContractError::RoleNotFound { .. })
+        ));
+
+        create_role(deps.as_mut(), "reviewers", &[]);
+        assert!(matches!(
+            clone(&mut deps, "editors", false),
+            Err(ContractError::RoleExists { .. })
+        ));
+    }
+}
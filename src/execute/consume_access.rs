@@ -0,0 +1,186 @@
+use crate::{
+    error::ContractError,
+    msg::ConsumeAccessMsg,
+    state::PRINCIPAL_PATH_AUTHORIZATIONS,
+    utils::{
+        decrement_or_remove_path_ref_count, remove_grant, resolve_principal, saturation_attributes,
+        to_acl_path, PathRef,
+    },
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_consume_access(
+    ctx: Context,
+    msg: ConsumeAccessMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, .. } = ctx;
+    let ConsumeAccessMsg { principal, path } = msg;
+    // Uses are consumed from the grant that IsAllowed would check
+    let principal = resolve_principal(deps.storage, &principal);
+    let cannonical_path = to_acl_path(deps.storage, &path)?;
+
+    let mut auth = PRINCIPAL_PATH_AUTHORIZATIONS
+        .may_load(deps.storage, (&principal, &cannonical_path))?
+        .ok_or_else(|| ContractError::GrantNotFound {
+            principal: principal.to_owned(),
+            target: cannonical_path.to_owned(),
+        })?;
+
+    if auth
+        .expires_at
+        .map(|t| env.block.time >= t)
+        .unwrap_or(false)
+    {
+        return Err(ContractError::Expired {
+            principal,
+            target: cannonical_path,
+        });
+    }
+
+    if auth.not_before.map(|t| env.block.time < t).unwrap_or(false) {
+        return Err(ContractError::ValidationError {
+            reason: format!("{} access to {} not yet active", principal, cannonical_path),
+        });
+    }
+
+    let remaining_uses = match auth.max_uses {
+        Some(n) if n > 0 => n - 1,
+        Some(_) => {
+            return Err(ContractError::ValidationError {
+                reason: format!(
+                    "{} access to {} has no uses left",
+                    principal, cannonical_path
+                ),
+            })
+        },
+        None => {
+            return Err(ContractError::ValidationError {
+                reason: format!(
+                    "{} access to {} is not use-limited",
+                    principal, cannonical_path
+                ),
+            })
+        },
+    };
+
+    // Remove the grant once it's used up
+    let mut saturated = false;
+    if remaining_uses == 0 {
+        remove_grant(deps.storage, &principal, &cannonical_path)?;
+        saturated =
+            decrement_or_remove_path_ref_count(deps.storage, &cannonical_path, PathRef::Principal)?;
+    } else {
+        auth.max_uses = Some(remaining_uses);
+        PRINCIPAL_PATH_AUTHORIZATIONS.save(deps.storage, (&principal, &cannonical_path), &auth)?;
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "consume_access"),
+            attr("principal", principal),
+            attr("path", cannonical_path),
+            attr("remaining_uses", remaining_uses.to_string()),
+        ])
+        .add_attributes(saturation_attributes(saturated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_env;
+
+    use crate::{
+        error::ContractError,
+        msg::{AllowMsg, ConsumeAccessMsg, ExecuteMsg, SetAliasMsg},
+        testing::{allow_msg, exec, is_allowed, setup, MockDeps, OPERATOR},
+    };
+
+    fn allow_uses(
+        deps: &mut MockDeps,
+        msg: AllowMsg,
+    ) {
+        exec(deps.as_mut(), OPERATOR, ExecuteMsg::Allow(msg)).unwrap();
+    }
+
+    fn consume(
+        deps: &mut MockDeps,
+        principal: &str,
+    ) -> Result<(), ContractError> {
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::ConsumeAccess(ConsumeAccessMsg {
+                principal: principal.to_owned(),
+                path: "/docs".to_owned(),
+            }),
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn two_use_grant_authorizes_twice() {
+        let mut deps = setup();
+        allow_uses(
+            &mut deps,
+            AllowMsg {
+                max_uses: Some(2),
+                ..allow_msg("alice", "/docs")
+            },
+        );
+
+        for _ in 0..2 {
+            assert!(is_allowed(deps.as_ref(), "alice", "/docs"));
+            consume(&mut deps, "alice").unwrap();
+        }
+
+        assert!(!is_allowed(deps.as_ref(), "alice", "/docs"));
+        assert!(matches!(
+            consume(&mut deps, "alice"),
+            Err(ContractError::GrantNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn inactive_grant_cannot_be_consumed() {
+        let mut deps = setup();
+        allow_uses(
+            &mut deps,
+            AllowMsg {
+                max_uses: Some(1),
+                not_before: Some(mock_env().block.time.plus_seconds(60)),
+                ..allow_msg("alice", "/docs")
+            },
+        );
+
+        assert!(matches!(
+            consume(&mut deps, "alice"),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn alias_consumes_its_principals_grant() {
+        let mut deps = setup();
+        allow_uses(
+            &mut deps,
+            AllowMsg {
+                max_uses: Some(2),
+                ..allow_msg("alice", "/docs")
+            },
+        );
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetAlias(SetAliasMsg {
+                addr: "alicehot".to_owned(),
+                principal: "alice".to_owned(),
+            }),
+        )
+        .unwrap();
+
+        consume(&mut deps, "alicehot").unwrap();
+        consume(&mut deps, "alice").unwrap();
+        assert!(!is_allowed(deps.as_ref(), "alicehot", "/docs"));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    error::ContractError,
+    msg::{CreateFromTemplateMsg, CreateRoleMsg},
+    state::{ROLE_INFOS, ROLE_PATHS},
+    utils::to_cannonical_role,
+};
+use cosmwasm_std::{attr, Order, Response, StdResult};
+
+use super::{create_role::exec_create_role, Context};
+
+pub fn exec_create_from_template(
+    ctx: Context,
+    msg: CreateFromTemplateMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+    let CreateFromTemplateMsg {
+        name,
+        template,
+        substitutions,
+        description,
+    } = msg;
+
+    let template = to_cannonical_role(deps.storage, template)?;
+    let template_info = ROLE_INFOS
+        .may_load(deps.storage, &template)?
+        .ok_or_else(|| ContractError::RoleNotFound {
+            role: template.clone(),
+        })?;
+
+    if !template_info.is_template {
+        return Err(ContractError::ValidationError {
+            reason: format!("role {} is not a template", template),
+        });
+    }
+
+    // Apply each substitution, in order, to each of the template's paths. The
+    // new role's paths are permanent, regardless of the template's TTLs.
+    let paths: Vec<String> = ROLE_PATHS
+        .prefix(&template)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|r| {
+            r.map(|path| {
+                substitutions
+                    .iter()
+                    .fold(path, |path, (from, to)| path.replace(from, to))
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let role = to_cannonical_role(deps.storage, name.to_owned())?;
+    let n_paths = paths.len();
+
+    exec_create_role(
+        Context { deps, env, info },
+        CreateRoleMsg {
+            name,
+            description: description.or(template_info.description),
+            paths: Some(paths),
+            is_template: None,
+            weight: Some(template_info.weight),
+            members: None,
+            upsert: None,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "create_role_from_template"),
+        attr("role", role),
+        attr("template", template),
+        attr("n_paths", n_paths.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        msg::{CreateFromTemplateMsg, CreateRoleMsg, ExecuteMsg, QueryMsg, RoleExecuteMsg},
+        responses::RoleResponse,
+        testing::{create_role, exec, query_as, role_msg, setup, MockDeps, OPERATOR},
+    };
+
+    fn from_template(
+        deps: &mut MockDeps,
+        name: &str,
+        template: &str,
+        tenant: &str,
+    ) -> Result<(), ContractError> {
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::CreateFromTemplate(CreateFromTemplateMsg {
+                name: name.to_owned(),
+                template: template.to_owned(),
+                substitutions: vec![("{tenant}".to_owned(), tenant.to_owned())],
+                description: None,
+            })),
+        )
+        .map(|_| ())
+    }
+
+    fn role_paths(
+        deps: &MockDeps,
+        name: &str,
+    ) -> Vec<String> {
+        let role: RoleResponse = query_as(
+            deps.as_ref(),
+            QueryMsg::RoleWithPaths {
+                name: name.to_owned(),
+                limit: None,
+                cursor: None,
+            },
+        )
+        .unwrap();
+        assert!(!role.is_template);
+        role.paths.unwrap()
+    }
+
+    #[test]
+    fn creates_tenant_roles_with_substituted_paths() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Create(CreateRoleMsg {
+                is_template: Some(true),
+                ..role_msg(
+                    "tenant-admin",
+                    &["/tenants/{tenant}/billing", "/tenants/{tenant}/docs"],
+                )
+            })),
+        )
+        .unwrap();
+
+        from_template(&mut deps, "acme-admin", "tenant-admin", "acme").unwrap();
+        from_template(&mut deps, "globex-admin", "tenant-admin", "globex").unwrap();
+
+        assert_eq!(
+            role_paths(&deps, "acme-admin"),
+            vec!["/tenants/acme/billing", "/tenants/acme/docs"]
+        );
+        assert_eq!(
+            role_paths(&deps, "globex-admin"),
+            vec!["/tenants/globex/billing", "/tenants/globex/docs"]
+        );
+    }
+
+    #[test]
+    fn only_template_roles_serve_as_templates() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/tenants/{tenant}"]);
+
+        assert!(matches!(
+            from_template(&mut deps, "acme-editors", "editors", "acme"),
+            Err(ContractError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            from_template(&mut deps, "acme-editors", "missing", "acme"),
+            Err(ContractError::RoleNotFound { .. })
+        ));
+    }
+}
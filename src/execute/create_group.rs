@@ -0,0 +1,50 @@
+use crate::{
+    error::ContractError,
+    models::GroupInfo,
+    msg::CreateGroupMsg,
+    state::{GROUP_INFOS, MAX_GROUP_NAME_LEN},
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_create_group(
+    ctx: Context,
+    msg: CreateGroupMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+    let CreateGroupMsg { name, description } = msg;
+
+    // Validate group name
+    if name.is_empty() {
+        return Err(ContractError::ValidationError {
+            reason: "group name cannot be empty".to_owned(),
+        });
+    }
+    if name.len() > MAX_GROUP_NAME_LEN {
+        return Err(ContractError::ValidationError {
+            reason: format!(
+                "group name cannot be longer than {} characters",
+                MAX_GROUP_NAME_LEN
+            ),
+        });
+    }
+    if GROUP_INFOS.has(deps.storage, &name) {
+        return Err(ContractError::ValidationError {
+            reason: format!("group {} already exists", name),
+        });
+    }
+
+    GROUP_INFOS.save(
+        deps.storage,
+        &name,
+        &GroupInfo {
+            description,
+            created_at: env.block.time,
+            created_by: info.sender,
+            n_members: 0,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "create_group"), attr("group", name)]))
+}
@@ -1,50 +1,393 @@
 use crate::{
     error::ContractError,
-    models::AuthRoleInfo,
-    msg::CreateRoleMsg,
-    state::{PATH_REF_COUNTS, PATH_ROLES, ROLE_INFOS, ROLE_PATHS},
-    utils::to_cannonical_path,
+    math::add_u32,
+    models::{default_role_weight, AuthRecord, AuthRoleInfo},
+    msg::{CreateRoleMsg, GrantRoleMsg},
+    state::{CONFIG, N_ROLES, PATH_ROLES, ROLE_INFOS, ROLE_PATHS},
+    utils::{
+        increment_path_ref_count, to_acl_path, to_cannonical_role, validate_role_name, PathRef,
+    },
 };
 use cosmwasm_std::{attr, Response};
 
-use super::Context;
+use super::{grant_role::exec_grant_role, Context};
 
 pub fn exec_create_role(
     ctx: Context,
     msg: CreateRoleMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, env, info } = ctx;
+    let Context {
+        mut deps,
+        env,
+        info,
+    } = ctx;
     let CreateRoleMsg {
         name: role,
         description,
         paths,
+        is_template,
+        weight,
+        members,
+        upsert,
     } = msg;
 
-    ROLE_INFOS.update(
-        deps.storage,
-        &role,
-        |maybe_info| -> Result<_, ContractError> {
-            if maybe_info.is_some() {
-                return Err(ContractError::NotAuthorized {
-                    reason: format!("role {} already exists", role),
-                });
+    // Validate the name as given, rather than as canonicalized
+    validate_role_name(deps.storage, &role)?;
+
+    let role = to_cannonical_role(deps.storage, role)?;
+
+    let existing_info = ROLE_INFOS.may_load(deps.storage, &role)?;
+    let is_update = existing_info.is_some();
+
+    match existing_info {
+        // When upserting, an existing role keeps its creation details and
+        // principals, while any given fields replace its own.
+        Some(mut role_info) if upsert.unwrap_or(false) => {
+            if description.is_some() && description != role_info.description {
+                role_info.description = description;
+                role_info.updated_at = env.block.time;
+            }
+            if let Some(is_template) = is_template {
+                role_info.is_template = is_template;
             }
-            Ok(AuthRoleInfo {
-                created_at: env.block.time,
-                created_by: info.sender,
-                n_principals: 0,
-                description,
+            if let Some(weight) = weight {
+                role_info.weight = weight;
+            }
+            ROLE_INFOS.save(deps.storage, &role, &role_info)?;
+        },
+        Some(_) => {
+            return Err(ContractError::RoleExists {
+                role: role.to_owned(),
             })
         },
-    )?;
+        None => {
+            // Enforce the maximum number of roles, if configured
+            let n_roles = N_ROLES.load(deps.storage)?;
+            if let Some(max_roles) = CONFIG.load(deps.storage)?.max_roles {
+                if n_roles >= max_roles {
+                    return Err(ContractError::ValidationError {
+                        reason: format!("ACL cannot have more than {} roles", max_roles),
+                    });
+                }
+            }
+
+            ROLE_INFOS.save(
+                deps.storage,
+                &role,
+                &AuthRoleInfo {
+                    created_at: env.block.time,
+                    created_by: info.sender.clone(),
+                    n_principals: 0,
+                    is_template: is_template.unwrap_or(false),
+                    n_paths: 0,
+                    updated_at: env.block.time,
+                    weight: weight.unwrap_or_else(default_role_weight),
+                    description,
+                },
+            )?;
+
+            N_ROLES.save(deps.storage, &add_u32(n_roles, 1)?)?;
+        },
+    }
+
+    let mut n_paths: u32 = 0;
 
     for path in paths.unwrap_or_default().iter() {
-        let cannonical_path = to_cannonical_path(path);
+        let cannonical_path = to_acl_path(deps.storage, path)?;
+
+        // Guard against the same path being listed more than once and, when
+        // upserting, keep the role's existing links as they are.
+        if ROLE_PATHS.has(deps.storage, (&role, &cannonical_path)) {
+            continue;
+        }
+
+        increment_path_ref_count(deps.storage, &cannonical_path, PathRef::Role)?;
+        n_paths = add_u32(n_paths, 1)?;
 
-        PATH_REF_COUNTS.save(deps.storage, &cannonical_path, &0)?;
-        ROLE_PATHS.save(deps.storage, (&role, &cannonical_path), &0)?;
+        ROLE_PATHS.save(
+            deps.storage,
+            (&role, &cannonical_path),
+            &AuthRecord {
+                expires_at: None,
+                not_before: None,
+                max_uses: None,
+                condition: None,
+                metadata: None,
+                no_inherit: false,
+                require_role: None,
+                granted_by: info.sender.clone(),
+                granted_at: env.block.time,
+                granted_at_height: env.block.height,
+            },
+        )?;
         PATH_ROLES.save(deps.storage, (&cannonical_path, &role), &0)?;
     }
 
-    Ok(Response::new().add_attributes(vec![attr("action", "create_role"), attr("role", role)]))
+    if n_paths > 0 {
+        let mut role_info = ROLE_INFOS.load(deps.storage, &role)?;
+        role_info.n_paths = add_u32(role_info.n_paths, n_paths)?;
+        role_info.updated_at = env.block.time;
+        ROLE_INFOS.save(deps.storage, &role, &role_info)?;
+    }
+
+    // Grant the new role to its initial members, which validates each member
+    // and counts the role's principals.
+    let members = members.unwrap_or_default();
+    let n_members = members.len();
+
+    for (principal, ttl) in members {
+        exec_grant_role(
+            Context {
+                deps: deps.branch(),
+                env: env.clone(),
+                info: info.clone(),
+            },
+            GrantRoleMsg {
+                principal,
+                role: role.to_owned(),
+                ttl,
+                ttl_unit: None,
+                not_before: None,
+            },
+        )?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "create_role"),
+        attr("role", role),
+        attr("n_members", n_members.to_string()),
+        attr("updated", is_update.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::DepsMut;
+
+    use crate::{
+        error::ContractError,
+        models::Config,
+        msg::{CreateRoleMsg, ExecuteMsg, QueryMsg, RemoveRoleMsg, RoleExecuteMsg},
+        responses::CountsResponse,
+        state::{MAX_ROLE_NAME_LEN, ROLE_INFOS},
+        testing::{
+            create_role, exec, grant_role, is_allowed, query_as, role_msg, setup,
+            setup_with_config, OPERATOR,
+        },
+    };
+
+    fn try_create_in(
+        deps: DepsMut,
+        name: &str,
+    ) -> Result<(), ContractError> {
+        exec(
+            deps,
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Create(role_msg(name, &[]))),
+        )
+        .map(|_| ())
+    }
+
+    fn try_create(name: &str) -> Result<(), ContractError> {
+        try_create_in(setup().as_mut(), name)
+    }
+
+    #[test]
+    fn role_name_length_is_bounded() {
+        try_create(&"r".repeat(MAX_ROLE_NAME_LEN)).unwrap();
+        assert!(matches!(
+            try_create(&"r".repeat(MAX_ROLE_NAME_LEN + 1)),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn empty_role_name_is_rejected() {
+        assert!(matches!(
+            try_create(""),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn role_count_is_capped_by_max_roles() {
+        let config = Config {
+            max_roles: Some(2),
+            ..Config::default()
+        };
+        let mut deps = setup_with_config(config.clone());
+        create_role(deps.as_mut(), "a", &[]);
+        create_role(deps.as_mut(), "b", &[]);
+
+        assert!(matches!(
+            try_create_in(deps.as_mut(), "c"),
+            Err(ContractError::ValidationError { .. })
+        ));
+
+        let counts: CountsResponse = query_as(deps.as_ref(), QueryMsg::Counts {}).unwrap();
+        assert_eq!((counts.n_roles, counts.max_roles), (2, Some(2)));
+
+        // Removing a role frees its slot
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Remove(RemoveRoleMsg {
+                role: "a".to_owned(),
+                force: None,
+            })),
+        )
+        .unwrap();
+        try_create_in(deps.as_mut(), "c").unwrap();
+        assert!(matches!(
+            try_create_in(deps.as_mut(), "d"),
+            Err(ContractError::ValidationError { .. })
+        ));
+
+        // As does raising the limit
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetConfig(Config {
+                max_roles: Some(3),
+                ..config
+            }),
+        )
+        .unwrap();
+        create_role(deps.as_mut(), "d", &[]);
+    }
+
+    #[test]
+    fn path_like_names_are_rejected_unless_namespaced() {
+        for name in ["/payments", "a/b"] {
+            assert!(
+                matches!(try_create(name), Err(ContractError::ValidationError { .. })),
+                "{}",
+                name
+            );
+        }
+
+        let mut deps = setup_with_config(Config {
+            namespaced_roles: true,
+            ..Config::default()
+        });
+        try_create_in(deps.as_mut(), "/payments").unwrap();
+        try_create_in(deps.as_mut(), "a/b").unwrap();
+    }
+
+    #[test]
+    fn namespaced_names_are_validated_as_given() {
+        let mut deps = setup_with_config(Config {
+            namespaced_roles: true,
+            ..Config::default()
+        });
+        // Names canonicalizing to the root, or too long before canonicalizing
+        let too_long = format!("a{}", "/".repeat(MAX_ROLE_NAME_LEN));
+        for name in ["", "/", "//", too_long.as_str()] {
+            assert!(
+                matches!(
+                    try_create_in(deps.as_mut(), name),
+                    Err(ContractError::ValidationError { .. })
+                ),
+                "{}",
+                name
+            );
+        }
+        assert!(!ROLE_INFOS.has(&deps.storage, &"/".to_owned()));
+    }
+
+    #[test]
+    fn existing_role_is_reported_as_role_exists() {
+        let mut deps = setup();
+        try_create_in(deps.as_mut(), "editors").unwrap();
+        assert!(matches!(
+            try_create_in(deps.as_mut(), "editors"),
+            Err(ContractError::RoleExists { role }) if role == "editors"
+        ));
+    }
+
+    #[test]
+    fn role_is_created_with_paths_and_members_at_once() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Create(CreateRoleMsg {
+                members: Some(vec![
+                    ("alice".to_owned(), None),
+                    ("bob".to_owned(), Some(60)),
+                ]),
+                ..role_msg("editors", &["/docs", "/wiki"])
+            })),
+        )
+        .unwrap();
+
+        let info = ROLE_INFOS
+            .load(&deps.storage, &"editors".to_owned())
+            .unwrap();
+        assert_eq!((info.n_paths, info.n_principals), (2, 2));
+        for path in ["/docs/a", "/wiki"] {
+            assert!(is_allowed(deps.as_ref(), "alice", path));
+            assert!(is_allowed(deps.as_ref(), "bob", path));
+        }
+        assert!(!is_allowed(deps.as_ref(), "carol", "/docs"));
+    }
+
+    #[test]
+    fn invalid_members_are_rejected_when_validating_principals() {
+        let mut deps = setup_with_config(Config {
+            validate_principals: true,
+            ..Config::default()
+        });
+        let result = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Create(CreateRoleMsg {
+                members: Some(vec![
+                    ("alice".to_owned(), None),
+                    ("Not An Address".to_owned(), None),
+                ]),
+                ..role_msg("editors", &["/docs"])
+            })),
+        );
+        assert!(matches!(result, Err(ContractError::Std(_))));
+    }
+
+    #[test]
+    fn upsert_merges_paths_and_keeps_members() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        let created = ROLE_INFOS
+            .load(&deps.storage, &"editors".to_owned())
+            .unwrap();
+
+        // Without upsert, an existing role is still an error
+        assert!(matches!(
+            try_create_in(deps.as_mut(), "editors"),
+            Err(ContractError::RoleExists { .. })
+        ));
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Create(CreateRoleMsg {
+                description: Some("Edits docs and the wiki".to_owned()),
+                upsert: Some(true),
+                ..role_msg("editors", &["/docs", "/wiki"])
+            })),
+        )
+        .unwrap();
+
+        let info = ROLE_INFOS
+            .load(&deps.storage, &"editors".to_owned())
+            .unwrap();
+        assert_eq!(info.description, Some("Edits docs and the wiki".to_owned()));
+        assert_eq!(
+            (info.created_at, info.created_by),
+            (created.created_at, created.created_by)
+        );
+        assert_eq!((info.n_paths, info.n_principals), (2, 1));
+        assert!(is_allowed(deps.as_ref(), "alice", "/wiki"));
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs"));
+    }
 }
@@ -1,8 +1,10 @@
 use crate::{
     error::ContractError,
     msg::DenyMsg,
-    state::PRINCIPAL_PATH_AUTHORIZATIONS,
-    utils::{decrement_or_remove_path_ref_count, to_cannonical_path},
+    utils::{
+        decrement_or_remove_path_ref_count, ensure_control_path_kept, remove_grant,
+        saturation_attributes, to_acl_path, PathRef,
+    },
 };
 use cosmwasm_std::{attr, Response};
 
@@ -12,18 +14,146 @@ pub fn exec_deny(
     ctx: Context,
     msg: DenyMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
-    let DenyMsg { principal, path } = msg;
-    let cannonical_path = to_cannonical_path(&path);
+    let Context { deps, env, .. } = ctx;
+    let DenyMsg {
+        principal,
+        path,
+        force,
+    } = msg;
+    let cannonical_path = to_acl_path(deps.storage, &path)?;
 
-    decrement_or_remove_path_ref_count(deps.storage, &cannonical_path)?;
+    ensure_control_path_kept(
+        deps.storage,
+        &env.contract.address,
+        [&cannonical_path],
+        force,
+    )?;
 
     // Disassciate the path from the principal
-    PRINCIPAL_PATH_AUTHORIZATIONS.remove(deps.storage, (&principal, &cannonical_path));
+    let mut saturated = false;
+    if remove_grant(deps.storage, &principal, &cannonical_path)?.is_some() {
+        saturated =
+            decrement_or_remove_path_ref_count(deps.storage, &cannonical_path, PathRef::Principal)?;
+    }
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "deny"),
-        attr("path", cannonical_path),
-        attr("principal", principal),
-    ]))
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "deny"),
+            attr("path", cannonical_path),
+            attr("principal", principal),
+        ])
+        .add_attributes(saturation_attributes(saturated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exec_deny;
+    use crate::{
+        error::ContractError,
+        models::PathRefCounts,
+        msg::DenyMsg,
+        state::{N_PATHS, PATH_REF_COUNTS},
+        testing::{allow, control_path, create_role, ctx, self_operate, setup},
+    };
+
+    fn deny_msg(
+        path: &str,
+        force: Option<bool>,
+    ) -> DenyMsg {
+        DenyMsg {
+            principal: "admin".to_owned(),
+            path: path.to_owned(),
+            force,
+        }
+    }
+
+    #[test]
+    fn deny_refuses_to_remove_access_to_control_path() {
+        let mut deps = setup();
+        for path in ["/", "/acls", &control_path()] {
+            allow(deps.as_mut(), "admin", path);
+        }
+        self_operate(deps.as_mut());
+
+        for path in ["/", "/acls", &control_path()] {
+            let result = exec_deny(ctx(deps.as_mut()), deny_msg(path, None));
+            assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+        }
+    }
+
+    #[test]
+    fn deny_removes_access_to_control_path_when_forced() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "admin", "/acls");
+        self_operate(deps.as_mut());
+
+        exec_deny(ctx(deps.as_mut()), deny_msg("/acls", Some(true))).unwrap();
+    }
+
+    #[test]
+    fn deny_allows_unrelated_paths() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "admin", "/acl");
+        allow(deps.as_mut(), "admin", "/projects");
+        self_operate(deps.as_mut());
+
+        exec_deny(ctx(deps.as_mut()), deny_msg("/projects", None)).unwrap();
+        // Sharing leading characters with the control path isn't covering it
+        exec_deny(ctx(deps.as_mut()), deny_msg("/acl", None)).unwrap();
+    }
+
+    #[test]
+    fn deny_is_unguarded_when_not_self_operated() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "admin", "/acls");
+
+        exec_deny(ctx(deps.as_mut()), deny_msg("/acls", None)).unwrap();
+    }
+
+    #[test]
+    fn deny_completes_despite_zero_ref_count() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "admin", "/docs");
+        allow(deps.as_mut(), "admin", "/wiki");
+        create_role(deps.as_mut(), "editors", &["/wiki"]);
+
+        // Simulate counts broken by an earlier accounting error
+        for (path, roles) in [("/docs", 0), ("/wiki", 1)] {
+            PATH_REF_COUNTS
+                .save(
+                    &mut deps.storage,
+                    &path.to_owned(),
+                    &PathRefCounts {
+                        principals: 0,
+                        roles,
+                    },
+                )
+                .unwrap();
+        }
+
+        for path in ["/docs", "/wiki"] {
+            let response = exec_deny(ctx(deps.as_mut()), deny_msg(path, None)).unwrap();
+            assert!(
+                response.attributes.iter().any(|a| a.key == "warning"),
+                "{}",
+                path
+            );
+        }
+        assert_eq!(
+            PATH_REF_COUNTS
+                .may_load(&deps.storage, &"/docs".to_owned())
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            PATH_REF_COUNTS
+                .load(&deps.storage, &"/wiki".to_owned())
+                .unwrap(),
+            PathRefCounts {
+                principals: 0,
+                roles: 1
+            }
+        );
+        assert_eq!(N_PATHS.load(&deps.storage).unwrap(), 1);
+    }
 }
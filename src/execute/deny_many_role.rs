@@ -0,0 +1,145 @@
+use crate::{
+    error::ContractError,
+    math::sub_u32,
+    msg::DenyManyRoleMsg,
+    state::{PATH_ROLES, ROLE_INFOS, ROLE_PATHS},
+    utils::{
+        decrement_or_remove_path_ref_count, ensure_control_path_kept, saturation_attributes,
+        to_acl_path, to_cannonical_role, PathRef,
+    },
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+const MAX_PATHS: usize = 100;
+
+pub fn exec_deny_many_role(
+    ctx: Context,
+    msg: DenyManyRoleMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, .. } = ctx;
+    let DenyManyRoleMsg { role, paths, force } = msg;
+    let role = to_cannonical_role(deps.storage, role)?;
+
+    if paths.len() > MAX_PATHS {
+        return Err(ContractError::ValidationError {
+            reason: format!("cannot deny more than {} paths at once", MAX_PATHS),
+        });
+    }
+
+    let mut role_info = ROLE_INFOS
+        .may_load(deps.storage, &role)?
+        .ok_or_else(|| ContractError::RoleNotFound { role: role.clone() })?;
+
+    let mut n_removed: u32 = 0;
+    let mut saturated = false;
+
+    for path in paths.iter() {
+        let cannonical_path = to_acl_path(deps.storage, path)?;
+
+        // Skip paths the role doesn't have, including repeats of a path
+        // already removed above.
+        if !ROLE_PATHS.has(deps.storage, (&role, &cannonical_path)) {
+            continue;
+        }
+
+        ensure_control_path_kept(
+            deps.storage,
+            &env.contract.address,
+            [&cannonical_path],
+            force,
+        )?;
+
+        saturated |=
+            decrement_or_remove_path_ref_count(deps.storage, &cannonical_path, PathRef::Role)?;
+        ROLE_PATHS.remove(deps.storage, (&role, &cannonical_path));
+        PATH_ROLES.remove(deps.storage, (&cannonical_path, &role));
+        n_removed += 1;
+    }
+
+    if n_removed > 0 {
+        role_info.n_paths = sub_u32(role_info.n_paths, n_removed)?;
+        role_info.updated_at = env.block.time;
+        ROLE_INFOS.save(deps.storage, &role, &role_info)?;
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "deny_many_role"),
+            attr("role", role),
+            attr("removed", n_removed.to_string()),
+        ])
+        .add_attributes(saturation_attributes(saturated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_PATHS;
+    use crate::{
+        error::ContractError,
+        msg::{DenyManyRoleMsg, ExecuteMsg, QueryMsg, RoleExecuteMsg},
+        responses::RoleResponse,
+        testing::{create_role, exec, query_as, setup, OPERATOR},
+    };
+
+    fn deny_many_msg(paths: Vec<String>) -> ExecuteMsg {
+        ExecuteMsg::Role(RoleExecuteMsg::DenyMany(DenyManyRoleMsg {
+            role: "editors".to_owned(),
+            paths,
+            force: None,
+        }))
+    }
+
+    #[test]
+    fn removes_only_attached_paths() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs", "/wiki", "/blog"]);
+
+        let resp = exec(
+            deps.as_mut(),
+            OPERATOR,
+            deny_many_msg(vec![
+                "/docs".to_owned(),
+                "wiki".to_owned(),
+                "/news".to_owned(),
+            ]),
+        )
+        .unwrap();
+        assert!(resp
+            .attributes
+            .iter()
+            .any(|a| a.key == "removed" && a.value == "2"));
+
+        let role: RoleResponse = query_as(
+            deps.as_ref(),
+            QueryMsg::RoleWithPaths {
+                name: "editors".to_owned(),
+                limit: None,
+                cursor: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(role.n_paths, 1);
+        assert_eq!(role.paths, Some(vec!["/blog".to_owned()]));
+        assert!(!query_as::<bool>(
+            deps.as_ref(),
+            QueryMsg::PathExists {
+                path: "/docs".to_owned()
+            }
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn number_of_paths_is_capped() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+
+        let paths = (0..=MAX_PATHS)
+            .map(|i| format!("/docs/{}", i))
+            .collect();
+        let result = exec(deps.as_mut(), OPERATOR, deny_many_msg(paths));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+}
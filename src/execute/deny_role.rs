@@ -1,8 +1,12 @@
 use crate::{
     error::ContractError,
+    math::sub_u32,
     msg::DenyRoleMsg,
-    state::{PATH_ROLES, ROLE_PATHS},
-    utils::{decrement_or_remove_path_ref_count, to_cannonical_path},
+    state::{PATH_ROLES, ROLE_INFOS, ROLE_PATHS},
+    utils::{
+        decrement_or_remove_path_ref_count, ensure_control_path_kept, saturation_attributes,
+        to_acl_path, to_cannonical_role, PathRef,
+    },
 };
 use cosmwasm_std::{attr, Response};
 
@@ -12,19 +16,167 @@ pub fn exec_deny_role(
     ctx: Context,
     msg: DenyRoleMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
-    let DenyRoleMsg { role, path } = msg;
+    let Context { deps, env, .. } = ctx;
+    let DenyRoleMsg {
+        role,
+        path,
+        strict,
+        force,
+    } = msg;
+    let role = to_cannonical_role(deps.storage, role)?;
 
-    let cannonical_path = to_cannonical_path(&path);
+    let cannonical_path = to_acl_path(deps.storage, &path)?;
 
-    decrement_or_remove_path_ref_count(deps.storage, &cannonical_path)?;
+    let existed = ROLE_PATHS.has(deps.storage, (&role, &cannonical_path));
+
+    if existed {
+        ensure_control_path_kept(
+            deps.storage,
+            &env.contract.address,
+            [&cannonical_path],
+            force,
+        )?;
+    }
+
+    let mut saturated = false;
+
+    // Principals who lose access to the path through the role, for the record
+    let mut affected_principals: u32 = 0;
+
+    if existed {
+        saturated =
+            decrement_or_remove_path_ref_count(deps.storage, &cannonical_path, PathRef::Role)?;
+        if let Some(mut role_info) = ROLE_INFOS.may_load(deps.storage, &role)? {
+            affected_principals = role_info.n_principals;
+            role_info.n_paths = sub_u32(role_info.n_paths, 1)?;
+            role_info.updated_at = env.block.time;
+            ROLE_INFOS.save(deps.storage, &role, &role_info)?;
+        }
+    } else if strict.unwrap_or(true) {
+        return Err(ContractError::ValidationError {
+            reason: format!("role {} does not have path {}", role, cannonical_path),
+        });
+    }
 
     ROLE_PATHS.remove(deps.storage, (&role, &cannonical_path));
     PATH_ROLES.remove(deps.storage, (&cannonical_path, &role));
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "deny_role"),
-        attr("role", role),
-        attr("path", cannonical_path),
-    ]))
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "deny_role"),
+            attr("role", role),
+            attr("path", cannonical_path),
+            attr("existed", existed.to_string()),
+            attr("affected_principals", affected_principals.to_string()),
+        ])
+        .add_attributes(saturation_attributes(saturated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Response;
+
+    use super::exec_deny_role;
+    use crate::{
+        error::ContractError,
+        msg::{DenyRoleMsg, ExecuteMsg, RevokeRoleMsg, RoleExecuteMsg},
+        testing::{create_role, ctx, exec, grant_role, is_allowed, self_operate, setup, OPERATOR},
+    };
+
+    fn deny_role_msg(
+        path: &str,
+        force: Option<bool>,
+    ) -> DenyRoleMsg {
+        DenyRoleMsg {
+            role: "admins".to_owned(),
+            path: path.to_owned(),
+            strict: None,
+            force,
+        }
+    }
+
+    #[test]
+    fn deny_role_guards_control_path() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/acls", "/projects"]);
+        self_operate(deps.as_mut());
+
+        let result = exec_deny_role(ctx(deps.as_mut()), deny_role_msg("/acls", None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+
+        exec_deny_role(ctx(deps.as_mut()), deny_role_msg("/projects", None)).unwrap();
+        exec_deny_role(ctx(deps.as_mut()), deny_role_msg("/acls", Some(true))).unwrap();
+    }
+
+    fn attr(
+        response: &Response,
+        key: &str,
+    ) -> String {
+        response
+            .attributes
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn deny_role_reports_existing_link() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/projects"]);
+        grant_role(deps.as_mut(), "alice", "admins");
+
+        let response =
+            exec_deny_role(ctx(deps.as_mut()), deny_role_msg("/projects", None)).unwrap();
+        assert_eq!(attr(&response, "existed"), "true");
+        assert_eq!(attr(&response, "affected_principals"), "1");
+        assert!(!is_allowed(deps.as_ref(), "alice", "/projects"));
+    }
+
+    #[test]
+    fn deny_role_rejects_missing_link_unless_lax() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/projects"]);
+
+        let result = exec_deny_role(ctx(deps.as_mut()), deny_role_msg("/docs", None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+
+        let response = exec_deny_role(
+            ctx(deps.as_mut()),
+            DenyRoleMsg {
+                strict: Some(false),
+                ..deny_role_msg("/docs", None)
+            },
+        )
+        .unwrap();
+        assert_eq!(attr(&response, "existed"), "false");
+        assert_eq!(attr(&response, "affected_principals"), "0");
+    }
+
+    #[test]
+    fn affected_principals_match_role_membership() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/projects", "/docs"]);
+        for principal in ["alice", "bob", "carol"] {
+            grant_role(deps.as_mut(), principal, "admins");
+        }
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Revoke(RevokeRoleMsg {
+                principal: "carol".to_owned(),
+                role: "admins".to_owned(),
+                force: None,
+            })),
+        )
+        .unwrap();
+
+        let response =
+            exec_deny_role(ctx(deps.as_mut()), deny_role_msg("/projects", None)).unwrap();
+        assert_eq!(attr(&response, "affected_principals"), "2");
+        for principal in ["alice", "bob"] {
+            assert!(!is_allowed(deps.as_ref(), principal, "/projects"));
+            assert!(is_allowed(deps.as_ref(), principal, "/docs"));
+        }
+    }
 }
@@ -0,0 +1,181 @@
+use crate::{
+    error::ContractError,
+    models::DenyRecord,
+    msg::ForbidMsg,
+    state::{PRINCIPAL_PATH_DENIALS, ROLE_INFOS},
+    utils::{
+        ensure_control_path_kept, ensure_valid_principal, expiry_attributes, expiry_from_ttl,
+        save_role_denial, to_acl_path, to_cannonical_role,
+    },
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_forbid(
+    ctx: Context,
+    msg: ForbidMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, .. } = ctx;
+    let ForbidMsg {
+        principal,
+        path,
+        ttl,
+        role,
+        recursive,
+        force,
+    } = msg;
+
+    ensure_valid_principal(deps.storage, deps.api, &principal)?;
+
+    let denial = DenyRecord {
+        expires_at: expiry_from_ttl(env.block.time, ttl)?,
+        recursive: recursive.unwrap_or(false),
+    };
+
+    let cannonical_path = to_acl_path(deps.storage, &path)?;
+
+    ensure_control_path_kept(
+        deps.storage,
+        &env.contract.address,
+        [&cannonical_path],
+        force,
+    )?;
+
+    // A denial via a role is an exception for this principal alone, unlike
+    // removing the path from the role, which affects all of its principals.
+    match &role {
+        Some(role) => {
+            let role = to_cannonical_role(deps.storage, role.to_owned())?;
+            if !ROLE_INFOS.has(deps.storage, &role) {
+                return Err(ContractError::RoleNotFound { role });
+            }
+            save_role_denial(deps.storage, &principal, &cannonical_path, &role, &denial)?;
+        },
+        None => {
+            PRINCIPAL_PATH_DENIALS.save(deps.storage, (&principal, &cannonical_path), &denial)?
+        },
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "forbid"),
+            attr("principal", principal),
+            attr("path", cannonical_path),
+            attr("role", role.unwrap_or_default()),
+        ])
+        .add_attributes(expiry_attributes(denial.expires_at)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exec_forbid;
+    use crate::{
+        error::ContractError,
+        models::{Config, Precedence},
+        msg::{ExecuteMsg, ForbidMsg},
+        testing::{
+            allow, control_path, create_role, ctx, exec, grant_role, is_allowed, self_operate,
+            setup, setup_with_config, OPERATOR,
+        },
+    };
+
+    fn forbid_msg(
+        path: &str,
+        role: Option<&str>,
+        force: Option<bool>,
+    ) -> ForbidMsg {
+        ForbidMsg {
+            principal: "admin".to_owned(),
+            path: path.to_owned(),
+            ttl: None,
+            role: role.map(|r| r.to_owned()),
+            recursive: None,
+            force,
+        }
+    }
+
+    #[test]
+    fn forbid_refuses_to_block_control_path() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/acls"]);
+        self_operate(deps.as_mut());
+
+        for path in ["/", "/acls", &control_path()] {
+            let result = exec_forbid(ctx(deps.as_mut()), forbid_msg(path, None, None));
+            assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+            let result = exec_forbid(ctx(deps.as_mut()), forbid_msg(path, Some("admins"), None));
+            assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+        }
+    }
+
+    #[test]
+    fn forbid_blocks_control_path_when_forced() {
+        let mut deps = setup();
+        self_operate(deps.as_mut());
+
+        exec_forbid(ctx(deps.as_mut()), forbid_msg("/acls", None, Some(true))).unwrap();
+    }
+
+    #[test]
+    fn forbid_allows_unrelated_paths() {
+        let mut deps = setup();
+        self_operate(deps.as_mut());
+
+        exec_forbid(ctx(deps.as_mut()), forbid_msg("/projects", None, None)).unwrap();
+    }
+
+    #[test]
+    fn role_denial_applies_to_one_principal() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        grant_role(deps.as_mut(), "bob", "editors");
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Forbid(ForbidMsg {
+                principal: "alice".to_owned(),
+                ..forbid_msg("/docs/secret", Some("editors"), None)
+            }),
+        )
+        .unwrap();
+
+        assert!(!is_allowed(deps.as_ref(), "alice", "/docs/secret"));
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs/public"));
+        assert!(is_allowed(deps.as_ref(), "bob", "/docs/secret"));
+
+        // Other grants to the path stay in effect
+        allow(deps.as_mut(), "alice", "/docs/secret");
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs/secret"));
+    }
+
+    #[test]
+    fn recursive_denial_blocks_subtree_despite_specific_role() {
+        let mut deps = setup_with_config(Config {
+            precedence: Precedence::MostSpecific,
+            ..Config::default()
+        });
+        create_role(deps.as_mut(), "admins", &["/admin/config"]);
+        grant_role(deps.as_mut(), "alice", "admins");
+        grant_role(deps.as_mut(), "bob", "admins");
+
+        for (principal, recursive) in [("alice", true), ("bob", false)] {
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Forbid(ForbidMsg {
+                    principal: principal.to_owned(),
+                    recursive: Some(recursive),
+                    ..forbid_msg("/admin", None, None)
+                }),
+            )
+            .unwrap();
+        }
+
+        assert!(!is_allowed(deps.as_ref(), "alice", "/admin/config"));
+        assert!(!is_allowed(deps.as_ref(), "alice", "/admin/config/db"));
+        // Without recursion, the more specific grant wins
+        assert!(is_allowed(deps.as_ref(), "bob", "/admin/config"));
+    }
+}
@@ -3,7 +3,11 @@ use crate::{
     math::add_u32,
     models::AuthRecord,
     msg::GrantRoleMsg,
-    state::{PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS},
+    state::{CONFIG, GROUP_INFOS, PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS, ROLE_PRINCIPALS},
+    utils::{
+        delay_not_before, ensure_valid_principal, expiry_attributes, expiry_from_ttl,
+        increment_principal_ref_count, resolve_ttl, to_cannonical_role, GROUP_PRINCIPAL_PREFIX,
+    },
 };
 use cosmwasm_std::{attr, Response};
 
@@ -13,43 +17,154 @@ pub fn exec_grant_role(
     ctx: Context,
     msg: GrantRoleMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, env, .. } = ctx;
+    grant_role(ctx, msg).map(|(response, _)| response)
+}
+
+/// Grant a role, also returning whether any count saturated, for callers
+/// granting as one step of a larger change. Granting only ever adds to counts,
+/// so none does yet.
+pub fn grant_role(
+    ctx: Context,
+    msg: GrantRoleMsg,
+) -> Result<(Response, bool), ContractError> {
+    let Context { deps, env, info } = ctx;
     let GrantRoleMsg {
         principal,
         role,
         ttl,
+        ttl_unit,
+        not_before,
     } = msg;
+    let ttl = resolve_ttl(ttl, ttl_unit)?;
+
+    let role = to_cannonical_role(deps.storage, role)?;
+
+    // A group's principal may be granted roles on behalf of its members
+    match principal.strip_prefix(GROUP_PRINCIPAL_PREFIX) {
+        Some(group) => {
+            if !GROUP_INFOS.has(deps.storage, &group.to_owned()) {
+                return Err(ContractError::GroupNotFound {
+                    group: group.to_owned(),
+                });
+            }
+        },
+        None => ensure_valid_principal(deps.storage, deps.api, &principal)?,
+    }
+
+    let config = CONFIG.load(deps.storage)?;
 
     let auth = AuthRecord {
-        expires_at: ttl.and_then(|n| Some(env.block.time.plus_seconds(n.into()))),
+        expires_at: expiry_from_ttl(env.block.time, ttl)?,
+        not_before: delay_not_before(&config, env.block.time, not_before),
+        max_uses: None,
+        condition: None,
+        metadata: None,
+        no_inherit: false,
+        require_role: None,
+        granted_by: info.sender,
+        granted_at: env.block.time,
+        granted_at_height: env.block.height,
     };
 
+    // Only a new grant adds to the number of principals, not an updated one
+    let is_new_grant = !PRINCIPAL_ROLE_AUTHORIZATIONS.has(deps.storage, (&principal, &role));
+    let max_principals = config.max_principals_per_role;
+
     ROLE_INFOS.update(
         deps.storage,
         &role,
         |maybe_info| -> Result<_, ContractError> {
             if let Some(mut info) = maybe_info {
-                info.n_principals = add_u32(info.n_principals, 1)?;
+                if is_new_grant {
+                    // Enforce the maximum number of principals, if configured
+                    if let Some(max) = max_principals {
+                        if info.n_principals >= max {
+                            return Err(ContractError::ValidationError {
+                                reason: format!(
+                                    "role {} cannot be granted to more than {} principals",
+                                    role, max
+                                ),
+                            });
+                        }
+                    }
+                    info.n_principals = add_u32(info.n_principals, 1)?;
+                }
                 Ok(info)
             } else {
-                Err(ContractError::NotAuthorized {
-                    reason: format!("role {} does not exist", role),
+                Err(ContractError::RoleNotFound {
+                    role: role.to_owned(),
                 })
             }
         },
     )?;
 
+    if is_new_grant {
+        increment_principal_ref_count(deps.storage, &principal)?;
+    }
+
     PRINCIPAL_ROLE_AUTHORIZATIONS.save(deps.storage, (&principal, &role), &auth)?;
+    ROLE_PRINCIPALS.save(deps.storage, (&role, &principal), &0)?;
+
+    let response = Response::new()
+        .add_attributes(vec![
+            attr("action", "grant_role"),
+            attr("principal", principal),
+            attr("role", role),
+        ])
+        .add_attributes(expiry_attributes(auth.expires_at));
+
+    Ok((response, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        models::Config,
+        msg::{ExecuteMsg, RevokeRoleMsg, RoleExecuteMsg},
+        testing::{create_role, exec, grant_role_msg, setup_with_config, MockDeps, OPERATOR},
+    };
+
+    fn try_grant(
+        deps: &mut MockDeps,
+        principal: &str,
+    ) -> Result<(), ContractError> {
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(grant_role_msg(principal, "editors"))),
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn role_membership_is_capped() {
+        let mut deps = setup_with_config(Config {
+            max_principals_per_role: Some(2),
+            ..Config::default()
+        });
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        try_grant(&mut deps, "alice").unwrap();
+        try_grant(&mut deps, "bob").unwrap();
+
+        assert!(matches!(
+            try_grant(&mut deps, "carol"),
+            Err(ContractError::ValidationError { .. })
+        ));
+        // Renewing an existing member's grant doesn't add to the count
+        try_grant(&mut deps, "alice").unwrap();
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "grant_role"),
-        attr("principal", principal),
-        attr("role", role),
-        attr(
-            "expires_at",
-            auth.expires_at
-                .and_then(|t| Some(t.to_string()))
-                .unwrap_or(String::from("null")),
-        ),
-    ]))
+        // Revoking frees a slot
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Revoke(RevokeRoleMsg {
+                principal: "bob".to_owned(),
+                role: "editors".to_owned(),
+                force: None,
+            })),
+        )
+        .unwrap();
+        try_grant(&mut deps, "carol").unwrap();
+    }
 }
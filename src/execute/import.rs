@@ -0,0 +1,596 @@
+use crate::{
+    error::ContractError,
+    math::add_u32,
+    models::{AuthRoleInfo, GroupInfo},
+    msg::{ExportEntry, GroupMemberMsg, ImportMsg, SetAliasMsg},
+    state::{
+        ALIASES, GROUP_INFOS, IMPORT_IN_PROGRESS, N_ROLES, PATH_REF_COUNTS, PATH_ROLES,
+        PRINCIPAL_PATH_DENIALS, PRINCIPAL_ROLE_AUTHORIZATIONS, PRINCIPAL_ROLE_PATH_DENIALS,
+        ROLE_INFOS, ROLE_PATHS, ROLE_PRINCIPALS,
+    },
+    utils::{
+        ensure_valid_max_uses, increment_path_ref_count, increment_principal_ref_count, save_grant,
+        save_role_denial, to_acl_path, to_cannonical_role, validate_role_name, PathRef,
+    },
+};
+use cosmwasm_std::{attr, Order, Response, Storage};
+
+use super::{add_to_group::exec_add_to_group, set_alias::exec_set_alias, Context};
+
+const MAX_IMPORT_ENTRIES: usize = 200;
+
+pub fn exec_import(
+    ctx: Context,
+    msg: ImportMsg,
+) -> Result<Response, ContractError> {
+    let Context {
+        mut deps,
+        env,
+        info,
+    } = ctx;
+    let ImportMsg {
+        entries,
+        merge,
+        done,
+    } = msg;
+
+    if entries.len() > MAX_IMPORT_ENTRIES {
+        return Err(ContractError::ValidationError {
+            reason: format!(
+                "cannot import more than {} entries at once",
+                MAX_IMPORT_ENTRIES
+            ),
+        });
+    }
+
+    // Only import into an empty ACL unless merging or continuing an import
+    let is_continuation = IMPORT_IN_PROGRESS.may_load(deps.storage)?.unwrap_or(false);
+    if !(merge.unwrap_or(false) || is_continuation || is_empty(deps.storage)?) {
+        return Err(ContractError::ValidationError {
+            reason: "cannot import into a non-empty ACL without merge".to_owned(),
+        });
+    }
+
+    let n_entries = entries.len();
+
+    for entry in entries {
+        match entry {
+            ExportEntry::Role { name, info } => {
+                // Validate the name as given, rather than as canonicalized
+                validate_role_name(deps.storage, &name)?;
+                let name = to_cannonical_role(deps.storage, name)?;
+                // Existing roles keep their principal and path counts, which
+                // are maintained as principal role grants and role paths are
+                // imported.
+                let (n_principals, n_paths) = match ROLE_INFOS.may_load(deps.storage, &name)? {
+                    Some(existing) => (existing.n_principals, existing.n_paths),
+                    None => {
+                        N_ROLES.update(deps.storage, |n| add_u32(n, 1))?;
+                        (0, 0)
+                    },
+                };
+                ROLE_INFOS.save(
+                    deps.storage,
+                    &name,
+                    &AuthRoleInfo {
+                        n_principals,
+                        n_paths,
+                        // Roles exported before updates were tracked
+                        updated_at: info.updated_at.max(info.created_at),
+                        ..info
+                    },
+                )?;
+            },
+            ExportEntry::RolePath { role, path, record } => {
+                ensure_role_exists(deps.storage, &role)?;
+                let path = to_acl_path(deps.storage, &path)?;
+                if !ROLE_PATHS.has(deps.storage, (&role, &path)) {
+                    increment_path_ref_count(deps.storage, &path, PathRef::Role)?;
+                    let mut info = ROLE_INFOS.load(deps.storage, &role)?;
+                    info.n_paths = add_u32(info.n_paths, 1)?;
+                    ROLE_INFOS.save(deps.storage, &role, &info)?;
+                }
+                ROLE_PATHS.save(deps.storage, (&role, &path), &record)?;
+                PATH_ROLES.save(deps.storage, (&path, &role), &0)?;
+            },
+            ExportEntry::Group { name, info } => {
+                // Existing groups keep their number of members, which is
+                // maintained as members are imported.
+                let n_members = GROUP_INFOS
+                    .may_load(deps.storage, &name)?
+                    .map(|existing| existing.n_members)
+                    .unwrap_or(0);
+                GROUP_INFOS.save(deps.storage, &name, &GroupInfo { n_members, ..info })?;
+            },
+            ExportEntry::GroupMember { group, principal } => {
+                // Adding maintains the group's number of members
+                exec_add_to_group(
+                    Context {
+                        deps: deps.branch(),
+                        env: env.clone(),
+                        info: info.clone(),
+                    },
+                    GroupMemberMsg { group, principal },
+                )?;
+            },
+            ExportEntry::PrincipalPath {
+                principal,
+                path,
+                record,
+            } => {
+                ensure_valid_max_uses(record.max_uses)?;
+                let path = to_acl_path(deps.storage, &path)?;
+                if save_grant(deps.storage, &principal, &path, &record)?.is_none() {
+                    increment_path_ref_count(deps.storage, &path, PathRef::Principal)?;
+                }
+            },
+            ExportEntry::PrincipalRole {
+                principal,
+                role,
+                record,
+            } => {
+                ensure_role_exists(deps.storage, &role)?;
+                if !PRINCIPAL_ROLE_AUTHORIZATIONS.has(deps.storage, (&principal, &role)) {
+                    let mut info = ROLE_INFOS.load(deps.storage, &role)?;
+                    info.n_principals = add_u32(info.n_principals, 1)?;
+                    ROLE_INFOS.save(deps.storage, &role, &info)?;
+                    increment_principal_ref_count(deps.storage, &principal)?;
+                }
+                PRINCIPAL_ROLE_AUTHORIZATIONS.save(deps.storage, (&principal, &role), &record)?;
+                ROLE_PRINCIPALS.save(deps.storage, (&role, &principal), &0)?;
+            },
+            ExportEntry::Denial {
+                principal,
+                path,
+                record,
+            } => {
+                let path = to_acl_path(deps.storage, &path)?;
+                PRINCIPAL_PATH_DENIALS.save(deps.storage, (&principal, &path), &record)?;
+            },
+            ExportEntry::RoleDenial {
+                principal,
+                path,
+                role,
+                record,
+            } => {
+                ensure_role_exists(deps.storage, &role)?;
+                let path = to_acl_path(deps.storage, &path)?;
+                save_role_denial(deps.storage, &principal, &path, &role, &record)?;
+            },
+            ExportEntry::Alias { addr, principal } => {
+                // Setting validates the alias and maintains the reverse index
+                exec_set_alias(
+                    Context {
+                        deps: deps.branch(),
+                        env: env.clone(),
+                        info: info.clone(),
+                    },
+                    SetAliasMsg { addr, principal },
+                )?;
+            },
+        }
+    }
+
+    IMPORT_IN_PROGRESS.save(deps.storage, &!done.unwrap_or(true))?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "import"),
+        attr("n_entries", n_entries.to_string()),
+    ]))
+}
+
+fn ensure_role_exists(
+    store: &dyn Storage,
+    role: &String,
+) -> Result<(), ContractError> {
+    if !ROLE_INFOS.has(store, role) {
+        return Err(ContractError::RoleNotFound {
+            role: role.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// An ACL is empty if it has no roles, paths, role grants, denials, role
+/// denials, groups, or aliases.
+fn is_empty(store: &dyn Storage) -> Result<bool, ContractError> {
+    Ok(N_ROLES.load(store)? == 0
+        && PATH_REF_COUNTS
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .is_none()
+        && PRINCIPAL_ROLE_AUTHORIZATIONS
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .is_none()
+        && PRINCIPAL_PATH_DENIALS
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .is_none()
+        && PRINCIPAL_ROLE_PATH_DENIALS
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .is_none()
+        && GROUP_INFOS
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .is_none()
+        && ALIASES
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::mock_env, Addr};
+
+    use crate::{
+        error::ContractError,
+        models::{AuthRecord, AuthRoleInfo, Config, PathRefCounts},
+        msg::{
+            CreateGroupMsg, ExecuteMsg, ExportEntry, ForbidMsg, GroupExecuteMsg, GroupMemberMsg,
+            ImportMsg, QueryMsg, SetAliasMsg,
+        },
+        responses::{AclResponse, ExportResponse, RoleResponse},
+        state::PATH_REF_COUNTS,
+        testing::{
+            allow, create_role, exec, grant_role, is_allowed_params, query_as, setup,
+            setup_with_config, MockDeps, OPERATOR,
+        },
+    };
+
+    /// Export every entry, a few at a time to exercise paging.
+    fn export_all(deps: &MockDeps) -> Vec<ExportEntry> {
+        let mut entries: Vec<ExportEntry> = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page: ExportResponse = query_as(
+                deps.as_ref(),
+                QueryMsg::Export {
+                    cursor,
+                    limit: Some(2),
+                    viewer: None,
+                },
+            )
+            .unwrap();
+            entries.extend(page.entries);
+            match page.cursor {
+                Some(next) => cursor = Some(next),
+                None => return entries,
+            }
+        }
+    }
+
+    fn import_all(entries: Vec<ExportEntry>) -> MockDeps {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Import(ImportMsg {
+                entries,
+                merge: None,
+                done: Some(true),
+            }),
+        )
+        .unwrap();
+        deps
+    }
+
+    fn is_allowed(
+        deps: &MockDeps,
+        principal: &str,
+        path: &str,
+    ) -> bool {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::IsAllowed(is_allowed_params(principal, &[path])),
+        )
+        .unwrap()
+    }
+
+    fn populate(deps: &mut MockDeps) {
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        allow(deps.as_mut(), "bob", "/projects");
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Forbid(ForbidMsg {
+                principal: "bob".to_owned(),
+                path: "/projects/secret".to_owned(),
+                ttl: None,
+                role: None,
+                recursive: None,
+                force: None,
+            }),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Forbid(ForbidMsg {
+                principal: "alice".to_owned(),
+                path: "/docs/private".to_owned(),
+                ttl: None,
+                role: Some("editors".to_owned()),
+                recursive: None,
+                force: None,
+            }),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Group(GroupExecuteMsg::Create(CreateGroupMsg {
+                name: "team".to_owned(),
+                description: Some("The team".to_owned()),
+            })),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Group(GroupExecuteMsg::Add(GroupMemberMsg {
+                group: "team".to_owned(),
+                principal: "carol".to_owned(),
+            })),
+        )
+        .unwrap();
+        grant_role(deps.as_mut(), "group:team", "editors");
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetAlias(SetAliasMsg {
+                addr: "carolwallet".to_owned(),
+                principal: "carol".to_owned(),
+            }),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let mut source = setup();
+        populate(&mut source);
+
+        // Carol has access only through her group, via an alias as well
+        assert!(is_allowed(&source, "carolwallet", "/docs"));
+
+        let entries = export_all(&source);
+        let target = import_all(entries.clone());
+
+        assert_eq!(export_all(&target), entries);
+        for (principal, path) in [
+            ("alice", "/docs"),
+            ("alice", "/docs/private"),
+            ("carol", "/docs/private"),
+            ("bob", "/projects"),
+            ("bob", "/projects/secret"),
+            ("carol", "/docs"),
+            ("carolwallet", "/docs"),
+            ("dave", "/docs"),
+        ] {
+            assert_eq!(
+                is_allowed(&target, principal, path),
+                is_allowed(&source, principal, path),
+                "{} to {}",
+                principal,
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn export_includes_groups_and_aliases() {
+        let mut source = setup();
+        populate(&mut source);
+
+        let entries = export_all(&source);
+        assert!(entries
+            .iter()
+            .any(|e| matches!(e, ExportEntry::Group { name, .. } if name == "team")));
+        assert!(entries.iter().any(|e| matches!(
+            e,
+            ExportEntry::GroupMember { group, principal } if group == "team" && principal == "carol"
+        )));
+        assert!(entries.iter().any(|e| matches!(
+            e,
+            ExportEntry::Alias { addr, principal } if addr == "carolwallet" && principal == "carol"
+        )));
+    }
+
+    #[test]
+    fn export_includes_role_denials() {
+        let mut source = setup();
+        populate(&mut source);
+
+        // Alice alone loses the path via the role
+        assert!(!is_allowed(&source, "alice", "/docs/private"));
+        assert!(is_allowed(&source, "carol", "/docs/private"));
+
+        let entries = export_all(&source);
+        assert!(entries.iter().any(|e| matches!(
+            e,
+            ExportEntry::RoleDenial { principal, path, role, .. }
+                if principal == "alice" && path == "/docs/private" && role == "editors"
+        )));
+
+        let target = import_all(entries);
+        assert!(!is_allowed(&target, "alice", "/docs/private"));
+        assert!(is_allowed(&target, "carol", "/docs/private"));
+    }
+
+    fn record() -> AuthRecord {
+        AuthRecord {
+            expires_at: None,
+            not_before: None,
+            max_uses: None,
+            condition: None,
+            metadata: None,
+            no_inherit: false,
+            require_role: None,
+            granted_by: Addr::unchecked(OPERATOR),
+            granted_at: mock_env().block.time,
+            granted_at_height: mock_env().block.height,
+        }
+    }
+
+    fn import(
+        deps: &mut MockDeps,
+        entries: Vec<ExportEntry>,
+        done: Option<bool>,
+    ) -> Result<(), ContractError> {
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Import(ImportMsg {
+                entries,
+                merge: None,
+                done,
+            }),
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn import_rebuilds_counts_from_hand_built_snapshot() {
+        let mut deps = setup();
+        let entries = vec![
+            ExportEntry::Role {
+                name: "editors".to_owned(),
+                info: AuthRoleInfo {
+                    description: None,
+                    created_at: mock_env().block.time,
+                    created_by: Addr::unchecked(OPERATOR),
+                    // Stale counts in the snapshot are rebuilt, not trusted
+                    n_principals: 99,
+                    is_template: false,
+                    n_paths: 99,
+                    updated_at: mock_env().block.time,
+                    weight: 1,
+                },
+            },
+            ExportEntry::RolePath {
+                role: "editors".to_owned(),
+                path: "/docs".to_owned(),
+                record: record(),
+            },
+            ExportEntry::PrincipalPath {
+                principal: "bob".to_owned(),
+                path: "/docs".to_owned(),
+                record: record(),
+            },
+            ExportEntry::PrincipalPath {
+                principal: "bob".to_owned(),
+                path: "/projects".to_owned(),
+                record: record(),
+            },
+            ExportEntry::PrincipalRole {
+                principal: "alice".to_owned(),
+                role: "editors".to_owned(),
+                record: record(),
+            },
+        ];
+
+        // Without done, the chunk is taken to be the whole import
+        import(&mut deps, entries.clone(), None).unwrap();
+
+        assert!(is_allowed(&deps, "alice", "/docs/guide"));
+        assert!(is_allowed(&deps, "bob", "/projects"));
+        assert!(!is_allowed(&deps, "alice", "/projects"));
+
+        let acl: AclResponse = query_as(deps.as_ref(), QueryMsg::Acl {}).unwrap();
+        assert_eq!((acl.n_roles, acl.n_paths, acl.n_principals), (1, 2, 2));
+
+        let role: RoleResponse =
+            query_as(deps.as_ref(), QueryMsg::Role("editors".to_owned())).unwrap();
+        assert_eq!((role.n_principals, role.n_paths), (1, 1));
+
+        assert_eq!(
+            PATH_REF_COUNTS
+                .load(deps.as_ref().storage, &"/docs".to_owned())
+                .unwrap(),
+            PathRefCounts {
+                principals: 1,
+                roles: 1,
+            }
+        );
+
+        // The import is over, so the now non-empty ACL takes no more without merge
+        assert!(matches!(
+            import(&mut deps, entries, None),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn chunked_import_continues_until_done() {
+        let mut deps = setup();
+        let grant = |principal: &str| ExportEntry::PrincipalPath {
+            principal: principal.to_owned(),
+            path: "/docs".to_owned(),
+            record: record(),
+        };
+
+        import(&mut deps, vec![grant("alice")], Some(false)).unwrap();
+        import(&mut deps, vec![grant("bob")], Some(true)).unwrap();
+        assert!(matches!(
+            import(&mut deps, vec![grant("carol")], None),
+            Err(ContractError::ValidationError { .. })
+        ));
+
+        assert!(is_allowed(&deps, "alice", "/docs"));
+        assert!(is_allowed(&deps, "bob", "/docs"));
+        assert!(!is_allowed(&deps, "carol", "/docs"));
+    }
+
+    #[test]
+    fn import_rejects_namespaced_role_named_root() {
+        let mut deps = setup_with_config(Config {
+            namespaced_roles: true,
+            ..Config::default()
+        });
+        let role = |name: &str| ExportEntry::Role {
+            name: name.to_owned(),
+            info: AuthRoleInfo {
+                description: None,
+                created_at: mock_env().block.time,
+                created_by: Addr::unchecked(OPERATOR),
+                n_principals: 0,
+                is_template: false,
+                n_paths: 0,
+                updated_at: mock_env().block.time,
+                weight: 1,
+            },
+        };
+
+        for name in ["/", "//"] {
+            assert!(matches!(
+                import(&mut deps, vec![role(name)], None),
+                Err(ContractError::ValidationError { .. })
+            ));
+        }
+        import(&mut deps, vec![role("/payments")], None).unwrap();
+    }
+
+    #[test]
+    fn import_rejects_grant_with_no_uses() {
+        let mut deps = setup();
+        let grant = |max_uses: Option<u32>| ExportEntry::PrincipalPath {
+            principal: "alice".to_owned(),
+            path: "/docs".to_owned(),
+            record: AuthRecord {
+                max_uses,
+                ..record()
+            },
+        };
+
+        assert!(matches!(
+            import(&mut deps, vec![grant(Some(0))], None),
+            Err(ContractError::ValidationError { .. })
+        ));
+        import(&mut deps, vec![grant(Some(1))], None).unwrap();
+        assert!(is_allowed(&deps, "alice", "/docs"));
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    error::ContractError,
+    msg::{AuthResource, MakePermanentMsg},
+    state::{PRINCIPAL_PATH_AUTHORIZATIONS, PRINCIPAL_ROLE_AUTHORIZATIONS},
+    utils::{to_acl_path, to_cannonical_role},
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_make_permanent(
+    ctx: Context,
+    msg: MakePermanentMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, .. } = ctx;
+    let MakePermanentMsg {
+        principal,
+        resource,
+    } = msg;
+
+    let (map, target, kind) = match resource {
+        AuthResource::Path(path) => (
+            PRINCIPAL_PATH_AUTHORIZATIONS,
+            to_acl_path(deps.storage, &path)?,
+            "path",
+        ),
+        AuthResource::Role(role) => (
+            PRINCIPAL_ROLE_AUTHORIZATIONS,
+            to_cannonical_role(deps.storage, role)?,
+            "role",
+        ),
+    };
+
+    let mut auth = map
+        .may_load(deps.storage, (&principal, &target))?
+        .ok_or_else(|| ContractError::GrantNotFound {
+            principal: principal.to_owned(),
+            target: target.to_owned(),
+        })?;
+
+    // An expired grant must be reissued rather than revived
+    if auth
+        .expires_at
+        .map(|t| env.block.time >= t)
+        .unwrap_or(false)
+    {
+        return Err(ContractError::Expired { principal, target });
+    }
+
+    auth.expires_at = None;
+    map.save(deps.storage, (&principal, &target), &auth)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "make_permanent"),
+        attr("principal", principal),
+        attr(kind, target),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{from_json, testing::mock_env};
+
+    use super::exec_make_permanent;
+    use crate::{
+        contract::query,
+        error::ContractError,
+        msg::{
+            AllowMsg, AuthResource, ExecuteMsg, GrantRoleMsg, MakePermanentMsg, QueryMsg,
+            RoleExecuteMsg,
+        },
+        state::{PRINCIPAL_PATH_AUTHORIZATIONS, PRINCIPAL_ROLE_AUTHORIZATIONS},
+        testing::{
+            allow_msg, create_role, ctx, exec, grant_role_msg, is_allowed_params, setup, OPERATOR,
+        },
+    };
+
+    fn make_permanent_msg(resource: AuthResource) -> MakePermanentMsg {
+        MakePermanentMsg {
+            principal: "alice".to_owned(),
+            resource,
+        }
+    }
+
+    #[test]
+    fn failures_are_reported_as_structured_errors() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(60),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(60),
+                ..grant_role_msg("alice", "editors")
+            })),
+        )
+        .unwrap();
+
+        for resource in [
+            AuthResource::Path("/wiki".to_owned()),
+            AuthResource::Role("viewers".to_owned()),
+        ] {
+            let result = exec_make_permanent(ctx(deps.as_mut()), make_permanent_msg(resource));
+            assert!(matches!(
+                result,
+                Err(ContractError::GrantNotFound { principal, .. }) if principal == "alice"
+            ));
+        }
+
+        for (resource, expected_target) in [
+            (AuthResource::Path("/docs".to_owned()), "/docs"),
+            (AuthResource::Role("editors".to_owned()), "editors"),
+        ] {
+            let mut later = ctx(deps.as_mut());
+            later.env.block.time = mock_env().block.time.plus_seconds(60);
+            let result = exec_make_permanent(later, make_permanent_msg(resource));
+            assert!(matches!(
+                result,
+                Err(ContractError::Expired { principal, target })
+                    if principal == "alice" && target == expected_target
+            ));
+        }
+    }
+
+    #[test]
+    fn time_limited_grants_become_permanent() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/wiki"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(60),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(60),
+                ..grant_role_msg("alice", "editors")
+            })),
+        )
+        .unwrap();
+
+        for resource in [
+            AuthResource::Path("/docs".to_owned()),
+            AuthResource::Role("editors".to_owned()),
+        ] {
+            exec_make_permanent(ctx(deps.as_mut()), make_permanent_msg(resource)).unwrap();
+        }
+
+        let alice = "alice".to_owned();
+        let direct = PRINCIPAL_PATH_AUTHORIZATIONS
+            .load(&deps.storage, (&alice, &"/docs".to_owned()))
+            .unwrap();
+        let role = PRINCIPAL_ROLE_AUTHORIZATIONS
+            .load(&deps.storage, (&alice, &"editors".to_owned()))
+            .unwrap();
+        assert_eq!((direct.expires_at, role.expires_at), (None, None));
+
+        // Both grants outlive their original TTL
+        let mut later = mock_env();
+        later.block.time = later.block.time.plus_seconds(120);
+        for path in ["/docs", "/wiki"] {
+            let allowed: bool = from_json(
+                query(
+                    deps.as_ref(),
+                    later.clone(),
+                    QueryMsg::IsAllowed(is_allowed_params("alice", &[path])),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert!(allowed, "{}", path);
+        }
+    }
+}
@@ -1,12 +1,35 @@
+pub mod add_to_group;
 pub mod allow;
 pub mod allow_role;
+pub mod clone_role;
+pub mod consume_access;
+pub mod create_from_template;
+pub mod create_group;
 pub mod create_role;
 pub mod deny;
+pub mod deny_many_role;
 pub mod deny_role;
+pub mod forbid;
 pub mod grant_role;
+pub mod import;
+pub mod make_permanent;
+pub mod prune_expired;
+pub mod remove_alias;
+pub mod remove_from_group;
 pub mod remove_role;
+pub mod remove_sub_operator;
+pub mod remove_superuser;
+pub mod remove_viewer;
+pub mod renounce_operator;
 pub mod revoke_role;
+pub mod set_alias;
+pub mod set_config;
 pub mod set_operator;
+pub mod set_sub_operator;
+pub mod set_superuser;
+pub mod set_viewer;
+pub mod swap_role;
+pub mod unforbid;
 
 use cosmwasm_std::{DepsMut, Env, MessageInfo};
 
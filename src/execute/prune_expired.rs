@@ -0,0 +1,240 @@
+use crate::{
+    error::ContractError,
+    msg::{PruneExpiredMsg, RevokeRoleMsg, Subject},
+    query::expired::scan_expired,
+    utils::{decrement_or_remove_path_ref_count, remove_grant, saturation_attributes, PathRef},
+};
+use cosmwasm_std::{attr, to_json_string, Response};
+
+use super::{revoke_role::revoke_role, Context};
+
+const MAX_LIMIT: u16 = 100;
+const DEFAULT_LIMIT: u16 = 50;
+
+pub fn exec_prune_expired(
+    ctx: Context,
+    msg: PruneExpiredMsg,
+) -> Result<Response, ContractError> {
+    let Context {
+        mut deps,
+        env,
+        info,
+    } = ctx;
+    let PruneExpiredMsg { limit, cursor } = msg;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+
+    let (grants, cursor) = scan_expired(deps.storage, env.block.time, Subject::Acl, cursor, limit)?;
+
+    let mut saturated = false;
+
+    // Expired grants already give no access, so removing them can't lock out
+    // the operator and isn't guarded like removing live access to the control
+    // path is.
+    for grant in grants.iter() {
+        match (&grant.path, &grant.role) {
+            (Some(path), _) => {
+                if remove_grant(deps.storage, &grant.principal, path)?.is_some() {
+                    saturated |=
+                        decrement_or_remove_path_ref_count(deps.storage, path, PathRef::Principal)?;
+                }
+            },
+            (None, Some(role)) => {
+                // Revoking maintains the role's number of principals
+                let (_, revoke_saturated) = revoke_role(
+                    Context {
+                        deps: deps.branch(),
+                        env: env.clone(),
+                        info: info.clone(),
+                    },
+                    RevokeRoleMsg {
+                        principal: grant.principal.to_owned(),
+                        role: role.to_owned(),
+                        force: Some(true),
+                    },
+                )?;
+                saturated |= revoke_saturated;
+            },
+            (None, None) => {},
+        }
+    }
+
+    let mut attrs = vec![
+        attr("action", "prune_expired"),
+        attr("n_pruned", grants.len().to_string()),
+    ];
+    if let Some(cursor) = cursor {
+        attrs.push(attr("cursor", to_json_string(&cursor)?));
+    }
+
+    Ok(Response::new()
+        .add_attributes(attrs)
+        .add_attributes(saturation_attributes(saturated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        from_json,
+        testing::{mock_env, mock_info},
+    };
+
+    use super::exec_prune_expired;
+    use crate::{
+        contract::{execute, query},
+        error::ContractError,
+        models::PathRefCounts,
+        msg::{
+            AllowMsg, ExecuteMsg, GrantRoleMsg, PruneExpiredMsg, QueryMsg, RoleExecuteMsg, Subject,
+        },
+        responses::{AclResponse, ExpiredResponse},
+        state::{
+            PATH_REF_COUNTS, PRINCIPAL_PATH_AUTHORIZATIONS, PRINCIPAL_ROLE_AUTHORIZATIONS,
+            ROLE_INFOS,
+        },
+        testing::{
+            allow_msg, control_path, create_role, ctx, exec, grant_role_msg, query_as,
+            self_operate, setup, MockDeps, OPERATOR,
+        },
+    };
+
+    fn prune(deps: &mut MockDeps) -> Result<(), ContractError> {
+        let mut later = ctx(deps.as_mut());
+        later.env.block.time = mock_env().block.time.plus_seconds(120);
+        exec_prune_expired(
+            later,
+            PruneExpiredMsg {
+                limit: None,
+                cursor: None,
+            },
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn pruning_removes_expired_access_to_control_path() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &[&control_path()]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(60),
+                ..allow_msg("admin", &control_path())
+            }),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(60),
+                ..grant_role_msg("admin", "admins")
+            })),
+        )
+        .unwrap();
+        self_operate(deps.as_mut());
+
+        prune(&mut deps).unwrap();
+
+        let storage = deps.as_ref().storage;
+        let admin = "admin".to_owned();
+        assert!(!PRINCIPAL_PATH_AUTHORIZATIONS.has(storage, (&admin, &control_path())));
+        assert!(!PRINCIPAL_ROLE_AUTHORIZATIONS.has(storage, (&admin, &"admins".to_owned())));
+    }
+
+    #[test]
+    fn expired_grants_are_listed_then_pruned_with_counts_adjusted() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/blog"]);
+        for (principal, path, ttl) in [
+            ("alice", "/docs", Some(60)),
+            ("bob", "/docs", None),
+            ("carol", "/wiki", Some(60)),
+        ] {
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Allow(AllowMsg {
+                    ttl,
+                    ..allow_msg(principal, path)
+                }),
+            )
+            .unwrap();
+        }
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(60),
+                ..grant_role_msg("carol", "editors")
+            })),
+        )
+        .unwrap();
+
+        let mut later = mock_env();
+        later.block.time = later.block.time.plus_seconds(120);
+        let expired = |deps: &MockDeps| -> Vec<(String, Option<String>, Option<String>)> {
+            let resp: ExpiredResponse = from_json(
+                query(
+                    deps.as_ref(),
+                    later.clone(),
+                    QueryMsg::Expired {
+                        subject: Subject::Acl,
+                        limit: None,
+                        cursor: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            resp.grants
+                .into_iter()
+                .map(|g| (g.principal, g.path, g.role))
+                .collect()
+        };
+
+        assert_eq!(
+            expired(&deps),
+            vec![
+                ("alice".to_owned(), Some("/docs".to_owned()), None),
+                ("carol".to_owned(), Some("/wiki".to_owned()), None),
+                ("carol".to_owned(), None, Some("editors".to_owned())),
+            ]
+        );
+
+        execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info(OPERATOR, &[]),
+            ExecuteMsg::PruneExpired(PruneExpiredMsg {
+                limit: None,
+                cursor: None,
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(expired(&deps), vec![]);
+        let refs = |path: &str| {
+            PATH_REF_COUNTS
+                .may_load(&deps.storage, &path.to_owned())
+                .unwrap()
+        };
+        assert_eq!(
+            refs("/docs"),
+            Some(PathRefCounts {
+                principals: 1,
+                roles: 0
+            })
+        );
+        assert_eq!(refs("/wiki"), None);
+        assert_eq!(
+            ROLE_INFOS
+                .load(&deps.storage, &"editors".to_owned())
+                .unwrap()
+                .n_principals,
+            0
+        );
+        let acl: AclResponse = query_as(deps.as_ref(), QueryMsg::Acl {}).unwrap();
+        assert_eq!(acl.n_principals, 1);
+    }
+}
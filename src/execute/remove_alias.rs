@@ -0,0 +1,28 @@
+use crate::{
+    error::ContractError,
+    msg::RemoveAliasMsg,
+    state::{ALIASES, PRINCIPAL_ALIASES},
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_remove_alias(
+    ctx: Context,
+    msg: RemoveAliasMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let RemoveAliasMsg { addr } = msg;
+    let addr = deps.api.addr_validate(&addr)?;
+
+    if let Some(principal) = ALIASES.may_load(deps.storage, &addr)? {
+        PRINCIPAL_ALIASES.remove(deps.storage, (&principal, &addr));
+    }
+
+    ALIASES.remove(deps.storage, &addr);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_alias"),
+        attr("addr", addr.to_string()),
+    ]))
+}
@@ -0,0 +1,109 @@
+use crate::{
+    error::ContractError,
+    math::sub_u32,
+    msg::RemoveGroupMemberMsg,
+    state::{
+        GROUP_INFOS, GROUP_MEMBERS, PRINCIPAL_GROUPS, PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_PATHS,
+    },
+    utils::{ensure_control_path_kept, to_group_principal},
+};
+use cosmwasm_std::{attr, Order, Response, StdResult};
+
+use super::Context;
+
+pub fn exec_remove_from_group(
+    ctx: Context,
+    msg: RemoveGroupMemberMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, .. } = ctx;
+    let RemoveGroupMemberMsg {
+        group,
+        principal,
+        force,
+    } = msg;
+
+    let mut info = GROUP_INFOS.may_load(deps.storage, &group)?.ok_or_else(|| {
+        ContractError::GroupNotFound {
+            group: group.clone(),
+        }
+    })?;
+
+    // A member loses the paths of every role granted to the group
+    if !force.unwrap_or(false) {
+        let group_principal = to_group_principal(&group);
+        let roles = PRINCIPAL_ROLE_AUTHORIZATIONS
+            .prefix(&group_principal)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<String>>>()?;
+        for role in roles.iter() {
+            let role_paths = ROLE_PATHS
+                .prefix(role)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<String>>>()?;
+            ensure_control_path_kept(deps.storage, &env.contract.address, &role_paths, force)?;
+        }
+    }
+
+    if GROUP_MEMBERS.has(deps.storage, (&group, &principal)) {
+        info.n_members = sub_u32(info.n_members, 1)?;
+        GROUP_INFOS.save(deps.storage, &group, &info)?;
+    }
+
+    GROUP_MEMBERS.remove(deps.storage, (&group, &principal));
+    PRINCIPAL_GROUPS.remove(deps.storage, (&principal, &group));
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_from_group"),
+        attr("group", group),
+        attr("principal", principal),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exec_remove_from_group;
+    use crate::{
+        error::ContractError,
+        msg::{CreateGroupMsg, ExecuteMsg, GroupExecuteMsg, GroupMemberMsg, RemoveGroupMemberMsg},
+        testing::{create_role, ctx, exec, grant_role, self_operate, setup, OPERATOR},
+    };
+
+    fn remove_msg(force: Option<bool>) -> RemoveGroupMemberMsg {
+        RemoveGroupMemberMsg {
+            group: "team".to_owned(),
+            principal: "admin".to_owned(),
+            force,
+        }
+    }
+
+    #[test]
+    fn removal_guards_control_path_granted_via_group() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Group(GroupExecuteMsg::Create(CreateGroupMsg {
+                name: "team".to_owned(),
+                description: None,
+            })),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Group(GroupExecuteMsg::Add(GroupMemberMsg {
+                group: "team".to_owned(),
+                principal: "admin".to_owned(),
+            })),
+        )
+        .unwrap();
+        create_role(deps.as_mut(), "admins", &["/acls"]);
+        grant_role(deps.as_mut(), "group:team", "admins");
+        self_operate(deps.as_mut());
+
+        let result = exec_remove_from_group(ctx(deps.as_mut()), remove_msg(None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+
+        exec_remove_from_group(ctx(deps.as_mut()), remove_msg(Some(true))).unwrap();
+    }
+}
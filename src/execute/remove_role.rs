@@ -1,31 +1,352 @@
 use crate::{
     error::ContractError,
-    state::{PATH_ROLES, ROLE_INFOS, ROLE_PATHS},
-    utils::decrement_or_remove_path_ref_count,
+    math::sub_u32,
+    msg::RemoveRoleMsg,
+    state::{
+        N_ROLES, PATH_ROLES, PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS, ROLE_PATHS,
+        ROLE_PRINCIPALS, ROLE_PRINCIPAL_PATH_DENIALS, ROLE_REQUIRED_GRANTS,
+    },
+    utils::{
+        decrement_or_remove_path_ref_count, decrement_or_remove_principal_ref_count,
+        ensure_control_path_kept, remove_grant, remove_role_denial, saturation_attributes,
+        to_cannonical_role, PathRef,
+    },
 };
-use cosmwasm_std::{attr, Order, Response};
+use cosmwasm_std::{attr, Addr, Order, Response, Storage};
 
 use super::Context;
 
 pub fn exec_remove_role(
     ctx: Context,
-    role: String,
+    msg: RemoveRoleMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
+    let Context { deps, env, .. } = ctx;
+    let RemoveRoleMsg { role, force } = msg;
+    let role = to_cannonical_role(deps.storage, role)?;
 
-    let paths_to_remove: Vec<String> = PATH_ROLES
+    let info = ROLE_INFOS
+        .may_load(deps.storage, &role)?
+        .ok_or_else(|| ContractError::RoleNotFound { role: role.clone() })?;
+
+    // Grants that require the role can never apply again, so they're removed
+    // rather than left for a role later created with the same name to revive
+    let grants_to_remove: Vec<(String, String)> = ROLE_REQUIRED_GRANTS
+        .sub_prefix(&role)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Refuse to remove a role that's still granted to principals, or required
+    // by their direct grants, unless forced
+    if !force.unwrap_or(false) {
+        if info.n_principals > 0 {
+            return Err(ContractError::ValidationError {
+                reason: format!(
+                    "role {} is granted to {} principals; use force to remove it",
+                    role, info.n_principals
+                ),
+            });
+        }
+        if !grants_to_remove.is_empty() {
+            return Err(ContractError::ValidationError {
+                reason: format!(
+                    "role {} is required by {} direct grants; use force to remove it",
+                    role,
+                    grants_to_remove.len()
+                ),
+            });
+        }
+    }
+
+    let paths_to_remove: Vec<String> = ROLE_PATHS
         .prefix(&role)
         .keys(deps.storage, None, None, Order::Ascending)
-        .map(|k| k.unwrap())
-        .collect();
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let principals_to_remove: Vec<String> = ROLE_PRINCIPALS
+        .prefix(&role)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !principals_to_remove.is_empty() {
+        ensure_control_path_kept_for_role(
+            deps.storage,
+            &env.contract.address,
+            &role,
+            &paths_to_remove,
+        )?;
+    }
+
+    ensure_control_path_kept_for_role(
+        deps.storage,
+        &env.contract.address,
+        &role,
+        grants_to_remove.iter().map(|(_, path)| path),
+    )?;
 
     ROLE_INFOS.remove(deps.storage, &role);
+    N_ROLES.update(deps.storage, |n| sub_u32(n, 1))?;
+
+    let mut saturated = false;
 
     for path in paths_to_remove.iter() {
-        decrement_or_remove_path_ref_count(deps.storage, &path)?;
-        ROLE_PATHS.remove(deps.storage, (&role, &path));
-        PATH_ROLES.remove(deps.storage, (&path, &role));
+        saturated |= decrement_or_remove_path_ref_count(deps.storage, path, PathRef::Role)?;
+        ROLE_PATHS.remove(deps.storage, (&role, path));
+        PATH_ROLES.remove(deps.storage, (path, &role));
+    }
+
+    // Revoke the role from any principals who still hold it
+    for principal in principals_to_remove.iter() {
+        PRINCIPAL_ROLE_AUTHORIZATIONS.remove(deps.storage, (principal, &role));
+        saturated |= decrement_or_remove_principal_ref_count(deps.storage, principal)?;
+        ROLE_PRINCIPALS.remove(deps.storage, (&role, principal));
+    }
+
+    // Drop denials of the role's access to paths, so that a role later created
+    // with the same name doesn't inherit them
+    let denials_to_remove: Vec<(String, String)> = ROLE_PRINCIPAL_PATH_DENIALS
+        .sub_prefix(&role)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (principal, path) in denials_to_remove.iter() {
+        remove_role_denial(deps.storage, principal, path, &role);
+    }
+
+    for (principal, path) in grants_to_remove.iter() {
+        if remove_grant(deps.storage, principal, path)?.is_some() {
+            saturated |=
+                decrement_or_remove_path_ref_count(deps.storage, path, PathRef::Principal)?;
+        }
     }
 
-    Ok(Response::new().add_attributes(vec![attr("action", "remove_role"), attr("role", role)]))
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "remove_role"),
+            attr("role", role),
+            attr(
+                "n_principals_revoked",
+                principals_to_remove.len().to_string(),
+            ),
+            attr("n_denials_removed", denials_to_remove.len().to_string()),
+            attr("n_grants_removed", grants_to_remove.len().to_string()),
+        ])
+        .add_attributes(saturation_attributes(saturated)))
+}
+
+/// Force only overrides the refusal to remove a role still granted to
+/// principals. Access to the control path lost with the role must first be
+/// removed explicitly, as by DenyRole or Deny with force, so that removing a
+/// role can't lock out the operator in passing.
+fn ensure_control_path_kept_for_role<'a>(
+    store: &dyn Storage,
+    acl_addr: &Addr,
+    role: &str,
+    cannonical_paths: impl IntoIterator<Item = &'a String>,
+) -> Result<(), ContractError> {
+    ensure_control_path_kept(store, acl_addr, cannonical_paths, None).map_err(|err| match err {
+        ContractError::ValidationError { .. } => ContractError::ValidationError {
+            reason: format!(
+                "removing role {} would remove access to the ACL's control path, which could lock out the operator; remove that access explicitly, with force, first",
+                role
+            ),
+        },
+        err => err,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exec_remove_role;
+    use crate::{
+        error::ContractError,
+        execute::deny_role::exec_deny_role,
+        msg::{AllowMsg, DenyRoleMsg, ExecuteMsg, ForbidMsg, QueryMsg, RemoveRoleMsg},
+        responses::RolesResponse,
+        state::{
+            PRINCIPAL_REF_COUNTS, PRINCIPAL_ROLE_PATH_DENIALS, ROLE_INFOS,
+            ROLE_PRINCIPAL_PATH_DENIALS, ROLE_REQUIRED_GRANTS,
+        },
+        testing::{
+            allow_msg, control_path, create_role, ctx, exec, forbid_msg, grant_role, is_allowed,
+            query_as, self_operate, setup, OPERATOR,
+        },
+    };
+    use cosmwasm_std::Order;
+
+    fn remove_msg(force: Option<bool>) -> RemoveRoleMsg {
+        RemoveRoleMsg {
+            role: "editors".to_owned(),
+            force,
+        }
+    }
+
+    #[test]
+    fn removes_role_granted_to_no_one() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+
+        exec_remove_role(ctx(deps.as_mut()), remove_msg(None)).unwrap();
+
+        assert!(!ROLE_INFOS.has(deps.as_ref().storage, &"editors".to_owned()));
+    }
+
+    #[test]
+    fn refuses_to_remove_granted_role_unless_forced() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+
+        let result = exec_remove_role(ctx(deps.as_mut()), remove_msg(None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+
+        assert!(ROLE_INFOS.has(deps.as_ref().storage, &"editors".to_owned()));
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs"));
+    }
+
+    #[test]
+    fn refuses_to_remove_role_required_by_grants_unless_forced() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                require_role: Some("editors".to_owned()),
+                ..allow_msg("bob", "/reports")
+            }),
+        )
+        .unwrap();
+
+        let result = exec_remove_role(ctx(deps.as_mut()), remove_msg(None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+        assert!(ROLE_INFOS.has(deps.as_ref().storage, &"editors".to_owned()));
+
+        exec_remove_role(ctx(deps.as_mut()), remove_msg(Some(true))).unwrap();
+        assert!(!ROLE_REQUIRED_GRANTS.has(
+            deps.as_ref().storage,
+            (
+                &"editors".to_owned(),
+                &"bob".to_owned(),
+                &"/reports".to_owned()
+            )
+        ));
+    }
+
+    #[test]
+    fn forced_removal_cleans_up_members() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Forbid(ForbidMsg {
+                role: Some("editors".to_owned()),
+                ..forbid_msg("alice", "/docs/drafts")
+            }),
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                require_role: Some("editors".to_owned()),
+                ..allow_msg("bob", "/reports")
+            }),
+        )
+        .unwrap();
+
+        let response = exec_remove_role(ctx(deps.as_mut()), remove_msg(Some(true))).unwrap();
+        let attr = |key: &str| {
+            response
+                .attributes
+                .iter()
+                .find(|a| a.key == key)
+                .map(|a| a.value.clone())
+        };
+        assert_eq!(attr("n_principals_revoked"), Some("1".to_owned()));
+        assert_eq!(attr("n_denials_removed"), Some("1".to_owned()));
+        assert_eq!(attr("n_grants_removed"), Some("1".to_owned()));
+
+        let RolesResponse(roles) = query_as(
+            deps.as_ref(),
+            QueryMsg::Roles {
+                principal: Some("alice".to_owned()),
+            },
+        )
+        .unwrap();
+        assert!(roles.is_empty());
+        assert!(!is_allowed(deps.as_ref(), "alice", "/docs"));
+
+        let storage = deps.as_ref().storage;
+        assert_eq!(
+            PRINCIPAL_ROLE_PATH_DENIALS
+                .keys(storage, None, None, Order::Ascending)
+                .count(),
+            0
+        );
+        assert_eq!(
+            ROLE_PRINCIPAL_PATH_DENIALS
+                .keys(storage, None, None, Order::Ascending)
+                .count(),
+            0
+        );
+        for principal in ["alice", "bob"] {
+            assert!(!PRINCIPAL_REF_COUNTS.has(storage, &principal.to_owned()));
+        }
+
+        // A role later created with the same name starts afresh
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        grant_role(deps.as_mut(), "bob", "editors");
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs/drafts"));
+        assert!(!is_allowed(deps.as_ref(), "bob", "/reports"));
+    }
+
+    #[test]
+    fn forced_removal_keeps_access_to_control_path() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/acls", "/projects"]);
+        grant_role(deps.as_mut(), "admin", "admins");
+        self_operate(deps.as_mut());
+
+        let remove_admins = || RemoveRoleMsg {
+            role: "admins".to_owned(),
+            force: Some(true),
+        };
+        let result = exec_remove_role(ctx(deps.as_mut()), remove_admins());
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+
+        // Once access to the control path is removed explicitly, the role goes
+        exec_deny_role(
+            ctx(deps.as_mut()),
+            DenyRoleMsg {
+                role: "admins".to_owned(),
+                path: "/acls".to_owned(),
+                strict: None,
+                force: Some(true),
+            },
+        )
+        .unwrap();
+        exec_remove_role(ctx(deps.as_mut()), remove_admins()).unwrap();
+    }
+
+    #[test]
+    fn removal_keeps_grants_requiring_role_to_control_path() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                require_role: Some("editors".to_owned()),
+                ..allow_msg("admin", &control_path())
+            }),
+        )
+        .unwrap();
+        self_operate(deps.as_mut());
+
+        let result = exec_remove_role(ctx(deps.as_mut()), remove_msg(None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
 }
@@ -0,0 +1,20 @@
+use crate::{error::ContractError, msg::RemoveSubOperatorMsg, state::SUB_OPERATORS};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_remove_sub_operator(
+    ctx: Context,
+    msg: RemoveSubOperatorMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let RemoveSubOperatorMsg { addr } = msg;
+    let addr = deps.api.addr_validate(&addr)?;
+
+    SUB_OPERATORS.remove(deps.storage, &addr);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_sub_operator"),
+        attr("sub_operator", addr.to_string()),
+    ]))
+}
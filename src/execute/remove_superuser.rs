@@ -0,0 +1,12 @@
+use crate::{error::ContractError, state::SUPERUSER};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_remove_superuser(ctx: Context) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+
+    SUPERUSER.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![attr("action", "remove_superuser")]))
+}
@@ -0,0 +1,20 @@
+use crate::{error::ContractError, msg::RemoveViewerMsg, state::VIEWERS};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_remove_viewer(
+    ctx: Context,
+    msg: RemoveViewerMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let RemoveViewerMsg { addr } = msg;
+    let addr = deps.api.addr_validate(&addr)?;
+
+    VIEWERS.remove(deps.storage, &addr);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_viewer"),
+        attr("viewer", addr.to_string()),
+    ]))
+}
@@ -0,0 +1,109 @@
+use crate::{client::Operator, error::ContractError, msg::RenounceOperatorMsg, state::OP};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_renounce_operator(
+    ctx: Context,
+    msg: RenounceOperatorMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, .. } = ctx;
+
+    // Freezing the ACL can't be undone, so it must be confirmed explicitly
+    if msg.confirm != env.contract.address.as_str() {
+        return Err(ContractError::ValidationError {
+            reason: "confirm must be the ACL's own address".to_owned(),
+        });
+    }
+
+    let old_operator = OP.load(deps.storage)?;
+    OP.save(deps.storage, &Operator::None)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "renounce_operator"),
+        attr("old_operator", old_operator.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::mock_env, Addr};
+
+    use crate::{
+        client::Operator,
+        error::ContractError,
+        models::ScopedPowers,
+        msg::{ExecuteMsg, QueryMsg, RenounceOperatorMsg, RoleExecuteMsg, SetSubOperatorMsg},
+        state::OP,
+        testing::{allow, allow_msg, exec, is_allowed, query_as, role_msg, setup, OPERATOR},
+    };
+
+    fn renounce_msg(confirm: &str) -> ExecuteMsg {
+        ExecuteMsg::RenounceOperator(RenounceOperatorMsg {
+            confirm: confirm.to_owned(),
+        })
+    }
+
+    #[test]
+    fn renouncing_requires_confirmation() {
+        let mut deps = setup();
+        let result = exec(deps.as_mut(), OPERATOR, renounce_msg("yes"));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+        assert_eq!(
+            OP.load(&deps.storage).unwrap(),
+            Operator::Address(Addr::unchecked(OPERATOR))
+        );
+    }
+
+    #[test]
+    fn renounced_acl_rejects_every_change_but_answers_queries() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/docs");
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetSubOperator(SetSubOperatorMsg {
+                addr: "helper".to_owned(),
+                powers: ScopedPowers {
+                    paths: true,
+                    ..ScopedPowers::default()
+                },
+            }),
+        )
+        .unwrap();
+        let contract_addr = mock_env().contract.address.to_string();
+        exec(deps.as_mut(), OPERATOR, renounce_msg(&contract_addr)).unwrap();
+
+        for (sender, msg) in [
+            (OPERATOR, ExecuteMsg::Allow(allow_msg("bob", "/docs"))),
+            ("helper", ExecuteMsg::Allow(allow_msg("bob", "/docs"))),
+            (
+                OPERATOR,
+                ExecuteMsg::Role(RoleExecuteMsg::Create(role_msg("editors", &[]))),
+            ),
+            (
+                OPERATOR,
+                ExecuteMsg::SetOperator(Operator::Address(Addr::unchecked(OPERATOR))),
+            ),
+            (OPERATOR, renounce_msg(&contract_addr)),
+        ] {
+            let result = exec(deps.as_mut(), sender, msg.clone());
+            assert!(
+                matches!(result, Err(ContractError::NotAuthorized { .. })),
+                "{:?}",
+                msg
+            );
+        }
+
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs"));
+        assert!(!is_allowed(deps.as_ref(), "bob", "/docs"));
+        let is_operator: bool = query_as(
+            deps.as_ref(),
+            QueryMsg::IsOperator {
+                address: OPERATOR.to_owned(),
+            },
+        )
+        .unwrap();
+        assert!(!is_operator);
+    }
+}
@@ -1,10 +1,13 @@
-use cosmwasm_std::{attr, Response};
+use cosmwasm_std::{attr, Order, Response, StdResult};
 
 use crate::{
     error::ContractError,
-    math::sub_u32,
     msg::RevokeRoleMsg,
-    state::{PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS},
+    state::{CONFIG, PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS, ROLE_PATHS, ROLE_PRINCIPALS},
+    utils::{
+        decrement_or_remove_principal_ref_count, ensure_control_path_kept, saturation_attributes,
+        sub_count, to_cannonical_role,
+    },
 };
 
 use super::Context;
@@ -13,8 +16,43 @@ pub fn exec_revoke_role(
     ctx: Context,
     msg: RevokeRoleMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
-    let RevokeRoleMsg { principal, role } = msg;
+    revoke_role(ctx, msg).map(|(response, _)| response)
+}
+
+/// Revoke a role, also returning whether any count saturated, for callers
+/// revoking as one step of a larger change.
+pub fn revoke_role(
+    ctx: Context,
+    msg: RevokeRoleMsg,
+) -> Result<(Response, bool), ContractError> {
+    let Context { deps, env, .. } = ctx;
+    let RevokeRoleMsg {
+        principal,
+        role,
+        force,
+    } = msg;
+    let role = to_cannonical_role(deps.storage, role)?;
+
+    let is_held = PRINCIPAL_ROLE_AUTHORIZATIONS.has(deps.storage, (&principal, &role));
+
+    // Revoking a role linked to the control path from its last holder removes
+    // access to it too, whereas other holders keep that access
+    if is_held && !force.unwrap_or(false) {
+        let n_holders = ROLE_PRINCIPALS
+            .prefix(&role)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .take(2)
+            .count();
+        if n_holders < 2 {
+            let role_paths = ROLE_PATHS
+                .prefix(&role)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<String>>>()?;
+            ensure_control_path_kept(deps.storage, &env.contract.address, &role_paths, force)?;
+        }
+    }
+    let saturating = CONFIG.load(deps.storage)?.saturating_counts;
+    let mut saturated = false;
 
     // Decrement the total number of principals associated with the role
     ROLE_INFOS.update(
@@ -22,22 +60,146 @@ pub fn exec_revoke_role(
         &role,
         |maybe_info| -> Result<_, ContractError> {
             if let Some(mut info) = maybe_info {
-                info.n_principals = sub_u32(info.n_principals, 1)?;
+                if is_held {
+                    (info.n_principals, saturated) = sub_count(saturating, info.n_principals, 1)?;
+                }
                 Ok(info)
             } else {
-                Err(ContractError::NotAuthorized {
-                    reason: format!("role {} does not exist", role),
+                Err(ContractError::RoleNotFound {
+                    role: role.to_owned(),
                 })
             }
         },
     )?;
 
+    if is_held {
+        saturated |= decrement_or_remove_principal_ref_count(deps.storage, &principal)?;
+    }
+
     // Disassciate the role from the principal
     PRINCIPAL_ROLE_AUTHORIZATIONS.remove(deps.storage, (&principal, &role));
+    ROLE_PRINCIPALS.remove(deps.storage, (&role, &principal));
+
+    let response = Response::new()
+        .add_attributes(vec![
+            attr("action", "revoke_role"),
+            attr("principal", principal),
+            attr("role", role),
+        ])
+        .add_attributes(saturation_attributes(saturated));
+
+    Ok((response, saturated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exec_revoke_role;
+    use crate::{
+        error::ContractError,
+        models::Config,
+        msg::RevokeRoleMsg,
+        state::ROLE_INFOS,
+        testing::{
+            create_role, ctx, grant_role, is_allowed, self_operate, setup, setup_with_config,
+            MockDeps,
+        },
+    };
+
+    fn revoke_msg(
+        role: &str,
+        force: Option<bool>,
+    ) -> RevokeRoleMsg {
+        RevokeRoleMsg {
+            principal: "admin".to_owned(),
+            role: role.to_owned(),
+            force,
+        }
+    }
+
+    #[test]
+    fn revoke_guards_roles_linked_to_control_path() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/acls"]);
+        create_role(deps.as_mut(), "editors", &["/projects"]);
+        grant_role(deps.as_mut(), "admin", "admins");
+        grant_role(deps.as_mut(), "admin", "editors");
+        self_operate(deps.as_mut());
+
+        let result = exec_revoke_role(ctx(deps.as_mut()), revoke_msg("admins", None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+
+        exec_revoke_role(ctx(deps.as_mut()), revoke_msg("editors", None)).unwrap();
+        exec_revoke_role(ctx(deps.as_mut()), revoke_msg("admins", Some(true))).unwrap();
+    }
+
+    #[test]
+    fn revoke_guards_only_the_last_holder() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/acls"]);
+        grant_role(deps.as_mut(), "admin", "admins");
+        grant_role(deps.as_mut(), "backup", "admins");
+        self_operate(deps.as_mut());
+
+        // Revoking from a principal not holding the role removes no access
+        let result = exec_revoke_role(
+            ctx(deps.as_mut()),
+            RevokeRoleMsg {
+                principal: "stranger".to_owned(),
+                role: "admins".to_owned(),
+                force: None,
+            },
+        );
+        assert!(result.is_ok());
+
+        // Nor does revoking it from one of several holders
+        exec_revoke_role(ctx(deps.as_mut()), revoke_msg("admins", None)).unwrap();
+        assert!(is_allowed(deps.as_ref(), "backup", "/acls"));
+
+        let result = exec_revoke_role(
+            ctx(deps.as_mut()),
+            RevokeRoleMsg {
+                principal: "backup".to_owned(),
+                role: "admins".to_owned(),
+                force: None,
+            },
+        );
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+
+    /// Grant editors to admin, then zero the role's count, as if an earlier
+    /// accounting error had lost it.
+    fn setup_miscounted(saturating_counts: bool) -> MockDeps {
+        let mut deps = setup_with_config(Config {
+            saturating_counts,
+            ..Config::default()
+        });
+        create_role(deps.as_mut(), "editors", &["/projects"]);
+        grant_role(deps.as_mut(), "admin", "editors");
+
+        let editors = "editors".to_owned();
+        let mut info = ROLE_INFOS.load(&deps.storage, &editors).unwrap();
+        info.n_principals = 0;
+        ROLE_INFOS.save(&mut deps.storage, &editors, &info).unwrap();
+        deps
+    }
+
+    #[test]
+    fn revoke_at_zero_fails_unless_saturating() {
+        let mut deps = setup_miscounted(false);
+        let result = exec_revoke_role(ctx(deps.as_mut()), revoke_msg("editors", None));
+        assert!(matches!(result, Err(ContractError::Std(_))));
+    }
+
+    #[test]
+    fn revoke_at_zero_saturates_with_warning() {
+        let mut deps = setup_miscounted(true);
+        let resp = exec_revoke_role(ctx(deps.as_mut()), revoke_msg("editors", None)).unwrap();
+        assert!(resp.attributes.iter().any(|a| a.key == "warning"));
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "revoke_role"),
-        attr("principal", principal),
-        attr("role", role),
-    ]))
+        let info = ROLE_INFOS
+            .load(&deps.storage, &"editors".to_owned())
+            .unwrap();
+        assert_eq!(info.n_principals, 0);
+        assert!(!is_allowed(deps.as_ref(), "admin", "/projects"));
+    }
 }
@@ -0,0 +1,131 @@
+use crate::{
+    error::ContractError,
+    msg::SetAliasMsg,
+    state::{ALIASES, PRINCIPAL_ALIASES},
+    utils::ensure_valid_principal,
+};
+use cosmwasm_std::{attr, Addr, Order, Response};
+
+use super::Context;
+
+pub fn exec_set_alias(
+    ctx: Context,
+    msg: SetAliasMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let SetAliasMsg { addr, principal } = msg;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    ensure_valid_principal(deps.storage, deps.api, &principal)?;
+
+    // Aliases are kept one level deep to rule out cycles: the principal can't
+    // itself be an alias, and the address can't already have aliases of its own.
+    if addr.as_str() == principal {
+        return Err(ContractError::ValidationError {
+            reason: "cannot alias an address to itself".to_owned(),
+        });
+    }
+    if ALIASES.has(deps.storage, &Addr::unchecked(&principal)) {
+        return Err(ContractError::ValidationError {
+            reason: format!("{} is itself an alias", principal),
+        });
+    }
+    if PRINCIPAL_ALIASES
+        .prefix(&addr.to_string())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+    {
+        return Err(ContractError::ValidationError {
+            reason: format!("{} already has aliases", addr),
+        });
+    }
+
+    // Replace any existing alias for the address
+    if let Some(prev_principal) = ALIASES.may_load(deps.storage, &addr)? {
+        PRINCIPAL_ALIASES.remove(deps.storage, (&prev_principal, &addr));
+    }
+
+    ALIASES.save(deps.storage, &addr, &principal)?;
+    PRINCIPAL_ALIASES.save(deps.storage, (&principal, &addr), &0)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_alias"),
+        attr("addr", addr.to_string()),
+        attr("principal", principal),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        msg::{ExecuteMsg, RemoveAliasMsg, SetAliasMsg},
+        testing::{allow, exec, is_allowed, setup, MockDeps, OPERATOR},
+    };
+
+    fn set_alias(
+        deps: &mut MockDeps,
+        addr: &str,
+        principal: &str,
+    ) -> Result<(), ContractError> {
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetAlias(SetAliasMsg {
+                addr: addr.to_owned(),
+                principal: principal.to_owned(),
+            }),
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn aliases_share_the_principals_grants() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/docs");
+        set_alias(&mut deps, "alicehot", "alice").unwrap();
+        set_alias(&mut deps, "alicecold", "alice").unwrap();
+
+        for addr in ["alice", "alicehot", "alicecold"] {
+            assert!(is_allowed(deps.as_ref(), addr, "/docs"), "{}", addr);
+        }
+        assert!(!is_allowed(deps.as_ref(), "bob", "/docs"));
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::RemoveAlias(RemoveAliasMsg {
+                addr: "alicehot".to_owned(),
+            }),
+        )
+        .unwrap();
+        assert!(!is_allowed(deps.as_ref(), "alicehot", "/docs"));
+        assert!(is_allowed(deps.as_ref(), "alicecold", "/docs"));
+    }
+
+    #[test]
+    fn aliases_cannot_form_chains_or_cycles() {
+        let mut deps = setup();
+        set_alias(&mut deps, "alicehot", "alice").unwrap();
+
+        for (addr, principal) in [
+            // To itself
+            ("alice", "alice"),
+            // To an alias
+            ("alicecold", "alicehot"),
+            // From a principal that has aliases
+            ("alice", "bob"),
+        ] {
+            assert!(
+                matches!(
+                    set_alias(&mut deps, addr, principal),
+                    Err(ContractError::ValidationError { .. })
+                ),
+                "{} to {}",
+                addr,
+                principal
+            );
+        }
+    }
+}
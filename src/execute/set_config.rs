@@ -0,0 +1,110 @@
+use crate::{error::ContractError, models::Config, state::CONFIG};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_set_config(
+    ctx: Context,
+    config: Config,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let old_config = CONFIG.load(deps.storage)?;
+
+    // Changing how role names are canonicalized would orphan existing roles
+    if config.namespaced_roles != old_config.namespaced_roles {
+        return Err(ContractError::ValidationError {
+            reason: "namespaced_roles cannot be changed after instantiation".to_owned(),
+        });
+    }
+
+    // Changing how paths are canonicalized would orphan existing paths
+    if config.path_style != old_config.path_style {
+        return Err(ContractError::ValidationError {
+            reason: "path_style cannot be changed after instantiation".to_owned(),
+        });
+    }
+
+    if config.decode_percent != old_config.decode_percent {
+        return Err(ContractError::ValidationError {
+            reason: "decode_percent cannot be changed after instantiation".to_owned(),
+        });
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "set_config")]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        models::{Config, PathStyle},
+        msg::{ExecuteMsg, PathsQueryParams, QueryMsg, Subject},
+        responses::PathsResponse,
+        testing::{
+            allow, create_role, exec, grant_role, is_allowed, query_as, setup_with_config,
+            MockDeps, OPERATOR,
+        },
+    };
+
+    fn principal_paths(
+        deps: &MockDeps,
+        principal: &str,
+    ) -> Vec<String> {
+        let resp: PathsResponse = query_as(
+            deps.as_ref(),
+            QueryMsg::Paths(PathsQueryParams {
+                subject: Subject::Principal(principal.to_owned()),
+                limit: None,
+                start: None,
+                stop: None,
+                cursor: None,
+                with_refs: None,
+                order_by: None,
+                include_roles: Some(true),
+                expand: None,
+            }),
+        )
+        .unwrap();
+        resp.paths.into_iter().map(|p| p.path).collect()
+    }
+
+    #[test]
+    fn each_path_style_writes_and_checks_same_keys() {
+        for (style, expected) in [
+            (PathStyle::Rooted, "/projects/42"),
+            (PathStyle::Bare, "projects/42"),
+        ] {
+            let mut deps = setup_with_config(Config {
+                path_style: style,
+                ..Config::default()
+            });
+            allow(deps.as_mut(), "alice", "projects/42");
+            create_role(deps.as_mut(), "devs", &["/projects/42"]);
+            grant_role(deps.as_mut(), "bob", "devs");
+
+            for principal in ["alice", "bob"] {
+                assert_eq!(principal_paths(&deps, principal), vec![expected]);
+                for path in ["projects/42/a", "/projects/42/a", "projects//42/a/"] {
+                    assert!(is_allowed(deps.as_ref(), principal, path), "{}", path);
+                }
+                assert!(!is_allowed(deps.as_ref(), principal, "projects/43"));
+            }
+        }
+    }
+
+    #[test]
+    fn path_style_cannot_change() {
+        let mut deps = setup_with_config(Config::default());
+        let result = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetConfig(Config {
+                path_style: PathStyle::Bare,
+                ..Config::default()
+            }),
+        );
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{client::Operator, error::ContractError, state::OP};
+use crate::{client::Operator, error::ContractError, state::OP, utils::validate_operator};
 use cosmwasm_std::{attr, Response};
 
 use super::Context;
@@ -8,7 +8,12 @@ pub fn exec_set_operator(
     new_operator: Operator,
 ) -> Result<Response, ContractError> {
     let Context { deps, .. } = ctx;
+
+    validate_operator(deps.api, &new_operator)?;
+
     let old_operator = OP.load(deps.storage)?;
+    OP.save(deps.storage, &new_operator)?;
+
     Ok(Response::new().add_attributes(vec![
         attr("action", "set_operator"),
         attr("old_operator", old_operator.to_string()),
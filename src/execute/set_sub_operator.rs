@@ -0,0 +1,112 @@
+use crate::{error::ContractError, msg::SetSubOperatorMsg, state::SUB_OPERATORS};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_set_sub_operator(
+    ctx: Context,
+    msg: SetSubOperatorMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let SetSubOperatorMsg { addr, powers } = msg;
+    let addr = deps.api.addr_validate(&addr)?;
+
+    // A sub-operator without any powers would only add to storage
+    if powers == Default::default() {
+        return Err(ContractError::ValidationError {
+            reason: "sub-operator must be given at least one power".to_owned(),
+        });
+    }
+
+    SUB_OPERATORS.save(deps.storage, &addr, &powers)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_sub_operator"),
+        attr("sub_operator", addr.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        models::ScopedPowers,
+        msg::{ExecuteMsg, RemoveSubOperatorMsg, RoleExecuteMsg, SetSubOperatorMsg},
+        testing::{allow_msg, create_role, exec, grant_role_msg, setup, MockDeps, OPERATOR},
+    };
+
+    fn set_helper(
+        deps: &mut MockDeps,
+        powers: ScopedPowers,
+    ) -> Result<(), ContractError> {
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetSubOperator(SetSubOperatorMsg {
+                addr: "helper".to_owned(),
+                powers,
+            }),
+        )
+        .map(|_| ())
+    }
+
+    fn grant_as_helper(deps: &mut MockDeps) -> Result<(), ContractError> {
+        exec(
+            deps.as_mut(),
+            "helper",
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(grant_role_msg("alice", "editors"))),
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn sub_operator_is_limited_to_its_powers() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        set_helper(
+            &mut deps,
+            ScopedPowers {
+                role_grants: true,
+                ..ScopedPowers::default()
+            },
+        )
+        .unwrap();
+
+        grant_as_helper(&mut deps).unwrap();
+        for msg in [
+            ExecuteMsg::Allow(allow_msg("alice", "/docs")),
+            ExecuteMsg::SetSubOperator(SetSubOperatorMsg {
+                addr: "helper".to_owned(),
+                powers: ScopedPowers {
+                    paths: true,
+                    ..ScopedPowers::default()
+                },
+            }),
+        ] {
+            let result = exec(deps.as_mut(), "helper", msg);
+            assert!(matches!(result, Err(ContractError::NotAuthorized { .. })));
+        }
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::RemoveSubOperator(RemoveSubOperatorMsg {
+                addr: "helper".to_owned(),
+            }),
+        )
+        .unwrap();
+        assert!(matches!(
+            grant_as_helper(&mut deps),
+            Err(ContractError::NotAuthorized { .. })
+        ));
+    }
+
+    #[test]
+    fn sub_operator_needs_a_power() {
+        let mut deps = setup();
+        assert!(matches!(
+            set_helper(&mut deps, ScopedPowers::default()),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{error::ContractError, msg::SetSuperuserMsg, state::SUPERUSER};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_set_superuser(
+    ctx: Context,
+    msg: SetSuperuserMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let SetSuperuserMsg { addr } = msg;
+    let addr = deps.api.addr_validate(&addr)?;
+
+    SUPERUSER.save(deps.storage, &addr)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_superuser"),
+        attr("superuser", addr.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Addr;
+
+    use crate::{
+        msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SetSuperuserMsg},
+        responses::AclResponse,
+        testing::{
+            exec, forbid, instantiate_msg, is_allowed, query_as, setup, setup_with, OPERATOR,
+        },
+    };
+
+    #[test]
+    fn superuser_passes_every_check_until_removed() {
+        let mut deps = setup_with(InstantiateMsg {
+            superuser: Some("root".to_owned()),
+            ..instantiate_msg()
+        });
+        forbid(deps.as_mut(), "root", "/vault");
+
+        assert!(is_allowed(deps.as_ref(), "root", "/anything"));
+        assert!(is_allowed(deps.as_ref(), "root", "/vault"));
+        let acl: AclResponse = query_as(deps.as_ref(), QueryMsg::Acl {}).unwrap();
+        assert_eq!(acl.superuser, Some(Addr::unchecked("root")));
+
+        exec(deps.as_mut(), OPERATOR, ExecuteMsg::RemoveSuperuser {}).unwrap();
+        assert!(!is_allowed(deps.as_ref(), "root", "/anything"));
+        let acl: AclResponse = query_as(deps.as_ref(), QueryMsg::Acl {}).unwrap();
+        assert_eq!(acl.superuser, None);
+    }
+
+    #[test]
+    fn superuser_is_opt_in() {
+        let mut deps = setup();
+        assert!(!is_allowed(deps.as_ref(), "root", "/anything"));
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetSuperuser(SetSuperuserMsg {
+                addr: "root".to_owned(),
+            }),
+        )
+        .unwrap();
+        assert!(is_allowed(deps.as_ref(), "root", "/anything"));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{error::ContractError, msg::SetViewerMsg, state::VIEWERS, utils::hash_viewing_key};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_set_viewer(
+    ctx: Context,
+    msg: SetViewerMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let SetViewerMsg { addr, key } = msg;
+    let addr = deps.api.addr_validate(&addr)?;
+
+    if key.is_empty() {
+        return Err(ContractError::ValidationError {
+            reason: "viewing key cannot be empty".to_owned(),
+        });
+    }
+
+    // Only the key's hash is stored, so the key isn't exposed in storage
+    VIEWERS.save(deps.storage, &addr, &hash_viewing_key(&key))?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_viewer"),
+        attr("viewer", addr.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        models::Config,
+        msg::{ExecuteMsg, QueryMsg, RemoveViewerMsg, SetViewerMsg, ViewerAuth},
+        testing::{allow, exec, query_as, setup, setup_with_config, MockDeps, OPERATOR},
+    };
+
+    fn matrix(
+        deps: &MockDeps,
+        viewer: Option<(&str, &str)>,
+    ) -> Result<Vec<Vec<bool>>, ContractError> {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::Matrix {
+                principals: vec!["alice".to_owned()],
+                paths: vec!["/docs".to_owned()],
+                viewer: viewer.map(|(addr, key)| ViewerAuth {
+                    addr: addr.to_owned(),
+                    key: key.to_owned(),
+                }),
+            },
+        )
+    }
+
+    #[test]
+    fn private_queries_require_authorized_viewer() {
+        let mut deps = setup_with_config(Config {
+            private_queries: true,
+            ..Config::default()
+        });
+        allow(deps.as_mut(), "alice", "/docs");
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::SetViewer(SetViewerMsg {
+                addr: "monitor".to_owned(),
+                key: "secret".to_owned(),
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            matrix(&deps, Some(("monitor", "secret"))).unwrap(),
+            vec![vec![true]]
+        );
+        for viewer in [
+            None,
+            Some(("monitor", "guess")),
+            Some(("intruder", "secret")),
+        ] {
+            assert!(matches!(
+                matrix(&deps, viewer),
+                Err(ContractError::NotAuthorized { .. })
+            ));
+        }
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::RemoveViewer(RemoveViewerMsg {
+                addr: "monitor".to_owned(),
+            }),
+        )
+        .unwrap();
+        assert!(matches!(
+            matrix(&deps, Some(("monitor", "secret"))),
+            Err(ContractError::NotAuthorized { .. })
+        ));
+    }
+
+    #[test]
+    fn public_queries_need_no_viewer() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/docs");
+        assert_eq!(matrix(&deps, None).unwrap(), vec![vec![true]]);
+    }
+}
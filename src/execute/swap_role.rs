@@ -0,0 +1,255 @@
+use cosmwasm_std::{attr, Order, Response, StdResult};
+
+use crate::{
+    error::ContractError,
+    msg::{GrantRoleMsg, RevokeRoleMsg, SwapRoleMsg},
+    state::{PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS, ROLE_PATHS, ROLE_PRINCIPALS},
+    utils::{ensure_control_path_kept, saturation_attributes, to_cannonical_role},
+};
+
+use super::{grant_role::grant_role, revoke_role::revoke_role, Context};
+
+pub fn exec_swap_role(
+    ctx: Context,
+    msg: SwapRoleMsg,
+) -> Result<Response, ContractError> {
+    let Context {
+        mut deps,
+        env,
+        info,
+    } = ctx;
+    let SwapRoleMsg {
+        principal,
+        from,
+        to,
+        ttl,
+        force,
+    } = msg;
+
+    let from = to_cannonical_role(deps.storage, from)?;
+    let to = to_cannonical_role(deps.storage, to)?;
+
+    if !PRINCIPAL_ROLE_AUTHORIZATIONS.has(deps.storage, (&principal, &from)) {
+        return Err(ContractError::GrantNotFound {
+            principal,
+            target: from,
+        });
+    }
+    if !ROLE_INFOS.has(deps.storage, &to) {
+        return Err(ContractError::RoleNotFound { role: to });
+    }
+
+    // Only the old role's last holder loses its access to the control path,
+    // and paths the new role also links to aren't lost in the swap
+    let n_holders = ROLE_PRINCIPALS
+        .prefix(&from)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(2)
+        .count();
+    if n_holders < 2 && !force.unwrap_or(false) {
+        let lost_paths = ROLE_PATHS
+            .prefix(&from)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .filter(|path| {
+                path.as_ref()
+                    .map(|path| !ROLE_PATHS.has(deps.storage, (&to, path)))
+                    .unwrap_or(true)
+            })
+            .collect::<StdResult<Vec<String>>>()?;
+        ensure_control_path_kept(deps.storage, &env.contract.address, &lost_paths, force)?;
+    }
+
+    // Both steps maintain the number of principals of their respective roles
+    let (_, revoke_saturated) = revoke_role(
+        Context {
+            deps: deps.branch(),
+            env: env.clone(),
+            info: info.clone(),
+        },
+        RevokeRoleMsg {
+            principal: principal.to_owned(),
+            role: from.to_owned(),
+            // Guarded above, with allowance for the new role's paths
+            force: Some(true),
+        },
+    )?;
+    let (_, grant_saturated) = grant_role(
+        Context { deps, env, info },
+        GrantRoleMsg {
+            principal: principal.to_owned(),
+            role: to.to_owned(),
+            ttl,
+            ttl_unit: None,
+            not_before: None,
+        },
+    )?;
+
+    let saturated = revoke_saturated || grant_saturated;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "swap_role"),
+            attr("principal", principal),
+            attr("from", from),
+            attr("to", to),
+        ])
+        .add_attributes(saturation_attributes(saturated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Response;
+
+    use super::exec_swap_role;
+    use crate::{
+        error::ContractError,
+        models::Config,
+        msg::{ExecuteMsg, QueryMsg, RoleExecuteMsg, SwapRoleMsg},
+        responses::RoleResponse,
+        state::ROLE_INFOS,
+        testing::{
+            create_role, ctx, exec, grant_role, is_allowed, query_as, self_operate, setup,
+            setup_with_config, MockDeps, OPERATOR,
+        },
+    };
+
+    fn populate(deps: &mut MockDeps) {
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        create_role(deps.as_mut(), "admins", &["/admin"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        grant_role(deps.as_mut(), "bob", "editors");
+    }
+
+    fn swap(
+        deps: &mut MockDeps,
+        principal: &str,
+        to: &str,
+    ) -> Result<Response, ContractError> {
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Swap(SwapRoleMsg {
+                principal: principal.to_owned(),
+                from: "editors".to_owned(),
+                to: to.to_owned(),
+                ttl: None,
+                force: None,
+            })),
+        )
+    }
+
+    fn counts(deps: &MockDeps) -> (u32, u32) {
+        let n_principals = |name: &str| {
+            query_as::<RoleResponse>(deps.as_ref(), QueryMsg::Role(name.to_owned()))
+                .unwrap()
+                .n_principals
+        };
+        (n_principals("editors"), n_principals("admins"))
+    }
+
+    #[test]
+    fn swap_moves_principal_between_roles() {
+        let mut deps = setup();
+        populate(&mut deps);
+
+        swap(&mut deps, "alice", "admins").unwrap();
+
+        assert_eq!(counts(&deps), (1, 1));
+        assert!(!is_allowed(deps.as_ref(), "alice", "/docs"));
+        assert!(is_allowed(deps.as_ref(), "alice", "/admin"));
+        assert!(is_allowed(deps.as_ref(), "bob", "/docs"));
+    }
+
+    #[test]
+    fn swap_requires_held_role_and_existing_target() {
+        let mut deps = setup();
+        populate(&mut deps);
+
+        assert!(matches!(
+            swap(&mut deps, "carol", "admins"),
+            Err(ContractError::GrantNotFound { .. })
+        ));
+        assert!(matches!(
+            swap(&mut deps, "alice", "owners"),
+            Err(ContractError::RoleNotFound { .. })
+        ));
+
+        assert_eq!(counts(&deps), (2, 0));
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs"));
+    }
+
+    #[test]
+    fn swap_forwards_saturation_warnings() {
+        let mut deps = setup_with_config(Config {
+            saturating_counts: true,
+            ..Config::default()
+        });
+        populate(&mut deps);
+
+        // As if an earlier accounting error had lost the role's principals
+        let editors = "editors".to_owned();
+        let mut info = ROLE_INFOS.load(deps.as_ref().storage, &editors).unwrap();
+        info.n_principals = 0;
+        ROLE_INFOS
+            .save(deps.as_mut().storage, &editors, &info)
+            .unwrap();
+
+        let response = swap(&mut deps, "alice", "admins").unwrap();
+        assert!(response.attributes.iter().any(|a| a.key == "warning"));
+        assert_eq!(counts(&deps), (0, 1));
+    }
+
+    #[test]
+    fn swap_guards_control_path_not_kept_by_new_role() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/acls"]);
+        create_role(deps.as_mut(), "owners", &["/acls", "/docs"]);
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "admin", "admins");
+        self_operate(deps.as_mut());
+
+        let swap_msg = |to: &str, force: Option<bool>| SwapRoleMsg {
+            principal: "admin".to_owned(),
+            from: "admins".to_owned(),
+            to: to.to_owned(),
+            ttl: None,
+            force,
+        };
+
+        let result = exec_swap_role(ctx(deps.as_mut()), swap_msg("editors", None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+
+        // The new role keeps access to the control path
+        exec_swap_role(ctx(deps.as_mut()), swap_msg("owners", None)).unwrap();
+
+        let mut back = swap_msg("admins", None);
+        back.from = "owners".to_owned();
+        exec_swap_role(ctx(deps.as_mut()), back).unwrap();
+        exec_swap_role(ctx(deps.as_mut()), swap_msg("editors", Some(true))).unwrap();
+    }
+
+    #[test]
+    fn swap_guards_only_the_last_holder() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "admins", &["/acls"]);
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "admin", "admins");
+        grant_role(deps.as_mut(), "backup", "admins");
+        self_operate(deps.as_mut());
+
+        let swap_msg = |principal: &str| SwapRoleMsg {
+            principal: principal.to_owned(),
+            from: "admins".to_owned(),
+            to: "editors".to_owned(),
+            ttl: None,
+            force: None,
+        };
+
+        // Swapping one of several holders keeps the others' access
+        exec_swap_role(ctx(deps.as_mut()), swap_msg("admin")).unwrap();
+        assert!(is_allowed(deps.as_ref(), "backup", "/acls"));
+
+        let result = exec_swap_role(ctx(deps.as_mut()), swap_msg("backup"));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{
+    error::ContractError,
+    msg::UnforbidMsg,
+    state::PRINCIPAL_PATH_DENIALS,
+    utils::{remove_role_denial, to_acl_path, to_cannonical_role},
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+pub fn exec_unforbid(
+    ctx: Context,
+    msg: UnforbidMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, .. } = ctx;
+    let UnforbidMsg {
+        principal,
+        path,
+        role,
+    } = msg;
+    let cannonical_path = to_acl_path(deps.storage, &path)?;
+
+    match &role {
+        Some(role) => {
+            let role = to_cannonical_role(deps.storage, role.to_owned())?;
+            remove_role_denial(deps.storage, &principal, &cannonical_path, &role);
+        },
+        None => PRINCIPAL_PATH_DENIALS.remove(deps.storage, (&principal, &cannonical_path)),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "unforbid"),
+        attr("path", cannonical_path),
+        attr("principal", principal),
+        attr("role", role.unwrap_or_default()),
+    ]))
+}
@@ -1,8 +1,11 @@
+pub mod auth;
 pub mod client;
 #[cfg(not(feature = "library"))]
 pub mod contract;
+pub mod error;
 #[cfg(not(feature = "library"))]
 pub mod execute;
+pub mod math;
 pub mod models;
 pub mod msg;
 #[cfg(not(feature = "library"))]
@@ -10,7 +13,6 @@ pub mod query;
 pub mod responses;
 pub mod state;
 
-mod error;
-#[allow(dead_code)]
-mod math;
+#[cfg(all(test, not(feature = "library")))]
+mod testing;
 mod utils;
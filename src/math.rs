@@ -143,3 +143,24 @@ pub fn add_u32(
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{add_u32, sub_u32};
+    use crate::error::ContractError;
+
+    #[test]
+    fn add_u32_fails_past_max() {
+        assert_eq!(add_u32(u32::MAX - 1, 1).unwrap(), u32::MAX);
+        assert_eq!(add_u32(u32::MAX, 0).unwrap(), u32::MAX);
+        assert!(matches!(add_u32(u32::MAX, 1), Err(ContractError::Std(_))));
+    }
+
+    #[test]
+    fn sub_u32_fails_below_zero() {
+        assert_eq!(sub_u32(1, 1).unwrap(), 0);
+        assert_eq!(sub_u32(u32::MAX, u32::MAX).unwrap(), 0);
+        assert!(matches!(sub_u32(0, 1), Err(ContractError::Std(_))));
+        assert!(matches!(sub_u32(1, u32::MAX), Err(ContractError::Std(_))));
+    }
+}
@@ -1,8 +1,171 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Timestamp};
+use cosmwasm_std::{Addr, Timestamp, Uint128};
 
+use crate::msg::{AuthResource, ExecuteMsg, GroupExecuteMsg, RoleExecuteMsg};
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Config {
+    /// When true, principals in path and role grants must be valid addresses.
+    /// When false, any string is accepted as a principal, which allows for
+    /// app-specific, non-address identifiers.
+    #[serde(default)]
+    pub validate_principals: bool,
+    /// How explicit denials are resolved against allows.
+    #[serde(default)]
+    pub precedence: Precedence,
+    /// When true, role names are slash-delimited namespaces, like paths, and
+    /// are canonicalized the same way (e.g. `team/payments/admin` becomes
+    /// `/team/payments/admin`). This must not change after instantiation.
+    #[serde(default)]
+    pub namespaced_roles: bool,
+    /// Maximum number of roles the ACL may define, if any.
+    pub max_roles: Option<u32>,
+    /// Maximum number of principals to which any one role may be granted, if
+    /// any.
+    pub max_principals_per_role: Option<u32>,
+    /// When true, sensitive queries (Export, Matrix, and DiffPrincipals)
+    /// require a viewer with a valid viewing key. Contract storage itself
+    /// remains readable through raw queries, so this only restricts the
+    /// convenience queries.
+    #[serde(default)]
+    pub private_queries: bool,
+    /// Whether canonical paths carry a leading slash. This must not change
+    /// after instantiation.
+    #[serde(default)]
+    pub path_style: PathStyle,
+    /// When true, decrementing a principal or reference count below zero, due
+    /// to an earlier accounting error, stops at zero with a warning attribute
+    /// rather than failing.
+    #[serde(default)]
+    pub saturating_counts: bool,
+    /// When true, paths are percent-decoded before they're canonicalized, so
+    /// `/docs/%20report` becomes `/docs/-report`. Malformed encodings are
+    /// rejected. This must not change after instantiation.
+    #[serde(default)]
+    pub decode_percent: bool,
+    /// Message prefixed to the reason given when IsAllowed raises an error
+    /// for a failed check, so apps can show a consistent, branded message. The
+    /// specifics of the failure follow it, and every such error, even for
+    /// expired access, is then raised as NotAuthorized.
+    pub denial_message: Option<String>,
+    /// Seconds for which new direct and role grants stay pending before they
+    /// take effect, giving watchers time to object. A grant's TTL still runs
+    /// from the time it's made.
+    pub grant_delay_secs: Option<u32>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum Precedence {
+    /// An active denial at the requested path or any of its ancestors blocks
+    /// access, regardless of any more specific allows.
+    #[default]
+    DenyWins,
+    /// The closest allow or denial to the requested path wins. A denial and an
+    /// allow at the same path resolve to the denial.
+    MostSpecific,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum PathStyle {
+    /// Paths begin with a slash, like `/projects/42`.
+    #[default]
+    Rooted,
+    /// Paths have no leading slash, like `projects/42`.
+    Bare,
+}
+
+/// Kinds of changes a sub-operator may make on the operator's behalf. A
+/// sub-operator can never change the operator, config, viewers, or other
+/// sub-operators, nor the superuser, nor import entries.
 #[cw_serde]
-pub struct Config {}
+#[derive(Default)]
+pub struct ScopedPowers {
+    /// Allow and deny principals direct access to paths, and consume access.
+    #[serde(default)]
+    pub paths: bool,
+    /// Forbid and unforbid principals' access to paths.
+    #[serde(default)]
+    pub denials: bool,
+    /// Grant, revoke, and swap roles.
+    #[serde(default)]
+    pub role_grants: bool,
+    /// Create, clone, and remove roles, and link them to paths.
+    #[serde(default)]
+    pub roles: bool,
+    /// Create groups and manage their members.
+    #[serde(default)]
+    pub groups: bool,
+    /// Set and remove aliases.
+    #[serde(default)]
+    pub aliases: bool,
+}
+
+impl ScopedPowers {
+    /// Whether the given message falls within these powers.
+    pub fn permits(
+        &self,
+        msg: &ExecuteMsg,
+    ) -> bool {
+        match msg {
+            ExecuteMsg::Allow(_) | ExecuteMsg::Deny(_) | ExecuteMsg::ConsumeAccess(_) => self.paths,
+            ExecuteMsg::MakePermanent(msg) => match msg.resource {
+                AuthResource::Path(_) => self.paths,
+                AuthResource::Role(_) => self.role_grants,
+            },
+            // Pruning removes both direct and role grants
+            ExecuteMsg::PruneExpired(_) => self.paths && self.role_grants,
+            ExecuteMsg::Forbid(_) | ExecuteMsg::Unforbid(_) => self.denials,
+            ExecuteMsg::Role(msg) => match msg {
+                RoleExecuteMsg::Grant(_) | RoleExecuteMsg::Revoke(_) | RoleExecuteMsg::Swap(_) => {
+                    self.role_grants
+                },
+                RoleExecuteMsg::Create(_)
+                | RoleExecuteMsg::CreateFromTemplate(_)
+                | RoleExecuteMsg::Clone(_)
+                | RoleExecuteMsg::Allow(_)
+                | RoleExecuteMsg::Deny(_)
+                | RoleExecuteMsg::DenyMany(_)
+                | RoleExecuteMsg::Remove(_) => self.roles,
+            },
+            ExecuteMsg::Group(msg) => match msg {
+                GroupExecuteMsg::Create(_)
+                | GroupExecuteMsg::Add(_)
+                | GroupExecuteMsg::Remove(_) => self.groups,
+            },
+            ExecuteMsg::SetAlias(_) | ExecuteMsg::RemoveAlias(_) => self.aliases,
+            ExecuteMsg::SetOperator(_)
+            | ExecuteMsg::SetConfig(_)
+            | ExecuteMsg::SetViewer(_)
+            | ExecuteMsg::RemoveViewer(_)
+            | ExecuteMsg::SetSubOperator(_)
+            | ExecuteMsg::RemoveSubOperator(_)
+            | ExecuteMsg::SetSuperuser(_)
+            | ExecuteMsg::RemoveSuperuser {}
+            | ExecuteMsg::RenounceOperator(_)
+            | ExecuteMsg::Import(_) => false,
+        }
+    }
+}
+
+/// References to a path, counted by source, so that a path referenced only by
+/// roles can be told apart from one referenced only by direct grants.
+#[cw_serde]
+#[derive(Default)]
+pub struct PathRefCounts {
+    /// Number of principals granted the path directly.
+    pub principals: u32,
+    /// Number of roles linked to the path.
+    pub roles: u32,
+}
+
+impl PathRefCounts {
+    pub fn total(&self) -> u32 {
+        self.principals.saturating_add(self.roles)
+    }
+}
 
 #[cw_serde]
 pub struct AuthRoleInfo {
@@ -10,9 +173,90 @@ pub struct AuthRoleInfo {
     pub created_at: Timestamp,
     pub created_by: Addr,
     pub n_principals: u32,
+    /// Template roles serve as blueprints for CreateFromTemplate.
+    #[serde(default)]
+    pub is_template: bool,
+    /// Number of paths linked to the role.
+    #[serde(default)]
+    pub n_paths: u32,
+    /// Time at which the role's paths or description last changed.
+    #[serde(default)]
+    pub updated_at: Timestamp,
+    /// Weight of the role in weighted authorization checks.
+    #[serde(default = "default_role_weight")]
+    pub weight: u32,
+}
+
+pub fn default_role_weight() -> u32 {
+    1
+}
+
+#[cw_serde]
+pub struct GroupInfo {
+    pub description: Option<String>,
+    pub created_at: Timestamp,
+    pub created_by: Addr,
+    pub n_members: u32,
 }
 
 #[cw_serde]
 pub struct AuthRecord {
     pub expires_at: Option<Timestamp>,
+    /// Time at which the grant becomes active, if not immediately.
+    pub not_before: Option<Timestamp>,
+    /// Number of remaining uses, consumed via ConsumeAccess, if limited.
+    pub max_uses: Option<u32>,
+    /// External condition that must hold at check time for the grant to apply.
+    pub condition: Option<Condition>,
+    /// App-specific data, such as the reason for the grant. This has no
+    /// bearing on authorization.
+    pub metadata: Option<String>,
+    /// If set, the grant covers only the exact path at which it was made and
+    /// not any of its descendants.
+    #[serde(default)]
+    pub no_inherit: bool,
+    /// Role the principal must also hold, unexpired, for a direct grant to
+    /// apply, which layers role-based authorization on top of the grant.
+    pub require_role: Option<String>,
+    pub granted_by: Addr,
+    pub granted_at: Timestamp,
+    /// Block height at which the grant was made.
+    #[serde(default)]
+    pub granted_at_height: u64,
+}
+
+#[cw_serde]
+pub struct DenyRecord {
+    pub expires_at: Option<Timestamp>,
+    /// If set, the denial blocks the path's entire subtree, even under
+    /// most-specific precedence, where allows on descendants would otherwise
+    /// win.
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+#[cw_serde]
+pub enum Comparator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// A smart query against another contract whose numeric result is compared
+/// with an expected value, such as a minimum token balance.
+#[cw_serde]
+pub struct Condition {
+    pub contract: Addr,
+    /// JSON query message, in which each quoted `"{principal}"` is replaced
+    /// with the principal being checked, as a JSON string. A query of another
+    /// ACL must be an IsAllowed check.
+    pub msg: String,
+    /// Field of the response object holding the value. If not given, the
+    /// response itself must be the value.
+    pub field: Option<String>,
+    pub comparator: Comparator,
+    pub value: Uint128,
 }
@@ -1,27 +1,71 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Timestamp;
 
-use crate::client::Operator;
+use crate::{
+    client::Operator,
+    models::{AuthRecord, AuthRoleInfo, Condition, Config, DenyRecord, GroupInfo, ScopedPowers},
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub operator: Option<Operator>,
     pub name: Option<String>,
     pub description: Option<String>,
+    pub config: Option<Config>,
+    /// Roles to create upon instantiation.
+    pub roles: Option<Vec<CreateRoleMsg>>,
+    /// Roles to grant upon instantiation. Each must refer to a role in `roles`.
+    pub grants: Option<Vec<GrantRoleMsg>>,
+    /// If the operator is an ACL, fail unless it responds to an Acl query.
+    pub verify_operator: Option<bool>,
+    /// Prefix of the path through which an ACL operator controls this ACL,
+    /// defaulting to /acls.
+    pub control_prefix: Option<String>,
+    /// Address that passes every authorization check, regardless of grants
+    /// or denials. There is none unless one is given.
+    pub superuser: Option<String>,
 }
 
 #[cw_serde]
 pub enum RoleExecuteMsg {
     /// Initialize a role with optional initial paths and principals.
     Create(CreateRoleMsg),
+    /// Create a role with the paths of a template role, after applying string
+    /// substitutions to each path (e.g. `{tenant}` to `acme`).
+    CreateFromTemplate(CreateFromTemplateMsg),
+    /// Create a role with the same paths as an existing role and, optionally,
+    /// grant it to the same principals.
+    Clone(CloneRoleMsg),
     /// Authorize a principal to a given path.
     Allow(AllowRoleMsg),
     /// Deny a path to an existing role (inverse of Allow).
     Deny(DenyRoleMsg),
+    /// Deny several paths to an existing role at once, skipping any the role
+    /// does not have.
+    DenyMany(DenyManyRoleMsg),
     /// Grant a role to a given principal, allowing the principal to inherit all
     /// paths allowed to the role.
     Grant(GrantRoleMsg),
     // The inverse of Grant.
     Revoke(RevokeRoleMsg),
+    /// Atomically revoke one role from a principal and grant another.
+    Swap(SwapRoleMsg),
+    /// Remove a role along with its paths. A role still granted to any
+    /// principals, or required by any direct grants, is only removed if
+    /// forced, revoking it from them all. Denials via the role, and grants
+    /// requiring it, are removed with it.
+    Remove(RemoveRoleMsg),
+}
+
+#[cw_serde]
+pub enum GroupExecuteMsg {
+    /// Create an empty group of principals. A role granted to the group's
+    /// principal, `group:<name>`, is held by each of its current members.
+    Create(CreateGroupMsg),
+    /// Add a principal to a group.
+    Add(GroupMemberMsg),
+    /// Remove a principal from a group (inverse of Add).
+    Remove(RemoveGroupMemberMsg),
 }
 
 #[cw_serde]
@@ -29,27 +73,195 @@ pub enum ExecuteMsg {
     /// Change the operator of the ACL. This is the contract or account who can
     /// execute the ACL.
     SetOperator(Operator),
+    /// Update the ACL's config. Namespaced roles, the path style, and percent
+    /// decoding cannot be changed, as stored roles and paths depend on them.
+    SetConfig(Config),
     /// Authorize a principal to a given path.
     Allow(AllowMsg),
     /// This is the inverse of Allow.
     Deny(DenyMsg),
+    /// Use up one of the remaining uses of a principal's active, use-limited
+    /// grant to a path, removing the grant once none remain. An alias consumes
+    /// the grant of the principal it's aliased to.
+    ConsumeAccess(ConsumeAccessMsg),
+    /// Remove the expiry of a principal's unexpired grant of a path or role,
+    /// leaving the rest of the grant as is.
+    MakePermanent(MakePermanentMsg),
+    /// Explicitly deny a principal access to a path, overriding allows
+    /// according to the ACL's configured precedence.
+    Forbid(ForbidMsg),
+    /// Remove an explicit denial (inverse of Forbid).
+    Unforbid(UnforbidMsg),
     /// Execute a change pertaining to a role.
     Role(RoleExecuteMsg),
+    /// Execute a change pertaining to a group.
+    Group(GroupExecuteMsg),
+    /// Treat an address as the given principal when checking authorization.
+    SetAlias(SetAliasMsg),
+    /// Remove an address's alias (inverse of SetAlias).
+    RemoveAlias(RemoveAliasMsg),
+    /// Authorize an address to make sensitive queries when private queries are
+    /// enabled, using the given viewing key.
+    SetViewer(SetViewerMsg),
+    /// Revoke a viewer's authorization (inverse of SetViewer).
+    RemoveViewer(RemoveViewerMsg),
+    /// Let an address make the given kinds of changes without being the
+    /// operator, replacing any powers it had before.
+    SetSubOperator(SetSubOperatorMsg),
+    /// Revoke a sub-operator's powers (inverse of SetSubOperator).
+    RemoveSubOperator(RemoveSubOperatorMsg),
+    /// Set the address that passes every authorization check, regardless of
+    /// grants or denials, replacing any existing superuser.
+    SetSuperuser(SetSuperuserMsg),
+    /// Remove the superuser, so that all principals are checked normally.
+    RemoveSuperuser {},
+    /// Permanently remove the operator, freezing the ACL as read-only. Every
+    /// later execute message, including from sub-operators, is rejected.
+    RenounceOperator(RenounceOperatorMsg),
+    /// Remove a batch of expired direct and role grants, scanning from the
+    /// given cursor. The cursor from which to continue, if any, is returned
+    /// in the response's `cursor` attribute as JSON.
+    PruneExpired(PruneExpiredMsg),
+    /// Import entries generated by the Export query. Ref counts and numbers of
+    /// principals per role are rebuilt from the imported data.
+    Import(ImportMsg),
 }
 
 #[cw_serde]
 pub enum QueryMsg {
     /// Get top-level ACL info and metadata.
     Acl {},
+    /// Get counts of entities in the ACL along with any configured limits.
+    Counts {},
     /// List roles pertaining to a given principal or, if not provided, all
     /// roles defined by the ACL.
     Roles { principal: Option<String> },
     /// Get role information from role name.
     Role(String),
+    /// Get role information from role name along with a page of the paths
+    /// linked to the role, starting after the cursor if given.
+    RoleWithPaths {
+        name: String,
+        limit: Option<u16>,
+        cursor: Option<String>,
+    },
+    /// Get role information for each of the given role names. In strict mode,
+    /// any missing role is an error; otherwise, missing roles are omitted.
+    RolesByName {
+        names: Vec<String>,
+        strict: Option<bool>,
+    },
+    /// List roles whose namespaced name is, or is nested under, the given
+    /// namespace prefix (e.g. `/team/payments`).
+    RolesUnder { prefix: String },
     /// List paths autorized to a principal, role, or the ACL as whole.
     Paths(PathsQueryParams),
+    /// Test if a path is referenced by any grant or role in the ACL.
+    PathExists { path: String },
+    /// Get the canonical form of a path, as the ACL stores and checks it.
+    /// Canonicalization is case-sensitive.
+    Canonicalize { path: String },
+    /// List paths authorized to a principal, role, or the ACL as a whole that
+    /// match a glob pattern, in which `*` matches any one segment and `**` any
+    /// number of segments, as in `/projects/*/docs/**`. A cursor is returned
+    /// until every path sharing the pattern's literal prefix has been scanned,
+    /// even if a page holds no matches.
+    GlobPaths {
+        subject: Subject,
+        pattern: String,
+        limit: Option<u16>,
+        cursor: Option<String>,
+    },
+    /// List direct grants made at or after `since` and, if given, before
+    /// `until`, oldest first.
+    RecentGrants {
+        since: Timestamp,
+        until: Option<Timestamp>,
+        limit: Option<u16>,
+        cursor: Option<RecentGrantsCursor>,
+    },
+    /// List a principal's direct and role grants that expire within the given
+    /// number of seconds, soonest first.
+    ExpiringSoon {
+        principal: String,
+        within_secs: u64,
+        limit: Option<u16>,
+    },
+    /// List direct and role grants that have already expired, which remain in
+    /// storage until pruned. Only role grants are listed for a role subject.
+    /// A cursor is returned until every grant has been scanned, even if a page
+    /// holds no expired grants.
+    Expired {
+        subject: Subject,
+        limit: Option<u16>,
+        cursor: Option<ExpiredCursor>,
+    },
+    /// List roles linked to a path and, if inherited, to each of its
+    /// ancestors, from the path itself up to the root.
+    PathRoles {
+        path: String,
+        inherited: Option<bool>,
+        limit: Option<u16>,
+        cursor: Option<PathRolesCursor>,
+    },
     /// Text if a given principal is allowed with respect to one or more paths.
+    /// The check never queries an operator. Conditional grants may query other
+    /// contracts, including other ACLs via IsAllowed, but forwarding between
+    /// ACLs is bounded, so a cycle of ACLs fails rather than looping.
     IsAllowed(IsAllowedParams),
+    /// Like IsAllowed but, instead of a boolean, report for each path the
+    /// ancestor path and source (direct or role) through which it's authorized.
+    /// Policy expressions aren't supported and are rejected.
+    IsAllowedExplain(IsAllowedParams),
+    /// Test if a principal is allowed to the given prefix or any path under
+    /// it, as when discovering whether there's anything it may access there.
+    IsAllowedSubtree { principal: String, prefix: String },
+    /// List every grant and denial to a principal at the given path and each of
+    /// its ancestors, from the most specific path up, including grants that
+    /// are shadowed by others.
+    AuthChain { principal: String, path: String },
+    /// Tell whether a principal's access to a path comes from a direct grant,
+    /// a role, or both, considering every level of the path.
+    AccessSource { principal: String, path: String },
+    /// Resolve a principal's access to a path in full: whether it's allowed,
+    /// the matched path and source of the grant, and when it expires, or the
+    /// reason it's not allowed.
+    Resolve { principal: String, path: String },
+    /// Test if the given address may operate the ACL, delegating to the
+    /// operator ACL if there is one.
+    IsOperator { address: String },
+    /// Follow the chain of operator ACLs, starting with this ACL's operator,
+    /// to diagnose delegation loops.
+    OperatorChain { max_depth: Option<u8> },
+    /// Test which of the given principals are allowed with respect to a path.
+    WhichAllowed {
+        principals: Vec<String>,
+        path: String,
+    },
+    /// Test each of the given principals against each of the given paths,
+    /// returning a row of results per principal, in the order given.
+    Matrix {
+        principals: Vec<String>,
+        paths: Vec<String>,
+        viewer: Option<ViewerAuth>,
+    },
+    /// Compare two principals against each of the given paths.
+    DiffPrincipals {
+        a: String,
+        b: String,
+        paths: Vec<String>,
+        viewer: Option<ViewerAuth>,
+    },
+    /// Export a page of the ACL's roles, role paths, groups, grants, denials,
+    /// and aliases, in a stable order, for backup or migration to another ACL.
+    Export {
+        cursor: Option<ExportCursor>,
+        limit: Option<u16>,
+        viewer: Option<ViewerAuth>,
+    },
+    /// Recompute a batch of stored counts from the data they summarize and
+    /// report any that disagree, resuming from the given cursor.
+    ConsistencyCheck { cursor: Option<ConsistencyCursor> },
 }
 
 #[cw_serde]
@@ -62,13 +274,48 @@ pub enum Subject {
     Principal(String),
 }
 
+#[cw_serde]
+pub enum OrderBy {
+    /// Order by path (the default).
+    Path,
+    /// Soonest-expiring first, with permanent grants last.
+    ExpiryAsc,
+}
+
+/// Paths implied by each of a role's paths to include alongside it.
+#[cw_serde]
+pub enum Expansion {
+    /// Only the role's own paths (the default).
+    None,
+    /// Each path's ancestors, nearest first, through which broader grants
+    /// may exist.
+    Ancestors,
+    /// Each path's descendants that are referenced by grants or roles, since
+    /// descendants aren't otherwise enumerable.
+    Descendants,
+}
+
 #[cw_serde]
 pub struct PathsQueryParams {
     pub subject: Subject,
     pub limit: Option<u16>,
+    /// Inclusive bounds on the paths returned, either raw or canonical.
     pub start: Option<String>,
     pub stop: Option<String>,
+    /// Last path of the previous page, exactly as returned.
     pub cursor: Option<String>,
+    /// Include each path's global ref counts, in total and by source (Acl and
+    /// Role subjects only).
+    pub with_refs: Option<bool>,
+    /// Ordering of the returned paths. Ordering by expiry only considers the
+    /// first 500 paths, by path, within the given bounds, and returns no
+    /// cursor. The response is marked truncated if there were more.
+    pub order_by: Option<OrderBy>,
+    /// Include paths reachable via the principal's roles (Principal subject
+    /// only). This costs an additional read of each role's paths.
+    pub include_roles: Option<bool>,
+    /// Paths implied by each returned path to include (Role subject only).
+    pub expand: Option<Expansion>,
 }
 
 #[cw_serde]
@@ -76,6 +323,41 @@ pub struct AllowMsg {
     pub principal: String,
     pub path: String,
     pub ttl: Option<u32>,
+    /// TTL in the given unit, as an alternative to ttl in seconds.
+    pub ttl_unit: Option<Duration>,
+    /// Time at which the grant becomes active, if not immediately.
+    pub not_before: Option<Timestamp>,
+    /// Number of times the grant may be consumed via ConsumeAccess before it's
+    /// removed, if limited. Must be at least 1.
+    pub max_uses: Option<u32>,
+    /// External condition that must hold whenever the grant is checked.
+    pub condition: Option<Condition>,
+    /// App-specific data to store with the grant, such as a ticket ID.
+    pub metadata: Option<String>,
+    /// Restrict the grant to the path itself, excluding its descendants.
+    pub no_inherit: Option<bool>,
+    /// Role the principal must also hold for the grant to apply.
+    pub require_role: Option<String>,
+}
+
+#[cw_serde]
+pub struct MakePermanentMsg {
+    pub principal: String,
+    pub resource: AuthResource,
+}
+
+#[cw_serde]
+pub struct ConsumeAccessMsg {
+    pub principal: String,
+    pub path: String,
+}
+
+#[cw_serde]
+pub enum Duration {
+    Secs(u32),
+    Mins(u32),
+    Hours(u32),
+    Days(u32),
 }
 
 #[cw_serde]
@@ -83,30 +365,166 @@ pub struct GrantRoleMsg {
     pub principal: String,
     pub role: String,
     pub ttl: Option<u32>,
+    /// TTL in the given unit, as an alternative to ttl in seconds.
+    pub ttl_unit: Option<Duration>,
+    /// Time at which the grant becomes active, if not immediately.
+    pub not_before: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct SwapRoleMsg {
+    pub principal: String,
+    /// Role currently held by the principal, to revoke.
+    pub from: String,
+    /// Role to grant in its place.
+    pub to: String,
+    pub ttl: Option<u32>,
+    /// Allow swapping out a role linked to a path covering the ACL's own
+    /// control path when the ACL is its own operator.
+    pub force: Option<bool>,
 }
 
 #[cw_serde]
 pub struct RevokeRoleMsg {
     pub principal: String,
     pub role: String,
+    /// Allow revoking a role linked to a path covering the ACL's own control
+    /// path from its last holder when the ACL is its own operator.
+    pub force: Option<bool>,
+}
+
+#[cw_serde]
+pub struct RemoveRoleMsg {
+    pub role: String,
+    pub force: Option<bool>,
 }
 
 #[cw_serde]
 pub struct AllowRoleMsg {
     pub role: String,
     pub path: String,
+    pub ttl: Option<u32>,
+    /// Restrict the link to the path itself, excluding its descendants.
+    pub no_inherit: Option<bool>,
 }
 
 #[cw_serde]
 pub struct DenyMsg {
     pub principal: String,
     pub path: String,
+    /// Allow removing a grant that covers the ACL's own control path when the
+    /// ACL is its own operator.
+    pub force: Option<bool>,
+}
+
+#[cw_serde]
+pub struct ImportMsg {
+    pub entries: Vec<ExportEntry>,
+    /// Allow importing into an ACL that already has roles or grants.
+    pub merge: Option<bool>,
+    /// Whether this is the last chunk of the import, which is assumed if not
+    /// given. Set it to false on every earlier chunk of a multi-message import
+    /// so that subsequent chunks are accepted without merge.
+    pub done: Option<bool>,
+}
+
+#[cw_serde]
+pub struct ForbidMsg {
+    pub principal: String,
+    pub path: String,
+    pub ttl: Option<u32>,
+    /// Deny the path only via this role, leaving other grants in effect.
+    pub role: Option<String>,
+    /// Block every descendant of the path as well, regardless of precedence.
+    pub recursive: Option<bool>,
+    /// Allow forbidding a path that covers the ACL's own control path when the
+    /// ACL is its own operator.
+    pub force: Option<bool>,
+}
+
+#[cw_serde]
+pub struct UnforbidMsg {
+    pub principal: String,
+    pub path: String,
+    pub role: Option<String>,
+}
+
+#[cw_serde]
+pub struct SetAliasMsg {
+    pub addr: String,
+    pub principal: String,
+}
+
+#[cw_serde]
+pub struct SetViewerMsg {
+    pub addr: String,
+    pub key: String,
+}
+
+#[cw_serde]
+pub struct RemoveViewerMsg {
+    pub addr: String,
+}
+
+#[cw_serde]
+pub struct SetSubOperatorMsg {
+    pub addr: String,
+    pub powers: ScopedPowers,
+}
+
+#[cw_serde]
+pub struct RemoveSubOperatorMsg {
+    pub addr: String,
+}
+
+#[cw_serde]
+pub struct PruneExpiredMsg {
+    pub limit: Option<u16>,
+    pub cursor: Option<ExpiredCursor>,
+}
+
+#[cw_serde]
+pub struct SetSuperuserMsg {
+    pub addr: String,
+}
+
+#[cw_serde]
+pub struct RenounceOperatorMsg {
+    /// The ACL's own address, confirming that it's meant to be frozen.
+    pub confirm: String,
+}
+
+/// Credentials of a viewer, required by sensitive queries when private queries
+/// are enabled.
+#[cw_serde]
+pub struct ViewerAuth {
+    pub addr: String,
+    pub key: String,
+}
+
+#[cw_serde]
+pub struct RemoveAliasMsg {
+    pub addr: String,
 }
 
 #[cw_serde]
 pub struct DenyRoleMsg {
     pub role: String,
     pub path: String,
+    /// Fail if the role does not have the path. Defaults to true.
+    pub strict: Option<bool>,
+    /// Allow removing a path covering the ACL's own control path from the role
+    /// when the ACL is its own operator.
+    pub force: Option<bool>,
+}
+
+#[cw_serde]
+pub struct DenyManyRoleMsg {
+    pub role: String,
+    pub paths: Vec<String>,
+    /// Allow removing paths covering the ACL's own control path from the role
+    /// when the ACL is its own operator.
+    pub force: Option<bool>,
 }
 
 #[cw_serde]
@@ -125,14 +543,42 @@ pub enum AuthResource {
 pub enum TestRequirement {
     Any,
     All,
+    /// Each path must be granted by roles of the principal whose summed
+    /// weights meet the threshold, which must be at least 1. Direct grants
+    /// carry no weight.
+    Weighted { threshold: u32 },
+}
+
+/// Boolean expression over paths, such as (A and B) or C.
+#[cw_serde]
+pub enum PolicyExpr {
+    Path(String),
+    All(Vec<PolicyExpr>),
+    Any(Vec<PolicyExpr>),
+    Not(Box<PolicyExpr>),
 }
 
 #[cw_serde]
 pub struct IsAllowedParams {
     pub principal: String,
     pub require: Option<TestRequirement>,
+    /// Paths to check, either raw or canonical. Each is canonicalized exactly
+    /// as on write, so `/a b` matches a grant to `/a-b`. With none, an
+    /// ALL check passes vacuously and an ANY check fails.
     pub paths: Vec<String>,
     pub raise: Option<bool>,
+    /// Evaluate authorization as of this time instead of the current block.
+    pub at: Option<Timestamp>,
+    /// Invert the result, so that the test passes only if the principal is
+    /// NOT allowed. When raising, an error is raised if the principal is
+    /// allowed.
+    pub negate: Option<bool>,
+    /// Policy to evaluate instead of paths and require, if given.
+    pub expression: Option<PolicyExpr>,
+    /// Number of ACLs through which conditions have forwarded this check.
+    /// Set by the forwarding ACL, bounding cycles of ACLs querying each
+    /// other; callers leave it unset.
+    pub hops: Option<u8>,
 }
 
 #[cw_serde]
@@ -140,4 +586,167 @@ pub struct CreateRoleMsg {
     pub name: String,
     pub description: Option<String>,
     pub paths: Option<Vec<String>>,
+    pub is_template: Option<bool>,
+    /// Weight of the role in weighted authorization checks, defaulting to 1.
+    pub weight: Option<u32>,
+    /// Principals to grant the role upon creation, each with an optional TTL
+    /// in seconds.
+    pub members: Option<Vec<(String, Option<u32>)>>,
+    /// If the role already exists, update it with any given fields and add
+    /// any of the given paths it lacks, instead of failing.
+    pub upsert: Option<bool>,
+}
+
+#[cw_serde]
+pub struct CreateGroupMsg {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[cw_serde]
+pub struct GroupMemberMsg {
+    pub group: String,
+    pub principal: String,
+}
+
+#[cw_serde]
+pub struct RemoveGroupMemberMsg {
+    pub group: String,
+    pub principal: String,
+    /// Allow removing a member from a group holding a role linked to a path
+    /// covering the ACL's own control path when the ACL is its own operator.
+    pub force: Option<bool>,
+}
+
+#[cw_serde]
+pub struct CloneRoleMsg {
+    pub from: String,
+    pub to: String,
+    /// Also grant the new role to each principal holding the existing role.
+    pub copy_members: bool,
+}
+
+#[cw_serde]
+pub struct CreateFromTemplateMsg {
+    pub name: String,
+    /// Name of a template role whose paths are copied to the new role.
+    pub template: String,
+    /// Pairs of (pattern, replacement) applied in order to each path.
+    pub substitutions: Vec<(String, String)>,
+    pub description: Option<String>,
+}
+
+#[cw_serde]
+pub enum ExportEntry {
+    Role {
+        name: String,
+        info: AuthRoleInfo,
+    },
+    RolePath {
+        role: String,
+        path: String,
+        record: AuthRecord,
+    },
+    Group {
+        name: String,
+        info: GroupInfo,
+    },
+    GroupMember {
+        group: String,
+        principal: String,
+    },
+    PrincipalPath {
+        principal: String,
+        path: String,
+        record: AuthRecord,
+    },
+    PrincipalRole {
+        principal: String,
+        role: String,
+        record: AuthRecord,
+    },
+    Denial {
+        principal: String,
+        path: String,
+        record: DenyRecord,
+    },
+    /// A denial of a path via a role, for one principal alone.
+    RoleDenial {
+        principal: String,
+        path: String,
+        role: String,
+        record: DenyRecord,
+    },
+    Alias {
+        addr: String,
+        principal: String,
+    },
+}
+
+/// Sections of an export, in the order in which they're exported.
+#[cw_serde]
+#[derive(Copy)]
+pub enum ExportSection {
+    Roles,
+    RolePaths,
+    Groups,
+    GroupMembers,
+    PrincipalPaths,
+    PrincipalRoles,
+    Denials,
+    RoleDenials,
+    Aliases,
+}
+
+#[cw_serde]
+pub struct ExportCursor {
+    pub section: ExportSection,
+    /// Key of the last entry exported from the section: the role name for
+    /// roles, the group name for groups, the address for aliases, the
+    /// principal, path, and role for role denials, or both parts of the
+    /// composite key for everything else.
+    pub key: Vec<String>,
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum CountKind {
+    /// A role's number of principals.
+    NPrincipals,
+    /// A path's number of references by grants and roles.
+    PathRefCount,
+    /// A path's number of references by direct grants.
+    PathPrincipalRefs,
+    /// A path's number of references by roles.
+    PathRoleRefs,
+}
+
+#[cw_serde]
+pub struct ConsistencyCursor {
+    pub kind: CountKind,
+    /// Role name or path last checked.
+    pub key: String,
+}
+
+/// Key of the last grant scanned. Direct grants are scanned before role grants.
+#[cw_serde]
+pub struct ExpiredCursor {
+    pub principal: String,
+    /// Path of a direct grant, if not a role grant.
+    pub path: Option<String>,
+    /// Role granted, if not a direct grant.
+    pub role: Option<String>,
+}
+
+#[cw_serde]
+pub struct PathRolesCursor {
+    pub path: String,
+    pub role: String,
+}
+
+#[cw_serde]
+pub struct RecentGrantsCursor {
+    pub granted_at: Timestamp,
+    pub principal: String,
+    pub path: String,
 }
@@ -0,0 +1,120 @@
+use crate::{
+    error::ContractError,
+    models::Precedence,
+    responses::{AccessSourceResponse, AuthSource},
+    state::CONFIG,
+};
+
+use super::{auth_chain::query_auth_chain, is_allowed::try_authorize_path, ReadonlyContext};
+
+/// Report whether a principal's access to a path comes from a direct grant, a
+/// role, or both. Unlike IsAllowed, which stops at the first grant, every level
+/// of the path is considered, so a role grant shadowed by a direct grant (or
+/// vice versa) is still reported.
+pub fn query_access_source(
+    ctx: ReadonlyContext,
+    principal: String,
+    path: String,
+) -> Result<AccessSourceResponse, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let config = CONFIG.load(deps.storage)?;
+
+    // Without access, whatever grants exist are moot.
+    if try_authorize_path(deps, &config, env.block.time, &principal, &path).is_err() {
+        return Ok(AccessSourceResponse::None);
+    }
+
+    let chain = query_auth_chain(ReadonlyContext { deps, env }, principal, path)?;
+    let mut direct: Option<AccessSourceResponse> = None;
+    let mut role: Option<AccessSourceResponse> = None;
+
+    for level in chain.levels.iter() {
+        // Under most-specific precedence, grants above a denial don't apply.
+        if level.denied && config.precedence == Precedence::MostSpecific {
+            break;
+        }
+        for grant in level.grants.iter().filter(|g| g.active) {
+            match &grant.via {
+                AuthSource::Direct if direct.is_none() => {
+                    direct = Some(AccessSourceResponse::Direct {
+                        expires_at: grant.expires_at,
+                    })
+                },
+                AuthSource::Role(name) if role.is_none() => {
+                    role = Some(AccessSourceResponse::Role {
+                        name: name.to_owned(),
+                        expires_at: grant.expires_at,
+                    })
+                },
+                _ => {},
+            }
+        }
+    }
+
+    Ok(match (direct, role) {
+        (Some(_), Some(_)) => AccessSourceResponse::Both,
+        (Some(direct), None) => direct,
+        (None, Some(role)) => role,
+        // Access that comes from neither, as the superuser's does
+        (None, None) => AccessSourceResponse::None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_env;
+
+    use crate::{
+        msg::{AllowMsg, ExecuteMsg, QueryMsg},
+        responses::AccessSourceResponse,
+        testing::{allow, allow_msg, create_role, exec, grant_role, query_as, setup, OPERATOR},
+    };
+
+    #[test]
+    fn access_is_reported_by_source() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(60),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+        create_role(deps.as_mut(), "editors", &["/docs/drafts", "/wiki"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        allow(deps.as_mut(), "bob", "/blog");
+
+        let source = |principal: &str, path: &str| -> AccessSourceResponse {
+            query_as(
+                deps.as_ref(),
+                QueryMsg::AccessSource {
+                    principal: principal.to_owned(),
+                    path: path.to_owned(),
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(source("bob", "/docs"), AccessSourceResponse::None);
+        assert_eq!(
+            source("alice", "/docs/final"),
+            AccessSourceResponse::Direct {
+                expires_at: Some(mock_env().block.time.plus_seconds(60))
+            }
+        );
+        assert_eq!(
+            source("alice", "/wiki/intro"),
+            AccessSourceResponse::Role {
+                name: "editors".to_owned(),
+                expires_at: None
+            }
+        );
+        // The role grant at /docs/drafts is reported despite the direct one above
+        assert_eq!(
+            source("alice", "/docs/drafts/1"),
+            AccessSourceResponse::Both
+        );
+    }
+}
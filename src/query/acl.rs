@@ -1,20 +1,100 @@
 use crate::{
+    client::Operator,
     error::ContractError,
-    models::Config,
     responses::AclResponse,
-    state::{CREATED_AT, CREATED_BY, DESCRIPTION, NAME, OP},
+    state::{
+        CONFIG, CREATED_AT, CREATED_BY, DESCRIPTION, NAME, N_PATHS, N_PRINCIPALS, N_ROLES, OP,
+        SUPERUSER,
+    },
 };
 
 use super::ReadonlyContext;
 
 pub fn query_acl(ctx: ReadonlyContext) -> Result<AclResponse, ContractError> {
     let ReadonlyContext { deps, .. } = ctx;
+    let operator = OP.load(deps.storage)?;
+
+    // An operator with no addresses can never authorize a change
+    let frozen = match &operator {
+        Operator::None => true,
+        Operator::Multi { addrs, .. } => addrs.is_empty(),
+        _ => false,
+    };
+
     Ok(AclResponse {
-        operator: OP.load(deps.storage)?,
+        operator,
         created_by: CREATED_BY.load(deps.storage)?,
         created_at: CREATED_AT.load(deps.storage)?,
         name: NAME.may_load(deps.storage)?,
         description: DESCRIPTION.may_load(deps.storage)?,
-        config: Config {},
+        config: CONFIG.load(deps.storage)?,
+        n_roles: N_ROLES.load(deps.storage)?,
+        n_paths: N_PATHS.may_load(deps.storage)?.unwrap_or_default(),
+        n_principals: N_PRINCIPALS.may_load(deps.storage)?.unwrap_or_default(),
+        frozen,
+        superuser: SUPERUSER.may_load(deps.storage)?,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_env;
+
+    use crate::{
+        msg::{ExecuteMsg, InstantiateMsg, QueryMsg, RenounceOperatorMsg},
+        responses::AclResponse,
+        testing::{
+            allow, create_role, exec, grant_role, instantiate_msg, query_as, setup, setup_with,
+            MockDeps, OPERATOR,
+        },
+    };
+
+    fn acl(deps: &MockDeps) -> AclResponse {
+        query_as(deps.as_ref(), QueryMsg::Acl {}).unwrap()
+    }
+
+    #[test]
+    fn counts_track_roles_paths_and_principals() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs", "/wiki"]);
+        create_role(deps.as_mut(), "viewers", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        grant_role(deps.as_mut(), "alice", "viewers");
+        allow(deps.as_mut(), "bob", "/blog");
+
+        let resp = acl(&deps);
+        assert_eq!((resp.n_roles, resp.n_paths, resp.n_principals), (2, 3, 2));
+        assert!(!resp.frozen);
+    }
+
+    #[test]
+    fn renounced_acl_is_frozen() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::RenounceOperator(RenounceOperatorMsg {
+                confirm: mock_env().contract.address.to_string(),
+            }),
+        )
+        .unwrap();
+        assert!(acl(&deps).frozen);
+    }
+
+    #[test]
+    fn blank_name_and_description_read_as_unset() {
+        let deps = setup_with(InstantiateMsg {
+            name: Some("".to_owned()),
+            description: Some("  ".to_owned()),
+            ..instantiate_msg()
+        });
+        let resp = acl(&deps);
+        assert_eq!((resp.name, resp.description), (None, None));
+
+        let deps = setup_with(InstantiateMsg {
+            name: Some("Docs".to_owned()),
+            ..instantiate_msg()
+        });
+        assert_eq!(acl(&deps).name, Some("Docs".to_owned()));
+    }
+}
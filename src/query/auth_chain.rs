@@ -0,0 +1,148 @@
+use cosmwasm_std::Timestamp;
+
+use crate::{
+    error::ContractError,
+    models::AuthRecord,
+    responses::{AuthChainGrant, AuthChainLevel, AuthChainResponse, AuthSource},
+    state::{CONFIG, PATH_ROLES, PRINCIPAL_PATH_AUTHORIZATIONS, ROLE_PATHS},
+    utils::{resolve_principal, to_configured_path, to_crumbs, to_styled_path_from_crumbs},
+};
+
+use super::{
+    is_allowed::{holds_role, is_denied, load_principal_roles, load_role_exceptions},
+    ReadonlyContext,
+};
+
+/// Diagnostic counterpart to the walk performed by IsAllowed. Rather than stop
+/// at the first matching grant, report every direct and role grant to the
+/// principal at each level of the path.
+pub fn query_auth_chain(
+    ctx: ReadonlyContext,
+    principal: String,
+    path: String,
+) -> Result<AuthChainResponse, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let time = env.block.time;
+    let principal = resolve_principal(deps.storage, &principal);
+
+    let principal_roles = load_principal_roles(deps.storage, &principal);
+
+    let config = CONFIG.load(deps.storage)?;
+    let style = &config.path_style;
+    let path = to_configured_path(&config, &path)?;
+    let excepted_roles = load_role_exceptions(deps.storage, style, time, &principal, &path);
+    let mut crumbs = to_crumbs(&path);
+    let mut levels: Vec<AuthChainLevel> = Vec::with_capacity(crumbs.len());
+
+    while !crumbs.is_empty() {
+        let cannonical_path = to_styled_path_from_crumbs(style, &crumbs);
+        let mut grants: Vec<AuthChainGrant> = Vec::with_capacity(1);
+        let is_exact = cannonical_path == path;
+
+        if let Some(record) =
+            PRINCIPAL_PATH_AUTHORIZATIONS.may_load(deps.storage, (&principal, &cannonical_path))?
+        {
+            grants.push(AuthChainGrant {
+                via: AuthSource::Direct,
+                expires_at: record.expires_at,
+                active: (is_exact || !record.no_inherit)
+                    && is_active(time, &[&record])
+                    && record
+                        .require_role
+                        .as_ref()
+                        .map(|role| holds_role(time, &principal_roles, role))
+                        .unwrap_or(true),
+            });
+        }
+
+        for (role, grant) in principal_roles.iter() {
+            if !PATH_ROLES.has(deps.storage, (&cannonical_path, role)) {
+                continue;
+            }
+            let link = ROLE_PATHS.may_load(deps.storage, (role, &cannonical_path))?;
+            let mut records = vec![grant];
+            records.extend(link.as_ref());
+
+            // The role grants access until either the principal's grant of
+            // the role or the role's link to the path expires.
+            let expires_at = records.iter().filter_map(|r| r.expires_at).min();
+
+            grants.push(AuthChainGrant {
+                via: AuthSource::Role(role.to_owned()),
+                expires_at,
+                active: (is_exact || !link.as_ref().map(|l| l.no_inherit).unwrap_or(false))
+                    && !excepted_roles.contains(role)
+                    && is_active(time, &records),
+            });
+        }
+
+        levels.push(AuthChainLevel {
+            denied: is_denied(deps.storage, time, &principal, &cannonical_path),
+            path: cannonical_path,
+            grants,
+        });
+
+        crumbs.pop();
+    }
+
+    Ok(AuthChainResponse { levels })
+}
+
+/// A grant is active if none of the records it depends on have expired or
+/// have yet to become active.
+pub fn is_active(
+    time: Timestamp,
+    records: &[&AuthRecord],
+) -> bool {
+    records.iter().all(|r| {
+        r.expires_at.map(|t| time < t).unwrap_or(true)
+            && r.not_before.map(|t| time >= t).unwrap_or(true)
+            && r.max_uses != Some(0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        msg::QueryMsg,
+        responses::{AuthChainResponse, AuthSource},
+        testing::{allow, create_role, grant_role, query_as, setup},
+    };
+
+    #[test]
+    fn reports_shadowed_grants_at_every_level() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/projects");
+        create_role(deps.as_mut(), "editors", &["/projects/42"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+
+        let AuthChainResponse { levels } = query_as(
+            deps.as_ref(),
+            QueryMsg::AuthChain {
+                principal: "alice".to_owned(),
+                path: "/projects/42/docs".to_owned(),
+            },
+        )
+        .unwrap();
+
+        let summary: Vec<(&str, Vec<AuthSource>)> = levels
+            .iter()
+            .map(|level| {
+                assert!(!level.denied);
+                assert!(level.grants.iter().all(|g| g.active));
+                (
+                    level.path.as_str(),
+                    level.grants.iter().map(|g| g.via.clone()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/projects/42/docs", vec![]),
+                ("/projects/42", vec![AuthSource::Role("editors".to_owned())]),
+                ("/projects", vec![AuthSource::Direct]),
+            ]
+        );
+    }
+}
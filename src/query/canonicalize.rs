@@ -0,0 +1,57 @@
+use crate::{error::ContractError, utils::to_acl_path};
+
+use super::ReadonlyContext;
+
+pub fn query_canonicalize(
+    ctx: ReadonlyContext,
+    path: String,
+) -> Result<String, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    to_acl_path(deps.storage, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{Config, PathStyle},
+        msg::QueryMsg,
+        testing::{query_as, setup, setup_with_config, MockDeps},
+    };
+
+    fn canonicalize(
+        deps: &MockDeps,
+        path: &str,
+    ) -> String {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::Canonicalize {
+                path: path.to_owned(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn returns_canonical_form() {
+        let deps = setup();
+        for (raw, canonical) in [
+            ("/my docs/q1 report", "/my-docs/q1-report"),
+            ("//docs///a/", "/docs/a"),
+            ("docs", "/docs"),
+            // Case is preserved, so differently cased paths are distinct
+            ("/Docs/README", "/Docs/README"),
+        ] {
+            assert_eq!(canonicalize(&deps, raw), canonical, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn follows_configured_style_and_decoding() {
+        let deps = setup_with_config(Config {
+            path_style: PathStyle::Bare,
+            decode_percent: true,
+            ..Config::default()
+        });
+        assert_eq!(canonicalize(&deps, "/docs/%20report"), "docs/-report");
+    }
+}
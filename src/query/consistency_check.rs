@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::{
+    error::ContractError,
+    models::PathRefCounts,
+    msg::{ConsistencyCursor, CountKind},
+    responses::{ConsistencyCheckResponse, Discrepancy},
+    state::{
+        PATH_REF_COUNTS, PATH_ROLES, PRINCIPAL_PATH_AUTHORIZATIONS, ROLE_INFOS, ROLE_PRINCIPALS,
+    },
+};
+
+use super::ReadonlyContext;
+
+/// Number of roles or paths checked per query.
+const BATCH_SIZE: usize = 50;
+
+/// Check a batch of roles' principal counts or paths' ref counts against
+/// counts recomputed from the underlying maps. Roles are checked first, then
+/// paths. Returns a cursor until every role and path has been checked.
+pub fn query_consistency_check(
+    ctx: ReadonlyContext,
+    cursor: Option<ConsistencyCursor>,
+) -> Result<ConsistencyCheckResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+
+    let (kind, after) = match cursor {
+        Some(ConsistencyCursor { kind, key }) => (kind, Some(key)),
+        None => (CountKind::NPrincipals, None),
+    };
+
+    let (discrepancies, last_key) = match kind {
+        CountKind::NPrincipals => check_role_principal_counts(deps.storage, after)?,
+        CountKind::PathRefCount | CountKind::PathPrincipalRefs | CountKind::PathRoleRefs => {
+            check_path_ref_counts(deps.storage, after)?
+        },
+    };
+
+    // Continue with the same kind if the batch was full; otherwise, move on
+    // from roles to paths or, having checked paths, finish.
+    let cursor = match (last_key, kind) {
+        (Some(key), CountKind::NPrincipals) => Some(ConsistencyCursor { kind, key }),
+        (Some(key), _) => Some(ConsistencyCursor {
+            kind: CountKind::PathRefCount,
+            key,
+        }),
+        (None, CountKind::NPrincipals) => Some(ConsistencyCursor {
+            kind: CountKind::PathRefCount,
+            key: "".to_owned(),
+        }),
+        (None, _) => None,
+    };
+
+    Ok(ConsistencyCheckResponse {
+        discrepancies,
+        cursor,
+    })
+}
+
+/// Compare each role's stored number of principals with the number of
+/// principals actually granted the role.
+fn check_role_principal_counts(
+    store: &dyn Storage,
+    after: Option<String>,
+) -> Result<(Vec<Discrepancy>, Option<String>), ContractError> {
+    let roles = ROLE_INFOS
+        .range(
+            store,
+            after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(BATCH_SIZE)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut discrepancies: Vec<Discrepancy> = vec![];
+
+    for (role, info) in roles.iter() {
+        let computed = ROLE_PRINCIPALS
+            .prefix(role)
+            .keys(store, None, None, Order::Ascending)
+            .count() as u32;
+        if computed != info.n_principals {
+            discrepancies.push(Discrepancy {
+                kind: CountKind::NPrincipals,
+                key: role.to_owned(),
+                stored: info.n_principals,
+                computed,
+            });
+        }
+    }
+
+    let last_key = if roles.len() == BATCH_SIZE {
+        roles.last().map(|(role, _)| role.to_owned())
+    } else {
+        None
+    };
+
+    Ok((discrepancies, last_key))
+}
+
+/// Compare each path's stored ref counts with the number of roles and direct
+/// grants that reference it, reporting each kind of reference separately.
+/// Since direct grants are keyed by principal, this requires reading every
+/// direct grant once per batch.
+fn check_path_ref_counts(
+    store: &dyn Storage,
+    after: Option<String>,
+) -> Result<(Vec<Discrepancy>, Option<String>), ContractError> {
+    let paths = PATH_REF_COUNTS
+        .range(
+            store,
+            after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(BATCH_SIZE)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut computed_counts: BTreeMap<&String, PathRefCounts> = paths
+        .iter()
+        .map(|(path, _)| {
+            let n_roles = PATH_ROLES
+                .prefix(path)
+                .keys(store, None, None, Order::Ascending)
+                .count() as u32;
+            (
+                path,
+                PathRefCounts {
+                    principals: 0,
+                    roles: n_roles,
+                },
+            )
+        })
+        .collect();
+
+    for result in PRINCIPAL_PATH_AUTHORIZATIONS.keys(store, None, None, Order::Ascending) {
+        let (_, path) = result?;
+        if let Some(refs) = computed_counts.get_mut(&path) {
+            refs.principals += 1;
+        }
+    }
+
+    let discrepancies = paths
+        .iter()
+        .flat_map(|(path, stored)| {
+            let computed = computed_counts.get(path).cloned().unwrap_or_default();
+            [
+                (
+                    CountKind::PathPrincipalRefs,
+                    stored.principals,
+                    computed.principals,
+                ),
+                (CountKind::PathRoleRefs, stored.roles, computed.roles),
+            ]
+            .into_iter()
+            .filter(|(_, stored, computed)| stored != computed)
+            .map(|(kind, stored, computed)| Discrepancy {
+                kind,
+                key: path.to_owned(),
+                stored,
+                computed,
+            })
+        })
+        .collect();
+
+    let last_key = if paths.len() == BATCH_SIZE {
+        paths.last().map(|(path, _)| path.to_owned())
+    } else {
+        None
+    };
+
+    Ok((discrepancies, last_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::PathRefCounts,
+        msg::{ConsistencyCursor, CountKind, QueryMsg},
+        responses::{ConsistencyCheckResponse, Discrepancy},
+        state::{PATH_REF_COUNTS, ROLE_INFOS},
+        testing::{allow, create_role, grant_role, query_as, setup, MockDeps},
+    };
+
+    /// Run every page of the check, collecting the discrepancies.
+    fn check_all(deps: &MockDeps) -> Vec<Discrepancy> {
+        let mut discrepancies = vec![];
+        let mut cursor: Option<ConsistencyCursor> = None;
+        loop {
+            let resp: ConsistencyCheckResponse =
+                query_as(deps.as_ref(), QueryMsg::ConsistencyCheck { cursor }).unwrap();
+            discrepancies.extend(resp.discrepancies);
+            match resp.cursor {
+                Some(next) => cursor = Some(next),
+                None => return discrepancies,
+            }
+        }
+    }
+
+    fn setup_grants() -> MockDeps {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        grant_role(deps.as_mut(), "bob", "editors");
+        allow(deps.as_mut(), "carol", "/docs");
+        deps
+    }
+
+    #[test]
+    fn reports_nothing_when_consistent() {
+        let deps = setup_grants();
+        assert_eq!(check_all(&deps), vec![]);
+    }
+
+    #[test]
+    fn reports_seeded_inconsistencies() {
+        let mut deps = setup_grants();
+
+        let role = "editors".to_owned();
+        let mut info = ROLE_INFOS.load(&deps.storage, &role).unwrap();
+        info.n_principals = 5;
+        ROLE_INFOS.save(&mut deps.storage, &role, &info).unwrap();
+        PATH_REF_COUNTS
+            .save(
+                &mut deps.storage,
+                &"/docs".to_owned(),
+                &PathRefCounts {
+                    principals: 0,
+                    roles: 1,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            check_all(&deps),
+            vec![
+                Discrepancy {
+                    kind: CountKind::NPrincipals,
+                    key: "editors".to_owned(),
+                    stored: 5,
+                    computed: 2,
+                },
+                Discrepancy {
+                    kind: CountKind::PathPrincipalRefs,
+                    key: "/docs".to_owned(),
+                    stored: 0,
+                    computed: 1,
+                },
+            ]
+        );
+    }
+}
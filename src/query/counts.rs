@@ -0,0 +1,15 @@
+use crate::{
+    error::ContractError,
+    responses::CountsResponse,
+    state::{CONFIG, N_ROLES},
+};
+
+use super::ReadonlyContext;
+
+pub fn query_counts(ctx: ReadonlyContext) -> Result<CountsResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    Ok(CountsResponse {
+        n_roles: N_ROLES.load(deps.storage)?,
+        max_roles: CONFIG.load(deps.storage)?.max_roles,
+    })
+}
@@ -0,0 +1,97 @@
+use crate::{
+    error::ContractError, msg::ViewerAuth, responses::PathDiff, state::CONFIG, utils::ensure_viewer,
+};
+
+use super::{is_allowed::try_authorize_path, ReadonlyContext};
+
+const MAX_PATHS: usize = 50;
+
+/// Test two principals against the same paths, for pairwise comparison of
+/// their effective permissions.
+pub fn query_diff_principals(
+    ctx: ReadonlyContext,
+    a: String,
+    b: String,
+    paths: Vec<String>,
+    viewer: Option<ViewerAuth>,
+) -> Result<Vec<PathDiff>, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+
+    ensure_viewer(deps.storage, viewer)?;
+
+    if paths.len() > MAX_PATHS {
+        return Err(ContractError::ValidationError {
+            reason: format!("cannot compare more than {} paths", MAX_PATHS),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let time = env.block.time;
+
+    Ok(paths
+        .into_iter()
+        .map(|path| PathDiff {
+            a_allowed: try_authorize_path(deps, &config, time, &a, &path).is_ok(),
+            b_allowed: try_authorize_path(deps, &config, time, &b, &path).is_ok(),
+            path,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_PATHS;
+    use crate::{
+        error::ContractError,
+        msg::QueryMsg,
+        responses::PathDiff,
+        testing::{allow, create_role, grant_role, query_as, setup},
+    };
+
+    fn diff_msg(paths: Vec<String>) -> QueryMsg {
+        QueryMsg::DiffPrincipals {
+            a: "alice".to_owned(),
+            b: "bob".to_owned(),
+            paths,
+            viewer: None,
+        }
+    }
+
+    #[test]
+    fn compares_principals_path_by_path() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs", "/wiki"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        allow(deps.as_mut(), "bob", "/wiki");
+
+        let diffs: Vec<PathDiff> = query_as(
+            deps.as_ref(),
+            diff_msg(vec![
+                "/docs/a".to_owned(),
+                "/wiki".to_owned(),
+                "/blog".to_owned(),
+            ]),
+        )
+        .unwrap();
+        let allowed: Vec<(&str, bool, bool)> = diffs
+            .iter()
+            .map(|d| (d.path.as_str(), d.a_allowed, d.b_allowed))
+            .collect();
+        assert_eq!(
+            allowed,
+            vec![
+                ("/docs/a", true, false),
+                ("/wiki", true, true),
+                ("/blog", false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn number_of_paths_is_capped() {
+        let deps = setup();
+        let paths = (0..=MAX_PATHS).map(|i| format!("/docs/{}", i)).collect();
+        let result = query_as::<Vec<PathDiff>>(deps.as_ref(), diff_msg(paths));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+}
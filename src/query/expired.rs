@@ -0,0 +1,271 @@
+use cosmwasm_std::{Order, Storage, Timestamp};
+use cw_storage_plus::Bound;
+
+use crate::{
+    error::ContractError,
+    models::AuthRecord,
+    msg::{ExpiredCursor, Subject},
+    responses::{ExpiredGrant, ExpiredResponse},
+    state::{PRINCIPAL_PATH_AUTHORIZATIONS, PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_PRINCIPALS},
+    utils::{resolve_principal, to_cannonical_role},
+};
+
+use super::ReadonlyContext;
+
+const MAX_LIMIT: u16 = 500;
+const DEFAULT_LIMIT: u16 = 100;
+
+/// Maximum number of grants read per page, expired or not, which bounds the
+/// cost of a page when few grants have expired.
+const MAX_SCAN: usize = 1000;
+
+pub fn query_expired(
+    ctx: ReadonlyContext,
+    subject: Subject,
+    limit: Option<u16>,
+    cursor: Option<ExpiredCursor>,
+) -> Result<ExpiredResponse, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+    let (grants, cursor) = scan_expired(deps.storage, env.block.time, subject, cursor, limit)?;
+    Ok(ExpiredResponse { grants, cursor })
+}
+
+/// Scan grants after the given cursor for up to `limit` that have expired,
+/// returning them along with the cursor from which to continue, if the scan
+/// stopped before reading every grant.
+pub fn scan_expired(
+    store: &dyn Storage,
+    time: Timestamp,
+    subject: Subject,
+    cursor: Option<ExpiredCursor>,
+    limit: usize,
+) -> Result<(Vec<ExpiredGrant>, Option<ExpiredCursor>), ContractError> {
+    let mut scan = Scan {
+        time,
+        limit,
+        n_scanned: 0,
+        grants: Vec::with_capacity(8),
+        last: None,
+    };
+
+    // Direct grants are scanned first, so a cursor at a role grant means
+    // they've all been scanned.
+    let in_roles = cursor.as_ref().map(|c| c.role.is_some()).unwrap_or(false);
+
+    let stopped = match subject {
+        Subject::Acl => {
+            let (mut direct_after, mut role_after) = (None, None);
+            if let Some(ExpiredCursor {
+                principal,
+                path,
+                role,
+            }) = &cursor
+            {
+                match (path, role) {
+                    (_, Some(role)) => role_after = Some((principal, role)),
+                    (Some(path), None) => direct_after = Some((principal, path)),
+                    (None, None) => {},
+                }
+            }
+            let mut stopped = false;
+            if !in_roles {
+                for result in PRINCIPAL_PATH_AUTHORIZATIONS.range(
+                    store,
+                    direct_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                ) {
+                    let ((principal, path), record) = result?;
+                    if scan.visit(principal, Some(path), None, &record) {
+                        stopped = true;
+                        break;
+                    }
+                }
+            }
+            if !stopped {
+                for result in PRINCIPAL_ROLE_AUTHORIZATIONS.range(
+                    store,
+                    role_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                ) {
+                    let ((principal, role), record) = result?;
+                    if scan.visit(principal, None, Some(role), &record) {
+                        stopped = true;
+                        break;
+                    }
+                }
+            }
+            stopped
+        },
+        Subject::Principal(principal) => {
+            let principal = resolve_principal(store, &principal);
+            let (mut direct_after, mut role_after) = (None, None);
+            if let Some(ExpiredCursor { path, role, .. }) = &cursor {
+                match (path, role) {
+                    (_, Some(role)) => role_after = Some(role),
+                    (Some(path), None) => direct_after = Some(path),
+                    (None, None) => {},
+                }
+            }
+            let mut stopped = false;
+            if !in_roles {
+                for result in PRINCIPAL_PATH_AUTHORIZATIONS.prefix(&principal).range(
+                    store,
+                    direct_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                ) {
+                    let (path, record) = result?;
+                    if scan.visit(principal.to_owned(), Some(path), None, &record) {
+                        stopped = true;
+                        break;
+                    }
+                }
+            }
+            if !stopped {
+                for result in PRINCIPAL_ROLE_AUTHORIZATIONS.prefix(&principal).range(
+                    store,
+                    role_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                ) {
+                    let (role, record) = result?;
+                    if scan.visit(principal.to_owned(), None, Some(role), &record) {
+                        stopped = true;
+                        break;
+                    }
+                }
+            }
+            stopped
+        },
+        Subject::Role(role) => {
+            let role = to_cannonical_role(store, role)?;
+            let after = cursor.as_ref().map(|c| &c.principal);
+            let mut stopped = false;
+            for result in ROLE_PRINCIPALS.prefix(&role).keys(
+                store,
+                after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            ) {
+                let principal = result?;
+                if let Some(record) =
+                    PRINCIPAL_ROLE_AUTHORIZATIONS.may_load(store, (&principal, &role))?
+                {
+                    if scan.visit(principal, None, Some(role.to_owned()), &record) {
+                        stopped = true;
+                        break;
+                    }
+                }
+            }
+            stopped
+        },
+    };
+
+    Ok((scan.grants, if stopped { scan.last } else { None }))
+}
+
+struct Scan {
+    time: Timestamp,
+    limit: usize,
+    n_scanned: usize,
+    grants: Vec<ExpiredGrant>,
+    last: Option<ExpiredCursor>,
+}
+
+impl Scan {
+    /// Read a grant, keeping it if expired. Returns true, without reading the
+    /// grant, if the page is already full or the scan budget spent, so the scan
+    /// only stops with a cursor when there's another grant to continue from.
+    fn visit(
+        &mut self,
+        principal: String,
+        path: Option<String>,
+        role: Option<String>,
+        record: &AuthRecord,
+    ) -> bool {
+        if self.grants.len() == self.limit || self.n_scanned == MAX_SCAN {
+            return true;
+        }
+        self.n_scanned += 1;
+        if let Some(expires_at) = record.expires_at.filter(|t| self.time >= *t) {
+            self.grants.push(ExpiredGrant {
+                principal: principal.clone(),
+                path: path.clone(),
+                role: role.clone(),
+                expires_at,
+            });
+        }
+        self.last = Some(ExpiredCursor {
+            principal,
+            path,
+            role,
+        });
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_env;
+
+    use super::scan_expired;
+    use crate::{
+        msg::{AllowMsg, ExecuteMsg, ExpiredCursor, GrantRoleMsg, RoleExecuteMsg, Subject},
+        responses::ExpiredGrant,
+        testing::{allow_msg, create_role, exec, grant_role_msg, setup, MockDeps, OPERATOR},
+    };
+
+    fn scan(
+        deps: &MockDeps,
+        cursor: Option<ExpiredCursor>,
+        limit: usize,
+    ) -> (Vec<ExpiredGrant>, Option<ExpiredCursor>) {
+        let time = mock_env().block.time.plus_seconds(120);
+        scan_expired(&deps.storage, time, Subject::Acl, cursor, limit).unwrap()
+    }
+
+    #[test]
+    fn last_full_page_has_no_cursor() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        for principal in ["alice", "bob"] {
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Allow(AllowMsg {
+                    ttl: Some(60),
+                    ..allow_msg(principal, "/docs")
+                }),
+            )
+            .unwrap();
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                    ttl: Some(60),
+                    ..grant_role_msg(principal, "editors")
+                })),
+            )
+            .unwrap();
+        }
+
+        // Four expired grants, two direct and two by role, in two full pages
+        let (first, cursor) = scan(&deps, None, 2);
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|g| g.path.is_some()));
+        assert!(cursor.is_some());
+
+        let (second, cursor) = scan(&deps, cursor, 2);
+        assert_eq!(second.len(), 2);
+        assert!(second.iter().all(|g| g.role.is_some()));
+        assert_eq!(cursor, None);
+
+        // All at once, the page is exactly full
+        let (all, cursor) = scan(&deps, None, 4);
+        assert_eq!(all.len(), 4);
+        assert_eq!(cursor, None);
+    }
+}
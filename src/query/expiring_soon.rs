@@ -0,0 +1,171 @@
+use cosmwasm_std::{Order, Timestamp};
+
+use crate::{
+    error::ContractError,
+    models::AuthRecord,
+    responses::{ExpiringGrant, ExpiringSoonResponse},
+    state::{PRINCIPAL_PATH_AUTHORIZATIONS, PRINCIPAL_ROLE_AUTHORIZATIONS},
+    utils::resolve_principal,
+};
+
+use super::ReadonlyContext;
+
+const MAX_LIMIT: u16 = 500;
+const DEFAULT_LIMIT: u16 = 100;
+
+/// Maximum number of grants read, expiring or not, which bounds the cost of
+/// the query for a principal with many grants.
+const MAX_SCAN: usize = 1000;
+
+/// Scan a principal's direct and role grants for those with an expiry between
+/// now and now plus the given number of seconds. Direct grants are scanned
+/// before role grants, up to MAX_SCAN in all.
+pub fn query_expiring_soon(
+    ctx: ReadonlyContext,
+    principal: String,
+    within_secs: u64,
+    limit: Option<u16>,
+) -> Result<ExpiringSoonResponse, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+    let principal = resolve_principal(deps.storage, &principal);
+
+    let now = env.block.time;
+    let until = now.plus_seconds(within_secs);
+    let is_expiring = |record: &AuthRecord| -> Option<Timestamp> {
+        record.expires_at.filter(|t| *t >= now && *t <= until)
+    };
+
+    let direct = PRINCIPAL_PATH_AUTHORIZATIONS
+        .prefix(&principal)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|r| r.map(|(path, record)| (Some(path), None, record)));
+    let roles = PRINCIPAL_ROLE_AUTHORIZATIONS
+        .prefix(&principal)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|r| r.map(|(role, record)| (None, Some(role), record)));
+
+    let mut grants: Vec<ExpiringGrant> = Vec::with_capacity(8);
+    let mut truncated = false;
+
+    for (n_scanned, result) in direct.chain(roles).enumerate() {
+        if n_scanned == MAX_SCAN {
+            truncated = true;
+            break;
+        }
+        let (path, role, record) = result?;
+        if let Some(expires_at) = is_expiring(&record) {
+            grants.push(ExpiringGrant {
+                path,
+                role,
+                expires_at,
+            });
+        }
+    }
+
+    grants.sort_by_key(|g| g.expires_at);
+    grants.truncate(limit);
+
+    Ok(ExpiringSoonResponse { grants, truncated })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_env;
+
+    use super::MAX_SCAN;
+    use crate::{
+        msg::{AllowMsg, ExecuteMsg, GrantRoleMsg, QueryMsg, RoleExecuteMsg},
+        responses::{ExpiringGrant, ExpiringSoonResponse},
+        testing::{
+            allow, allow_msg, create_role, exec, grant_role_msg, query_as, setup, MockDeps,
+            OPERATOR,
+        },
+    };
+
+    fn expiring_within_hour(deps: &MockDeps) -> ExpiringSoonResponse {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::ExpiringSoon {
+                principal: "alice".to_owned(),
+                within_secs: 3_600,
+                limit: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn lists_grants_expiring_within_window() {
+        let mut deps = setup();
+        for (path, ttl) in [
+            ("/soon", Some(30)),
+            ("/later", Some(7_200)),
+            ("/never", None),
+        ] {
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Allow(AllowMsg {
+                    ttl,
+                    ..allow_msg("alice", path)
+                }),
+            )
+            .unwrap();
+        }
+        for (role, ttl) in [("soon", Some(30)), ("later", Some(7_200)), ("never", None)] {
+            create_role(deps.as_mut(), role, &[]);
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                    ttl,
+                    ..grant_role_msg("alice", role)
+                })),
+            )
+            .unwrap();
+        }
+
+        let expires_at = mock_env().block.time.plus_seconds(30);
+        assert_eq!(
+            expiring_within_hour(&deps),
+            ExpiringSoonResponse {
+                grants: vec![
+                    ExpiringGrant {
+                        path: Some("/soon".to_owned()),
+                        role: None,
+                        expires_at,
+                    },
+                    ExpiringGrant {
+                        path: None,
+                        role: Some("soon".to_owned()),
+                        expires_at,
+                    },
+                ],
+                truncated: false,
+            }
+        );
+    }
+
+    #[test]
+    fn scan_is_bounded() {
+        let mut deps = setup();
+        for i in 0..MAX_SCAN {
+            allow(deps.as_mut(), "alice", &format!("/docs/{:04}", i));
+        }
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(30),
+                ..allow_msg("alice", "/soon")
+            }),
+        )
+        .unwrap();
+
+        // The expiring grant sorts after every permanent one, beyond the scan
+        let resp = expiring_within_hour(&deps);
+        assert!(resp.truncated);
+        assert_eq!(resp.grants, vec![]);
+    }
+}
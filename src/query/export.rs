@@ -0,0 +1,321 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::ContractError,
+    msg::{ExportCursor, ExportEntry, ExportSection, ViewerAuth},
+    responses::ExportResponse,
+    state::{
+        ALIASES, GROUP_INFOS, GROUP_MEMBERS, PRINCIPAL_PATH_AUTHORIZATIONS, PRINCIPAL_PATH_DENIALS,
+        PRINCIPAL_ROLE_AUTHORIZATIONS, PRINCIPAL_ROLE_PATH_DENIALS, ROLE_INFOS, ROLE_PATHS,
+    },
+    utils::ensure_viewer,
+};
+
+use super::ReadonlyContext;
+
+const MAX_LIMIT: u16 = 500;
+const DEFAULT_LIMIT: u16 = 100;
+
+const SECTIONS: [ExportSection; 9] = [
+    ExportSection::Roles,
+    ExportSection::RolePaths,
+    ExportSection::Groups,
+    ExportSection::GroupMembers,
+    ExportSection::PrincipalPaths,
+    ExportSection::PrincipalRoles,
+    ExportSection::Denials,
+    ExportSection::RoleDenials,
+    ExportSection::Aliases,
+];
+
+/// Export a page of entries, section by section, resuming from the given
+/// cursor. Entries within each section are ordered by their storage key, so
+/// the export is deterministic.
+pub fn query_export(
+    ctx: ReadonlyContext,
+    cursor: Option<ExportCursor>,
+    limit: Option<u16>,
+    viewer: Option<ViewerAuth>,
+) -> Result<ExportResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+
+    ensure_viewer(deps.storage, viewer)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+    let mut entries: Vec<ExportEntry> = Vec::with_capacity(limit);
+
+    let (first_section, mut after) = match cursor {
+        Some(ExportCursor { section, key }) => (section, Some(key)),
+        None => (ExportSection::Roles, None),
+    };
+
+    // Section and key of each entry read, from which the cursor is built
+    let mut keys: Vec<(ExportSection, Vec<String>)> = Vec::with_capacity(limit + 1);
+
+    for section in SECTIONS.iter().skip_while(|s| **s != first_section) {
+        // Read one extra entry to tell whether there's another page
+        let page = export_section(
+            deps.storage,
+            *section,
+            after.take(),
+            limit + 1 - entries.len(),
+        )?;
+
+        for (key, entry) in page {
+            entries.push(entry);
+            keys.push((*section, key));
+        }
+
+        if entries.len() > limit {
+            break;
+        }
+    }
+
+    // Resume after the last entry returned if there's more
+    let cursor = if entries.len() > limit {
+        entries.truncate(limit);
+        keys.truncate(limit);
+        keys.pop()
+            .map(|(section, key)| ExportCursor { section, key })
+    } else {
+        None
+    };
+
+    Ok(ExportResponse { entries, cursor })
+}
+
+/// Read up to `limit` entries from a section, after the given key, returning
+/// each with its key for use in a cursor.
+fn export_section(
+    store: &dyn Storage,
+    section: ExportSection,
+    after: Option<Vec<String>>,
+    limit: usize,
+) -> Result<Vec<(Vec<String>, ExportEntry)>, ContractError> {
+    Ok(match section {
+        ExportSection::Roles => {
+            let after = match after {
+                Some(key) => Some(parse_key::<1>(key)?[0].to_owned()),
+                None => None,
+            };
+            ROLE_INFOS
+                .range(
+                    store,
+                    after.as_ref().map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|r| {
+                    r.map(|(name, info)| (vec![name.to_owned()], ExportEntry::Role { name, info }))
+                })
+                .collect::<StdResult<Vec<_>>>()?
+        },
+        ExportSection::RolePaths => {
+            export_pairs(store, ROLE_PATHS, after, limit, |role, path, record| {
+                ExportEntry::RolePath { role, path, record }
+            })?
+        },
+        ExportSection::Groups => {
+            let after = match after {
+                Some(key) => Some(parse_key::<1>(key)?[0].to_owned()),
+                None => None,
+            };
+            GROUP_INFOS
+                .range(
+                    store,
+                    after.as_ref().map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|r| {
+                    r.map(|(name, info)| (vec![name.to_owned()], ExportEntry::Group { name, info }))
+                })
+                .collect::<StdResult<Vec<_>>>()?
+        },
+        ExportSection::GroupMembers => {
+            export_pairs(store, GROUP_MEMBERS, after, limit, |group, principal, _| {
+                ExportEntry::GroupMember { group, principal }
+            })?
+        },
+        ExportSection::PrincipalPaths => export_pairs(
+            store,
+            PRINCIPAL_PATH_AUTHORIZATIONS,
+            after,
+            limit,
+            |principal, path, record| ExportEntry::PrincipalPath {
+                principal,
+                path,
+                record,
+            },
+        )?,
+        ExportSection::PrincipalRoles => export_pairs(
+            store,
+            PRINCIPAL_ROLE_AUTHORIZATIONS,
+            after,
+            limit,
+            |principal, role, record| ExportEntry::PrincipalRole {
+                principal,
+                role,
+                record,
+            },
+        )?,
+        ExportSection::Denials => export_pairs(
+            store,
+            PRINCIPAL_PATH_DENIALS,
+            after,
+            limit,
+            |principal, path, record| ExportEntry::Denial {
+                principal,
+                path,
+                record,
+            },
+        )?,
+        ExportSection::RoleDenials => {
+            let after = match after {
+                Some(key) => Some(parse_key::<3>(key)?),
+                None => None,
+            };
+            PRINCIPAL_ROLE_PATH_DENIALS
+                .range(
+                    store,
+                    after.as_ref().map(|[a, b, c]| Bound::exclusive((a, b, c))),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|r| {
+                    r.map(|((principal, path, role), record)| {
+                        (
+                            vec![principal.to_owned(), path.to_owned(), role.to_owned()],
+                            ExportEntry::RoleDenial {
+                                principal,
+                                path,
+                                role,
+                                record,
+                            },
+                        )
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?
+        },
+        ExportSection::Aliases => {
+            let after = match after {
+                Some(key) => Some(Addr::unchecked(&parse_key::<1>(key)?[0])),
+                None => None,
+            };
+            ALIASES
+                .range(
+                    store,
+                    after.as_ref().map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|r| {
+                    r.map(|(addr, principal)| {
+                        (
+                            vec![addr.to_string()],
+                            ExportEntry::Alias {
+                                addr: addr.to_string(),
+                                principal,
+                            },
+                        )
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?
+        },
+    })
+}
+
+/// Read up to `limit` entries from a map with a two-part key.
+fn export_pairs<'a, T, F>(
+    store: &dyn Storage,
+    map: Map<'a, (&'a String, &'a String), T>,
+    after: Option<Vec<String>>,
+    limit: usize,
+    to_entry: F,
+) -> Result<Vec<(Vec<String>, ExportEntry)>, ContractError>
+where
+    T: Serialize + DeserializeOwned,
+    F: Fn(String, String, T) -> ExportEntry,
+{
+    let after = match after {
+        Some(key) => Some(parse_key::<2>(key)?),
+        None => None,
+    };
+    let min_bound = after.as_ref().map(|[a, b]| Bound::exclusive((a, b)));
+    Ok(map
+        .range(store, min_bound, None, Order::Ascending)
+        .take(limit)
+        .map(|r| r.map(|((a, b), v)| (vec![a.to_owned(), b.to_owned()], to_entry(a, b, v))))
+        .collect::<StdResult<Vec<_>>>()?)
+}
+
+fn parse_key<const N: usize>(key: Vec<String>) -> Result<[String; N], ContractError> {
+    key.try_into().map_err(|_| ContractError::ValidationError {
+        reason: format!("export cursor key must have {} parts", N),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        msg::{ExportCursor, ExportEntry, QueryMsg},
+        responses::ExportResponse,
+        testing::{allow, create_role, query_as, setup, MockDeps},
+    };
+
+    fn export_page(
+        deps: &MockDeps,
+        cursor: Option<ExportCursor>,
+        limit: u16,
+    ) -> ExportResponse {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::Export {
+                cursor,
+                limit: Some(limit),
+                viewer: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn pages_of_any_size_concatenate_to_the_same_export() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        create_role(deps.as_mut(), "viewers", &["/docs"]);
+        allow(deps.as_mut(), "alice", "/projects");
+        allow(deps.as_mut(), "bob", "/projects");
+
+        let full = export_page(&deps, None, 100);
+        assert_eq!(full.entries.len(), 6);
+        assert_eq!(full.cursor, None);
+        assert!(matches!(
+            full.entries.first(),
+            Some(ExportEntry::Role { name, .. }) if name == "editors"
+        ));
+
+        // Including sizes that divide the total exactly, which must not leave a
+        // cursor to an empty page
+        for limit in 1..=7 {
+            let mut entries: Vec<ExportEntry> = Vec::new();
+            let mut cursor = None;
+            loop {
+                let page = export_page(&deps, cursor, limit);
+                assert!(!page.entries.is_empty());
+                entries.extend(page.entries);
+                match page.cursor {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+            assert_eq!(entries, full.entries, "limit {}", limit);
+        }
+    }
+}
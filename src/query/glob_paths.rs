@@ -0,0 +1,216 @@
+use cosmwasm_std::{Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::{
+    error::ContractError,
+    msg::Subject,
+    responses::GlobPathsResponse,
+    state::{CONFIG, PATH_REF_COUNTS, PRINCIPAL_PATH_AUTHORIZATIONS, ROLE_PATHS},
+    utils::{
+        resolve_principal, to_cannonical_role, to_configured_path, to_crumbs,
+        to_styled_path_from_crumbs,
+    },
+};
+
+use super::ReadonlyContext;
+
+const MAX_LIMIT: u16 = 500;
+const DEFAULT_LIMIT: u16 = 100;
+
+/// Maximum number of paths read per page, matching or not, which bounds the
+/// cost of a page when few paths match.
+const MAX_SCAN: usize = 1000;
+
+/// Maximum number of `**` segments in a pattern, each of which multiplies the
+/// cost of matching a path.
+const MAX_GLOBSTARS: usize = 4;
+
+pub fn query_glob_paths(
+    ctx: ReadonlyContext,
+    subject: Subject,
+    pattern: String,
+    limit: Option<u16>,
+    cursor: Option<String>,
+) -> Result<GlobPathsResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+    let config = CONFIG.load(deps.storage)?;
+
+    // Patterns are canonicalized like paths, which leaves wildcards intact
+    let pattern = to_configured_path(&config, &pattern)?;
+    let pattern_crumbs = to_crumbs(&pattern);
+    if pattern_crumbs.iter().filter(|c| **c == "**").count() > MAX_GLOBSTARS {
+        return Err(ContractError::ValidationError {
+            reason: format!("pattern may have at most {} ** segments", MAX_GLOBSTARS),
+        });
+    }
+
+    // Every matching path begins with the crumbs before the first wildcard, so
+    // scanning can start there and stop once paths no longer share them.
+    let literal_crumbs: Vec<&str> = pattern_crumbs
+        .iter()
+        .take_while(|c| !c.contains('*'))
+        .copied()
+        .collect();
+    let prefix = to_styled_path_from_crumbs(&config.path_style, &literal_crumbs);
+
+    let min_bound = match &cursor {
+        Some(cursor) => Some(Bound::exclusive(cursor)),
+        None => Some(Bound::inclusive(&prefix)),
+    };
+
+    let keys: Box<dyn Iterator<Item = StdResult<String>>> = match subject {
+        Subject::Acl => PATH_REF_COUNTS.keys(deps.storage, min_bound, None, Order::Ascending),
+        Subject::Role(role) => {
+            let role = to_cannonical_role(deps.storage, role)?;
+            ROLE_PATHS
+                .prefix(&role)
+                .keys(deps.storage, min_bound, None, Order::Ascending)
+        },
+        Subject::Principal(principal) => {
+            let principal = resolve_principal(deps.storage, &principal);
+            PRINCIPAL_PATH_AUTHORIZATIONS.prefix(&principal).keys(
+                deps.storage,
+                min_bound,
+                None,
+                Order::Ascending,
+            )
+        },
+    };
+
+    let mut paths: Vec<String> = Vec::with_capacity(8);
+    let mut last: Option<String> = None;
+    let mut stopped = false;
+
+    for (n_scanned, result) in keys.enumerate() {
+        let path = result?;
+        if !path.starts_with(&prefix) {
+            break;
+        }
+        if paths.len() == limit || n_scanned == MAX_SCAN {
+            stopped = true;
+            break;
+        }
+        if glob_matches(&pattern_crumbs, &to_crumbs(&path)) {
+            paths.push(path.to_owned());
+        }
+        last = Some(path);
+    }
+
+    Ok(GlobPathsResponse {
+        paths,
+        cursor: if stopped { last } else { None },
+    })
+}
+
+/// Match path crumbs against pattern crumbs, where `*` matches any one crumb
+/// and `**` matches any number of crumbs, including none.
+fn glob_matches(
+    pattern: &[&str],
+    crumbs: &[&str],
+) -> bool {
+    match pattern.split_first() {
+        None => crumbs.is_empty(),
+        Some((&"**", rest)) => (0..=crumbs.len()).any(|i| glob_matches(rest, &crumbs[i..])),
+        Some((p, rest)) => match crumbs.split_first() {
+            Some((c, crumbs_rest)) => (*p == "*" || p == c) && glob_matches(rest, crumbs_rest),
+            None => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        msg::{QueryMsg, Subject},
+        responses::GlobPathsResponse,
+        testing::{allow, query_as, setup, MockDeps},
+    };
+
+    // In storage order
+    const SEEDED_PATHS: [&str; 6] = [
+        "/projects",
+        "/projects/1/read",
+        "/projects/1/write",
+        "/projects/2/drafts/read",
+        "/projects/2/read",
+        "/projectsx/1/read",
+    ];
+
+    fn seeded() -> MockDeps {
+        let mut deps = setup();
+        for path in SEEDED_PATHS {
+            allow(deps.as_mut(), "alice", path);
+        }
+        deps
+    }
+
+    fn glob(
+        deps: &MockDeps,
+        pattern: &str,
+        limit: Option<u16>,
+        cursor: Option<String>,
+    ) -> Result<GlobPathsResponse, ContractError> {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::GlobPaths {
+                subject: Subject::Acl,
+                pattern: pattern.to_owned(),
+                limit,
+                cursor,
+            },
+        )
+    }
+
+    #[test]
+    fn single_star_matches_one_segment() {
+        let deps = seeded();
+        let resp = glob(&deps, "/projects/*/read", None, None).unwrap();
+        assert_eq!(resp.paths, vec!["/projects/1/read", "/projects/2/read"]);
+        assert_eq!(resp.cursor, None);
+    }
+
+    #[test]
+    fn double_star_matches_any_depth() {
+        let deps = seeded();
+        let resp = glob(&deps, "/projects/**", None, None).unwrap();
+        assert_eq!(resp.paths, SEEDED_PATHS[..5].to_vec());
+
+        let resp = glob(&deps, "/projects/**/read", None, None).unwrap();
+        assert_eq!(
+            resp.paths,
+            vec![
+                "/projects/1/read",
+                "/projects/2/drafts/read",
+                "/projects/2/read"
+            ]
+        );
+    }
+
+    #[test]
+    fn pages_continue_from_cursor() {
+        let deps = seeded();
+        let mut cursor = None;
+        let mut matched: Vec<String> = vec![];
+        loop {
+            let resp = glob(&deps, "/projects/*/read", Some(1), cursor.take()).unwrap();
+            matched.extend(resp.paths);
+            match resp.cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(matched, vec!["/projects/1/read", "/projects/2/read"]);
+    }
+
+    #[test]
+    fn globstars_are_bounded() {
+        let deps = seeded();
+        glob(&deps, "/**/**/**/**", None, None).unwrap();
+        assert!(matches!(
+            glob(&deps, "/**/**/**/**/**", None, None),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
+}
@@ -1,14 +1,51 @@
-use cosmwasm_std::{Order, Storage, Timestamp};
+use std::collections::{BTreeMap, BTreeSet};
+
+use cosmwasm_std::{
+    from_json, to_json_string, Binary, Deps, Empty, Order, QuerierWrapper, QueryRequest, Storage,
+    Timestamp, Uint128, WasmQuery,
+};
+use serde::{de::IgnoredAny, Deserialize};
 
 use crate::{
+    auth::{self, Check, Outcome},
     error::ContractError,
-    models::AuthRecord,
-    msg::{IsAllowedParams, TestRequirement},
-    state::{PATH_ROLES, PRINCIPAL_PATH_AUTHORIZATIONS, PRINCIPAL_ROLE_AUTHORIZATIONS},
-    utils::{to_cannonical_path, to_cannonical_path_from_crumbs},
+    models::{AuthRecord, Comparator, Condition, Config, DenyRecord, PathStyle, Precedence},
+    msg::{IsAllowedParams, PolicyExpr, QueryMsg, TestRequirement},
+    responses::{AuthFailureKind, AuthMatch, AuthSource},
+    state::{
+        CONFIG, PATH_ROLES, PRINCIPAL_GROUPS, PRINCIPAL_PATH_AUTHORIZATIONS,
+        PRINCIPAL_PATH_DENIALS, PRINCIPAL_ROLE_AUTHORIZATIONS, PRINCIPAL_ROLE_PATH_DENIALS,
+        ROLE_INFOS, ROLE_PATHS, SUPERUSER,
+    },
+    utils::{
+        resolve_principal, to_configured_path, to_crumbs, to_group_principal,
+        to_styled_path_from_crumbs,
+    },
 };
 
-use super::ReadonlyContext;
+use super::{auth_chain::is_active, ReadonlyContext};
+
+/// Maximum nesting of a policy expression passed to IsAllowed.
+const MAX_POLICY_DEPTH: usize = 8;
+
+/// Maximum number of ACLs through which conditions may forward an IsAllowed
+/// check, so that ACLs whose conditions query each other fail cleanly.
+pub const MAX_ACL_HOPS: u8 = 4;
+
+/// Reason why a principal is not authorized to a path.
+pub struct AuthFailure {
+    pub kind: AuthFailureKind,
+    pub reason: String,
+}
+
+impl AuthFailure {
+    pub fn new(
+        kind: AuthFailureKind,
+        reason: String,
+    ) -> Self {
+        Self { kind, reason }
+    }
+}
 
 /// Query that checks if a given principal is authorized to a list of given
 /// roles and/or paths. In the case of paths, we check first for direct
@@ -26,116 +63,1297 @@ pub fn query_is_allowed(
         paths,
         require,
         raise,
+        at,
+        negate,
+        expression,
+        hops,
     } = msg;
 
     // Replace optional args with defaults
     let require = require.unwrap_or(TestRequirement::All);
+    ensure_valid_requirement(&require)?;
     let raise = raise.unwrap_or(false);
+    let negate = negate.unwrap_or(false);
+
+    let config = CONFIG.load(deps.storage)?;
+    let time = at.unwrap_or(env.block.time);
+    let hops = hops.unwrap_or(0);
 
     // Storage for error messages generated below
     let mut error_msgs: Vec<String> = Vec::with_capacity(paths.len());
 
-    // Check if principal has authorization for each role or path provided.
-    for p in paths.iter() {
-        // Return a result containing a error message string in an Err if not
-        // authorized to the given role or path.
-        if let Err(error_msg) = try_authorize_path(deps.storage, env.block.time, &principal, &p) {
-            // If we require ALL checks to pass, fail if we've got an error
-            if require == TestRequirement::All {
-                if raise {
-                    return Err(ContractError::NotAuthorized { reason: error_msg });
+    // Error for failing the aggregate auth check, if it fails
+    let mut failure: Option<ContractError> = None;
+
+    // A policy expression, if given, takes the place of paths and require.
+    if let Some(expr) = &expression {
+        if !eval_policy(deps, &config, time, &principal, expr, hops, 0)? {
+            failure = Some(ContractError::NotAuthorized {
+                reason: format!("{} does not satisfy policy", principal),
+            });
+        }
+    } else {
+        // Check if principal has authorization for each role or path provided.
+        for p in paths.iter() {
+            // Return a result containing a error message string in an Err if not
+            // authorized to the given role or path.
+            let result = match require {
+                TestRequirement::Weighted { threshold } => {
+                    try_authorize_path_weighted(deps, &config, time, &principal, p, threshold)
+                        .map(|_| ())
+                },
+                _ => {
+                    try_authorize_path_at_hop(deps, &config, time, &principal, p, hops).map(|_| ())
+                },
+            };
+            if let Err(AuthFailure {
+                kind,
+                reason: error_msg,
+            }) = result
+            {
+                // If we require ALL checks to pass, fail if we've got an error.
+                // Every path must likewise meet a weight threshold.
+                if require != TestRequirement::Any {
+                    failure = Some(match kind {
+                        AuthFailureKind::Expired => ContractError::Expired {
+                            principal: principal.to_owned(),
+                            target: to_configured_path(&config, p).unwrap_or_else(|_| p.to_owned()),
+                        },
+                        _ => ContractError::NotAuthorized { reason: error_msg },
+                    });
+                    break;
                 } else {
-                    return Ok(false);
+                    error_msgs.push(error_msg)
                 }
-            } else {
-                error_msgs.push(error_msg)
             }
         }
-    }
 
-    // If we're here, it means that the test mode is ANY, implying that all we
-    // require is a single test to pass. If none have passed, however, we fail
-    // the aggregate auth check.
-    if error_msgs.len() == paths.len() {
-        if raise {
-            return Err(ContractError::NotAuthorized {
+        // In ANY mode, all we require is a single test to pass. If none have
+        // passed, however, we fail the aggregate auth check.
+        if require == TestRequirement::Any && error_msgs.len() == paths.len() {
+            failure = Some(ContractError::NotAuthorized {
                 reason: error_msgs.join(", "),
             });
-        } else {
-            return Ok(false);
         }
     }
 
-    // Principal is authorized!
-    Ok(true)
+    // When negated, the check fails only if the principal is authorized.
+    if negate {
+        failure = match failure {
+            Some(_) => None,
+            None => Some(ContractError::NotAuthorized {
+                reason: match expression {
+                    Some(_) => format!("{} satisfies policy", principal),
+                    None => format!("{} is allowed to {}", principal, paths.join(", ")),
+                },
+            }),
+        };
+    }
+
+    // A configured denial message prefixes every raised failure, with expired
+    // access then reported as NotAuthorized, its specifics following.
+    let denial_message = config.denial_message.as_deref().filter(|m| !m.is_empty());
+    match (failure, denial_message) {
+        (Some(ContractError::NotAuthorized { reason }), Some(message)) if raise => {
+            Err(ContractError::NotAuthorized {
+                reason: format!("{}: {}", message, reason),
+            })
+        },
+        (Some(err), Some(message)) if raise => Err(ContractError::NotAuthorized {
+            reason: format!("{}: {}", message, err),
+        }),
+        (Some(err), None) if raise => Err(err),
+        (Some(_), _) => Ok(false),
+        (None, _) => Ok(true),
+    }
+}
+
+/// Recursively evaluate a policy expression, where each path is authorized as
+/// by try_authorize_path. Nesting is limited to bound the gas consumed.
+fn eval_policy(
+    deps: Deps,
+    config: &Config,
+    time: Timestamp,
+    principal: &str,
+    expr: &PolicyExpr,
+    hops: u8,
+    depth: usize,
+) -> Result<bool, ContractError> {
+    if depth > MAX_POLICY_DEPTH {
+        return Err(ContractError::ValidationError {
+            reason: format!(
+                "policy cannot be nested more than {} levels",
+                MAX_POLICY_DEPTH
+            ),
+        });
+    }
+    Ok(match expr {
+        PolicyExpr::Path(path) => {
+            try_authorize_path_at_hop(deps, config, time, principal, path, hops).is_ok()
+        },
+        PolicyExpr::All(exprs) => {
+            for e in exprs.iter() {
+                if !eval_policy(deps, config, time, principal, e, hops, depth + 1)? {
+                    return Ok(false);
+                }
+            }
+            true
+        },
+        PolicyExpr::Any(exprs) => {
+            for e in exprs.iter() {
+                if eval_policy(deps, config, time, principal, e, hops, depth + 1)? {
+                    return Ok(true);
+                }
+            }
+            false
+        },
+        PolicyExpr::Not(e) => !eval_policy(deps, config, time, principal, e, hops, depth + 1)?,
+    })
 }
 
 /// First, ensure principal is authorized to the given path directly; however,
 /// if there is no direct authorization, first check if prinicipal is authorized
 /// transitively through any inherited roles. If not, then we return an error.
-fn try_authorize_path(
-    store: &dyn Storage,
+/// On success, the ancestor path and source of the winning authorization are
+/// returned. Explicit denials are resolved according to the configured
+/// precedence. An aliased address is authorized as its aliased principal.
+pub fn try_authorize_path(
+    deps: Deps,
+    config: &Config,
     time: Timestamp,
-    principal: &String,
-    path: &String,
-) -> Result<(), String> {
-    let mut crumbs: Vec<&str> = path.trim_matches('/').split("/").collect();
+    principal: &str,
+    path: &str,
+) -> Result<AuthMatch, AuthFailure> {
+    try_authorize_path_at_hop(deps, config, time, principal, path, 0)
+}
+
+/// Same as try_authorize_path, for a check forwarded through the given number
+/// of ACLs by conditions.
+pub fn try_authorize_path_at_hop(
+    deps: Deps,
+    config: &Config,
+    time: Timestamp,
+    principal: &str,
+    path: &str,
+    hops: u8,
+) -> Result<AuthMatch, AuthFailure> {
+    let store = deps.storage;
+    let is_superuser = is_superuser(store, principal);
+    let principal = &resolve_principal(store, principal);
+    let style = &config.path_style;
+    let path = to_auth_path(config, path)?;
+
+    // The superuser is authorized to everything, regardless of grants or
+    // denials.
+    if is_superuser {
+        return Ok(AuthMatch {
+            matched_path: path,
+            via: AuthSource::Superuser,
+        });
+    }
+
+    ensure_not_blocked(store, config, time, principal, &path)?;
 
-    // Iterate from full path up the tree of parent paths so that the most
+    // Roles held by the principal, which is typically a much smaller set than
+    // the roles attached to any given path, less any through which the
+    // principal has been individually denied the path.
+    let excepted_roles = load_role_exceptions(store, style, time, principal, &path);
+    let principal_roles: Vec<(String, AuthRecord)> = load_principal_roles(store, principal)
+        .into_iter()
+        .filter(|(role, _)| !excepted_roles.contains(role))
+        .collect();
+
+    // Walk from the full path up the tree of parent paths so that the most
     // specific set of authorization parameters "overrides" the parameters of
-    // its parents.
-    while !crumbs.is_empty() {
-        let cannonical_path = to_cannonical_path_from_crumbs(&crumbs);
-
-        let maybe_assignment = PRINCIPAL_PATH_AUTHORIZATIONS
-            .load(store, (principal, &cannonical_path))
-            .ok();
-
-        // If there's an auth record for principal to the path directly, ensure
-        // that it is valid here.
-        if let Some(assignment) = maybe_assignment {
-            if let Some(expiry) = assignment.expires_at {
-                if time >= expiry {
-                    return Err(format!(
-                        "{} access to {} has expired",
-                        principal, cannonical_path
-                    ));
-                }
+    // its parents. Under most-specific precedence, a denial at any level
+    // overrides any allows at that level or above.
+    let most_specific = config.precedence == Precedence::MostSpecific;
+    let principal_roles = &principal_roles;
+    let outcome = auth::authorize(
+        &path,
+        |level| most_specific && is_denied(store, time, principal, &level.to_owned()),
+        |level, is_exact| {
+            // Grants made with no_inherit only cover the exact path requested.
+            match PRINCIPAL_PATH_AUTHORIZATIONS
+                .load(store, (principal, &level.to_owned()))
+                .ok()
+                .filter(|a| is_exact || !a.no_inherit)
+            {
+                Some(assignment) => check_direct_grant(
+                    deps,
+                    time,
+                    principal,
+                    principal_roles,
+                    level,
+                    &assignment,
+                    hops,
+                ),
+                None => Check::Absent,
             }
-            return Ok(()); // authorized
-        } else {
+        },
+        move |level, is_exact| {
             // Otherwise, check for authorization via any roles inherited by
-            // prinicipal before erroring out.
-            let roles: Vec<String> = PATH_ROLES
-                .prefix(&cannonical_path)
-                .keys(store, None, None, Order::Ascending)
-                .map(|r| r.unwrap())
-                .collect();
-
-            // For any roles assigned this path, check if prinicap has it and
-            // the assignment the role hasn't expired.
-            for role in roles {
-                if let Some(AuthRecord { expires_at: expiry }) = PRINCIPAL_ROLE_AUTHORIZATIONS
-                    .may_load(store, (principal, &role))
-                    .unwrap_or(None)
-                {
-                    if let Some(expiry) = expiry {
-                        if time >= expiry {
-                            return Err(format!("{} role {} has expired", principal, role));
-                        }
-                    } else {
-                        return Ok(()); // authorized
-                    }
+            // prinicipal. For any of the principal's roles assigned this
+            // path, check that neither the assignment of the role nor the
+            // role's link to the path has expired.
+            let level = level.to_owned();
+            principal_roles.iter().filter_map(move |(role, grant)| {
+                if !PATH_ROLES.has(store, (&level, role)) {
+                    return None;
+                }
+                let link = ROLE_PATHS.may_load(store, (role, &level)).unwrap_or(None);
+                if !is_exact && link.as_ref().map(|l| l.no_inherit).unwrap_or(false) {
+                    return None;
                 }
+                Some((
+                    role.to_owned(),
+                    check_role_grant(time, principal, role, &level, grant, link.as_ref()),
+                ))
+            })
+        },
+    );
+
+    match outcome {
+        Outcome::Allowed {
+            path: matched_path,
+            role,
+        } => Ok(AuthMatch {
+            matched_path,
+            via: match role {
+                Some(role) => AuthSource::Role(role),
+                None => AuthSource::Direct,
+            },
+        }),
+        Outcome::Denied { path: denied_path } => Err(AuthFailure::new(
+            AuthFailureKind::Denied,
+            format!("{} denied access to {}", principal, denied_path),
+        )),
+        Outcome::Invalid { error, .. } => Err(error),
+        Outcome::NotGranted => Err(AuthFailure::new(
+            AuthFailureKind::NotGranted,
+            format!("{} not authorized to {}", principal, path),
+        )),
+    }
+}
+
+/// Ensure a principal's direct grant of a path is usable now, evaluating its
+/// condition, if any.
+fn check_direct_grant(
+    deps: Deps,
+    time: Timestamp,
+    principal: &str,
+    principal_roles: &[(String, AuthRecord)],
+    cannonical_path: &str,
+    assignment: &AuthRecord,
+    hops: u8,
+) -> Check<AuthFailure> {
+    if let Some(expiry) = assignment.expires_at {
+        if time >= expiry {
+            return Check::Invalid(AuthFailure::new(
+                AuthFailureKind::Expired,
+                format!("{} access to {} has expired", principal, cannonical_path),
+            ));
+        }
+    }
+    if let Some(not_before) = assignment.not_before {
+        if time < not_before {
+            return Check::Invalid(AuthFailure::new(
+                AuthFailureKind::NotYetActive,
+                format!("{} access to {} not yet active", principal, cannonical_path),
+            ));
+        }
+    }
+    if assignment.max_uses == Some(0) {
+        return Check::Invalid(AuthFailure::new(
+            AuthFailureKind::Exhausted,
+            format!(
+                "{} access to {} has no uses left",
+                principal, cannonical_path
+            ),
+        ));
+    }
+    if let Some(role) = &assignment.require_role {
+        if !holds_role(time, principal_roles, role) {
+            return Check::Invalid(AuthFailure::new(
+                AuthFailureKind::NotGranted,
+                format!(
+                    "{} access to {} requires role {}",
+                    principal, cannonical_path, role
+                ),
+            ));
+        }
+    }
+    // Only direct grants may be conditional, so at most one condition is
+    // evaluated, via a single cross-contract query, per path.
+    if let Some(condition) = &assignment.condition {
+        if let Err(reason) = check_condition(&deps.querier, principal, condition, hops) {
+            return Check::Invalid(AuthFailure::new(
+                AuthFailureKind::ConditionFailed,
+                format!(
+                    "{} access to {} is conditional: {}",
+                    principal, cannonical_path, reason
+                ),
+            ));
+        }
+    }
+    Check::Granted
+}
+
+/// Check whether the principal currently holds the given role, through an
+/// active grant made to the principal or one of its groups.
+pub fn holds_role(
+    time: Timestamp,
+    principal_roles: &[(String, AuthRecord)],
+    role: &str,
+) -> bool {
+    principal_roles
+        .iter()
+        .any(|(r, grant)| r == role && is_active(time, &[grant]))
+}
+
+/// Ensure neither a principal's grant of a role nor the role's link to a path
+/// has expired, and that the grant is active.
+fn check_role_grant(
+    time: Timestamp,
+    principal: &str,
+    role: &str,
+    cannonical_path: &str,
+    grant: &AuthRecord,
+    link: Option<&AuthRecord>,
+) -> Check<AuthFailure> {
+    if let Some(expiry) = grant.expires_at {
+        if time >= expiry {
+            return Check::Invalid(AuthFailure::new(
+                AuthFailureKind::Expired,
+                format!("{} role {} has expired", principal, role),
+            ));
+        }
+    }
+    if let Some(not_before) = grant.not_before {
+        if time < not_before {
+            return Check::Invalid(AuthFailure::new(
+                AuthFailureKind::NotYetActive,
+                format!("{} role {} not yet active", principal, role),
+            ));
+        }
+    }
+    if let Some(AuthRecord {
+        expires_at: Some(expiry),
+        ..
+    }) = link
+    {
+        if time >= *expiry {
+            return Check::Invalid(AuthFailure::new(
+                AuthFailureKind::Expired,
+                format!("role {} access to {} has expired", role, cannonical_path),
+            ));
+        }
+    }
+    Check::Granted
+}
+
+/// Reject a weighted requirement with a zero threshold, which any principal
+/// would meet without holding a single role.
+pub fn ensure_valid_requirement(require: &TestRequirement) -> Result<(), ContractError> {
+    if *require == (TestRequirement::Weighted { threshold: 0 }) {
+        return Err(ContractError::ValidationError {
+            reason: "weighted threshold must be at least 1".to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Sum the weights of the principal's roles that grant the given path, at the
+/// path itself or through an ancestor, counting each role once. Authorization
+/// succeeds, returning the sum, if it meets the threshold. Direct grants carry
+/// no weight, while denials apply as they do in try_authorize_path.
+pub fn try_authorize_path_weighted(
+    deps: Deps,
+    config: &Config,
+    time: Timestamp,
+    principal: &str,
+    path: &str,
+    threshold: u32,
+) -> Result<u32, AuthFailure> {
+    let store = deps.storage;
+    let is_superuser = is_superuser(store, principal);
+    let principal = &resolve_principal(store, principal);
+    let style = &config.path_style;
+    let path = to_auth_path(config, path)?;
+
+    // The superuser meets any threshold
+    if is_superuser {
+        return Ok(u32::MAX);
+    }
+
+    ensure_not_blocked(store, config, time, principal, &path)?;
+
+    let excepted_roles = load_role_exceptions(store, style, time, principal, &path);
+    let principal_roles: Vec<(String, AuthRecord)> = load_principal_roles(store, principal)
+        .into_iter()
+        .filter(|(role, _)| !excepted_roles.contains(role))
+        .collect();
+
+    let mut granting_roles: BTreeSet<&String> = BTreeSet::new();
+    let mut crumbs = to_crumbs(&path);
+
+    while !crumbs.is_empty() {
+        let cannonical_path = to_styled_path_from_crumbs(style, &crumbs);
+
+        // Under most-specific precedence, a denial here overrides any roles
+        // at this level or above.
+        if config.precedence == Precedence::MostSpecific
+            && is_denied(store, time, principal, &cannonical_path)
+        {
+            break;
+        }
+
+        let is_exact = cannonical_path == path;
+
+        for (role, grant) in principal_roles.iter() {
+            if granting_roles.contains(role) || !PATH_ROLES.has(store, (&cannonical_path, role)) {
+                continue;
+            }
+            let link = ROLE_PATHS
+                .may_load(store, (role, &cannonical_path))
+                .unwrap_or(None);
+            if !is_exact && link.as_ref().map(|l| l.no_inherit).unwrap_or(false) {
+                continue;
+            }
+            let mut records = vec![grant];
+            records.extend(link.as_ref());
+            if is_active(time, &records) {
+                granting_roles.insert(role);
             }
         }
 
         crumbs.pop();
     }
 
-    Err(format!(
-        "{} not authorized to {}",
-        principal,
-        to_cannonical_path(path)
-    ))
+    let weight = granting_roles
+        .iter()
+        .filter_map(|role| ROLE_INFOS.may_load(store, role).ok().flatten())
+        .fold(0u32, |sum, info| sum.saturating_add(info.weight));
+
+    if weight < threshold {
+        return Err(AuthFailure::new(
+            AuthFailureKind::NotGranted,
+            format!(
+                "{} roles granting {} have weight {}, short of {}",
+                principal, path, weight, threshold
+            ),
+        ));
+    }
+
+    Ok(weight)
+}
+
+/// Canonicalize a path the same way it's canonicalized on write, so the keys
+/// we look up match the keys stored.
+fn to_auth_path(
+    config: &Config,
+    path: &str,
+) -> Result<String, AuthFailure> {
+    to_configured_path(config, path)
+        .map_err(|err| AuthFailure::new(AuthFailureKind::NotGranted, err.to_string()))
+}
+
+/// Check whether the given address is the ACL's superuser.
+fn is_superuser(
+    store: &dyn Storage,
+    principal: &str,
+) -> bool {
+    SUPERUSER
+        .may_load(store)
+        .unwrap_or(None)
+        .map(|superuser| superuser.as_str() == principal)
+        .unwrap_or(false)
+}
+
+/// Under deny-wins precedence, an active denial at any level blocks access
+/// outright, as does a recursive denial under any precedence, so check all
+/// ancestors for one before looking at any allows.
+fn ensure_not_blocked(
+    store: &dyn Storage,
+    config: &Config,
+    time: Timestamp,
+    principal: &String,
+    path: &str,
+) -> Result<(), AuthFailure> {
+    let deny_wins = config.precedence == Precedence::DenyWins;
+    let mut crumbs = to_crumbs(path);
+    while !crumbs.is_empty() {
+        let cannonical_path = to_styled_path_from_crumbs(&config.path_style, &crumbs);
+        if let Some(denial) = load_active_denial(store, time, principal, &cannonical_path) {
+            if deny_wins || denial.recursive {
+                return Err(AuthFailure::new(
+                    AuthFailureKind::Denied,
+                    format!("{} denied access to {}", principal, cannonical_path),
+                ));
+            }
+        }
+        crumbs.pop();
+    }
+    Ok(())
+}
+
+/// Check if principal has an active (unexpired) explicit denial for exactly the
+/// given cannonical path.
+pub fn is_denied(
+    store: &dyn Storage,
+    time: Timestamp,
+    principal: &String,
+    cannonical_path: &String,
+) -> bool {
+    load_active_denial(store, time, principal, cannonical_path).is_some()
+}
+
+/// Load the principal's explicit denial for exactly the given cannonical path,
+/// if there is one and it's active (unexpired).
+fn load_active_denial(
+    store: &dyn Storage,
+    time: Timestamp,
+    principal: &String,
+    cannonical_path: &String,
+) -> Option<DenyRecord> {
+    PRINCIPAL_PATH_DENIALS
+        .may_load(store, (principal, cannonical_path))
+        .ok()
+        .flatten()
+        .filter(|denial| {
+            denial
+                .expires_at
+                .map(|expiry| time < expiry)
+                .unwrap_or(true)
+        })
+}
+
+/// Load the roles through which a principal has an active denial of the given
+/// cannonical path or any of its ancestors.
+pub fn load_role_exceptions(
+    store: &dyn Storage,
+    style: &PathStyle,
+    time: Timestamp,
+    principal: &str,
+    cannonical_path: &str,
+) -> Vec<String> {
+    let principal = principal.to_owned();
+    let mut crumbs = to_crumbs(cannonical_path);
+    let mut roles: Vec<String> = Vec::new();
+    while !crumbs.is_empty() {
+        let path = to_styled_path_from_crumbs(style, &crumbs);
+        roles.extend(
+            PRINCIPAL_ROLE_PATH_DENIALS
+                .prefix((&principal, &path))
+                .range(store, None, None, Order::Ascending)
+                .filter_map(|r| r.ok())
+                .filter(|(_, denial)| denial.expires_at.map(|t| time < t).unwrap_or(true))
+                .map(|(role, _)| role),
+        );
+        crumbs.pop();
+    }
+    roles
+}
+
+/// Load the roles granted to a principal, either directly or via any of the
+/// groups to which the principal currently belongs.
+pub fn load_principal_roles(
+    store: &dyn Storage,
+    principal: &str,
+) -> Vec<(String, AuthRecord)> {
+    let mut principals = vec![principal.to_owned()];
+    principals.extend(
+        PRINCIPAL_GROUPS
+            .prefix(&principal.to_owned())
+            .keys(store, None, None, Order::Ascending)
+            .filter_map(|r| r.ok())
+            .map(|group| to_group_principal(&group)),
+    );
+    principals
+        .iter()
+        .flat_map(|p| {
+            PRINCIPAL_ROLE_AUTHORIZATIONS
+                .prefix(p)
+                .range(store, None, None, Order::Ascending)
+                .filter_map(|r| r.ok())
+        })
+        .collect()
+}
+
+/// Value of a field in a condition's response, where fields other than the
+/// one compared needn't hold a Uint128.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ConditionField {
+    Value(Uint128),
+    Other(IgnoredAny),
+}
+
+/// Build the condition's query, with each quoted `"{principal}"` placeholder
+/// replaced by the principal as a JSON string, so that it can't alter the
+/// structure of the query. A check forwarded to another ACL carries the number
+/// of hops so far, failing once it would exceed MAX_ACL_HOPS. Other ACL
+/// queries can't carry it, so conditions may not make them.
+fn condition_request(
+    principal: &str,
+    condition: &Condition,
+    hops: u8,
+) -> Result<QueryRequest<Empty>, String> {
+    let principal = to_json_string(principal).map_err(|e| e.to_string())?;
+    let mut msg = condition.msg.replace("\"{principal}\"", &principal);
+
+    if let Ok(acl_msg) = from_json::<QueryMsg>(msg.as_bytes()) {
+        let forwarded = match acl_msg {
+            QueryMsg::IsAllowed(params) if hops < MAX_ACL_HOPS => {
+                QueryMsg::IsAllowed(IsAllowedParams {
+                    hops: Some(hops + 1),
+                    ..params
+                })
+            },
+            QueryMsg::IsAllowed(_) => {
+                return Err(format!("check exceeds {} hops between ACLs", MAX_ACL_HOPS))
+            },
+            _ => return Err("conditions can only query an ACL via IsAllowed".to_owned()),
+        };
+        msg = to_json_string(&forwarded).map_err(|e| e.to_string())?;
+    }
+
+    Ok(QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: condition.contract.to_string(),
+        msg: Binary::from(msg.as_bytes()),
+    }))
+}
+
+/// Query the condition's contract and compare the resulting value with the
+/// expected value, returning the reason if the condition doesn't hold.
+fn check_condition(
+    querier: &QuerierWrapper,
+    principal: &str,
+    condition: &Condition,
+    hops: u8,
+) -> Result<(), String> {
+    let request = condition_request(principal, condition, hops)?;
+
+    let value: Uint128 = match &condition.field {
+        Some(field) => match querier
+            .query::<BTreeMap<String, ConditionField>>(&request)
+            .map_err(|e| e.to_string())?
+            .remove(field)
+        {
+            Some(ConditionField::Value(value)) => value,
+            Some(ConditionField::Other(_)) => {
+                return Err(format!("response field {} is not a Uint128", field))
+            },
+            None => return Err(format!("response has no field {}", field)),
+        },
+        None => querier.query(&request).map_err(|e| e.to_string())?,
+    };
+
+    let expected = condition.value;
+    let holds = match condition.comparator {
+        Comparator::Eq => value == expected,
+        Comparator::Ne => value != expected,
+        Comparator::Gt => value > expected,
+        Comparator::Gte => value >= expected,
+        Comparator::Lt => value < expected,
+        Comparator::Lte => value <= expected,
+    };
+
+    if holds {
+        Ok(())
+    } else {
+        Err(format!(
+            "value {} fails comparison with {}",
+            value, expected
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, collections::BTreeMap};
+
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        from_json, testing::mock_env, to_json_binary, to_json_string, Addr, Binary, Deps, DepsMut,
+        Empty, Env, MessageInfo, Order, QuerierWrapper, QueryRequest, Record, Response, StdResult,
+        Storage, Timestamp, Uint128, WasmQuery,
+    };
+    use cw20::{BalanceResponse, Cw20QueryMsg};
+    use cw_multi_test::{App, ContractWrapper, Executor};
+    use cw_storage_plus::Map;
+
+    use super::{
+        condition_request, try_authorize_path, ConditionField, MAX_ACL_HOPS, MAX_POLICY_DEPTH,
+    };
+    use crate::{
+        error::ContractError,
+        models::{Comparator, Condition, Config, Precedence},
+        msg::{
+            AllowMsg, ExecuteMsg, GrantRoleMsg, IsAllowedParams, PolicyExpr, QueryMsg,
+            RoleExecuteMsg, TestRequirement,
+        },
+        responses::{AuthSource, IsAllowedExplainResponse},
+        testing::{
+            acl_contract, allow, allow_msg, create_role, exec, forbid, grant_role, grant_role_msg,
+            instantiate_acl, is_allowed, is_allowed_params, query_as, setup, setup_with_config,
+            MockDeps, OPERATOR,
+        },
+    };
+
+    fn setup_with_precedence(precedence: Precedence) -> MockDeps {
+        setup_with_config(Config {
+            precedence,
+            ..Config::default()
+        })
+    }
+
+    #[test]
+    fn empty_paths_keep_the_vacuous_result() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/projects");
+
+        for (require, expected) in [
+            (TestRequirement::All, true),
+            (TestRequirement::Any, false),
+            (TestRequirement::Weighted { threshold: 1 }, true),
+        ] {
+            for negate in [false, true] {
+                let mut params = is_allowed_params("alice", &[]);
+                params.require = Some(require.clone());
+                params.negate = Some(negate);
+                assert_eq!(
+                    query_as::<bool>(deps.as_ref(), QueryMsg::IsAllowed(params.clone())).unwrap(),
+                    expected != negate,
+                    "{:?}, negate: {}",
+                    require,
+                    negate
+                );
+                let explained = query_as::<IsAllowedExplainResponse>(
+                    deps.as_ref(),
+                    QueryMsg::IsAllowedExplain(params),
+                )
+                .unwrap();
+                assert_eq!(explained.allowed, expected != negate);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_weighted_threshold_is_rejected() {
+        let deps = setup();
+        let mut params = is_allowed_params("alice", &["/projects"]);
+        params.require = Some(TestRequirement::Weighted { threshold: 0 });
+
+        let result = query_as::<bool>(deps.as_ref(), QueryMsg::IsAllowed(params.clone()));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+        let result =
+            query_as::<IsAllowedExplainResponse>(deps.as_ref(), QueryMsg::IsAllowedExplain(params));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn denial_message_prefixes_every_raised_failure() {
+        let mut deps = setup_with_config(Config {
+            denial_message: Some("Ask an admin for access".to_owned()),
+            ..Config::default()
+        });
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(60),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+
+        let raise = |principal: &str, at: Option<Timestamp>| -> String {
+            let mut params = is_allowed_params(principal, &["/docs"]);
+            params.raise = Some(true);
+            params.at = at;
+            match query_as::<bool>(deps.as_ref(), QueryMsg::IsAllowed(params)) {
+                Err(ContractError::NotAuthorized { reason }) => reason,
+                result => panic!("unexpected result: {:?}", result),
+            }
+        };
+
+        let reason = raise("bob", None);
+        assert!(
+            reason.starts_with("Ask an admin for access: "),
+            "{}",
+            reason
+        );
+        assert!(reason.contains("bob"), "{}", reason);
+
+        let reason = raise("alice", Some(mock_env().block.time.plus_seconds(120)));
+        assert!(
+            reason.starts_with("Ask an admin for access: "),
+            "{}",
+            reason
+        );
+        assert!(reason.contains("Expired"), "{}", reason);
+    }
+
+    #[test]
+    fn valid_role_authorizes_despite_expired_role_at_same_path() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "a-expired", &["/docs"]);
+        create_role(deps.as_mut(), "b-valid", &["/docs"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(60),
+                ..grant_role_msg("alice", "a-expired")
+            })),
+        )
+        .unwrap();
+        grant_role(deps.as_mut(), "alice", "b-valid");
+
+        let config = Config::default();
+        let later = mock_env().block.time.plus_seconds(120);
+        let auth_match = try_authorize_path(deps.as_ref(), &config, later, "alice", "/docs/a");
+        assert_eq!(
+            auth_match.ok().map(|m| m.via),
+            Some(AuthSource::Role("b-valid".to_owned()))
+        );
+    }
+
+    #[test]
+    fn role_weights_together_meet_threshold() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "reviewers", &["/proposals"]);
+        create_role(deps.as_mut(), "signers", &["/proposals/42"]);
+        grant_role(deps.as_mut(), "alice", "reviewers");
+        grant_role(deps.as_mut(), "alice", "signers");
+        grant_role(deps.as_mut(), "bob", "reviewers");
+        grant_role(deps.as_mut(), "carol", "signers");
+
+        let meets = |principal: &str| -> bool {
+            let mut params = is_allowed_params(principal, &["/proposals/42"]);
+            params.require = Some(TestRequirement::Weighted { threshold: 2 });
+            query_as(deps.as_ref(), QueryMsg::IsAllowed(params)).unwrap()
+        };
+        assert!(meets("alice"));
+        assert!(!meets("bob"));
+        assert!(!meets("carol"));
+    }
+
+    #[test]
+    fn non_empty_paths_are_checked() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/projects");
+
+        let allowed: bool = query_as(
+            deps.as_ref(),
+            QueryMsg::IsAllowed(is_allowed_params("alice", &["/projects/42"])),
+        )
+        .unwrap();
+        assert!(allowed);
+
+        let allowed: bool = query_as(
+            deps.as_ref(),
+            QueryMsg::IsAllowed(is_allowed_params("bob", &["/projects/42"])),
+        )
+        .unwrap();
+        assert!(!allowed);
+    }
+
+    #[test]
+    fn raw_paths_match_grants_canonicalized_on_write() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/team docs/q1\u{7}report");
+
+        for path in [
+            "/team-docs/q1report",
+            "/team docs/q1report",
+            "team docs//q1\u{7}report/",
+            "/team\u{0} docs/q1report/draft",
+        ] {
+            assert!(is_allowed(deps.as_ref(), "alice", path), "{:?}", path);
+        }
+        assert!(!is_allowed(deps.as_ref(), "alice", "/team_docs/q1report"));
+    }
+
+    #[test]
+    fn precedence_decides_between_ancestor_denial_and_specific_allow() {
+        for (precedence, expected) in [
+            (Precedence::DenyWins, false),
+            (Precedence::MostSpecific, true),
+        ] {
+            let mut deps = setup_with_precedence(precedence.clone());
+            forbid(deps.as_mut(), "alice", "/projects");
+            allow(deps.as_mut(), "alice", "/projects/42");
+
+            assert_eq!(
+                is_allowed(deps.as_ref(), "alice", "/projects/42"),
+                expected,
+                "{:?}",
+                precedence
+            );
+            assert!(!is_allowed(deps.as_ref(), "alice", "/projects/7"));
+        }
+    }
+
+    #[test]
+    fn specific_denial_wins_under_either_precedence() {
+        for precedence in [Precedence::DenyWins, Precedence::MostSpecific] {
+            let mut deps = setup_with_precedence(precedence);
+            allow(deps.as_mut(), "alice", "/projects");
+            forbid(deps.as_mut(), "alice", "/projects/42");
+
+            assert!(!is_allowed(deps.as_ref(), "alice", "/projects/42"));
+            assert!(is_allowed(deps.as_ref(), "alice", "/projects/7"));
+        }
+    }
+
+    #[test]
+    fn negate_inverts_the_aggregate_result() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/docs");
+
+        for (require, paths, expected) in [
+            (TestRequirement::All, vec!["/docs"], false),
+            (TestRequirement::All, vec!["/docs", "/admin"], true),
+            (TestRequirement::Any, vec!["/admin", "/docs"], false),
+            (TestRequirement::Any, vec!["/admin", "/billing"], true),
+        ] {
+            let mut params = is_allowed_params("alice", &paths);
+            params.require = Some(require.clone());
+            params.negate = Some(true);
+            let negated: bool =
+                query_as(deps.as_ref(), QueryMsg::IsAllowed(params.clone())).unwrap();
+            assert_eq!(negated, expected, "{:?} {:?}", require, paths);
+
+            // Raising fails only when the negated condition is violated
+            params.raise = Some(true);
+            let raised = query_as::<bool>(deps.as_ref(), QueryMsg::IsAllowed(params));
+            match expected {
+                true => assert!(raised.unwrap()),
+                false => assert!(matches!(
+                    raised,
+                    Err(ContractError::NotAuthorized { reason }) if reason.contains("is allowed to")
+                )),
+            }
+        }
+    }
+
+    /// Storage that counts the records read from it.
+    struct CountingStorage<'a> {
+        inner: &'a dyn Storage,
+        reads: Cell<usize>,
+    }
+
+    impl Storage for CountingStorage<'_> {
+        fn get(
+            &self,
+            key: &[u8],
+        ) -> Option<Vec<u8>> {
+            self.reads.set(self.reads.get() + 1);
+            self.inner.get(key)
+        }
+
+        fn range<'b>(
+            &'b self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> Box<dyn Iterator<Item = Record> + 'b> {
+            Box::new(self.inner.range(start, end, order).inspect(|_| {
+                self.reads.set(self.reads.get() + 1);
+            }))
+        }
+
+        fn set(
+            &mut self,
+            _key: &[u8],
+            _value: &[u8],
+        ) {
+            panic!("CountingStorage is read-only")
+        }
+
+        fn remove(
+            &mut self,
+            _key: &[u8],
+        ) {
+            panic!("CountingStorage is read-only")
+        }
+    }
+
+    #[test]
+    fn role_check_reads_only_the_principals_roles() {
+        let mut deps = setup();
+        for i in 0..100 {
+            create_role(deps.as_mut(), &format!("role-{}", i), &["/docs"]);
+        }
+        grant_role(deps.as_mut(), "alice", "role-42");
+
+        let storage = CountingStorage {
+            inner: &deps.storage,
+            reads: Cell::new(0),
+        };
+        let counted = Deps {
+            storage: &storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&deps.querier),
+        };
+        let config = Config::default();
+        let time = mock_env().block.time;
+
+        let auth_match = try_authorize_path(counted, &config, time, "alice", "/docs/a").ok();
+        assert_eq!(
+            auth_match.map(|m| m.via),
+            Some(AuthSource::Role("role-42".to_owned()))
+        );
+        // Far fewer reads than there are roles linked to the path
+        assert!(storage.reads.get() < 20, "{} reads", storage.reads.get());
+
+        assert!(try_authorize_path(counted, &config, time, "bob", "/docs/a").is_err());
+    }
+
+    const BALANCES: Map<&str, Uint128> = Map::new("balances");
+
+    #[cw_serde]
+    struct SetBalanceMsg {
+        address: String,
+        amount: Uint128,
+    }
+
+    fn token_execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: SetBalanceMsg,
+    ) -> StdResult<Response> {
+        BALANCES.save(deps.storage, &msg.address, &msg.amount)?;
+        Ok(Response::new())
+    }
+
+    fn token_instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn token_query(
+        deps: Deps,
+        _env: Env,
+        msg: Cw20QueryMsg,
+    ) -> StdResult<Binary> {
+        match msg {
+            Cw20QueryMsg::Balance { address } => to_json_binary(&BalanceResponse {
+                balance: BALANCES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            }),
+            _ => unreachable!("token only answers balance queries"),
+        }
+    }
+
+    #[test]
+    fn conditional_grant_follows_external_balance() {
+        let mut app = App::default();
+        let acl_code_id = app.store_code(acl_contract());
+        let token_code_id = app.store_code(Box::new(ContractWrapper::new(
+            token_execute,
+            token_instantiate,
+            token_query,
+        )));
+        let token = app
+            .instantiate_contract(
+                token_code_id,
+                Addr::unchecked(OPERATOR),
+                &Empty {},
+                &[],
+                "token",
+                None,
+            )
+            .unwrap();
+        let acl = instantiate_acl(&mut app, acl_code_id, None);
+
+        app.execute_contract(
+            Addr::unchecked(OPERATOR),
+            acl.clone(),
+            &ExecuteMsg::Allow(AllowMsg {
+                condition: Some(Condition {
+                    contract: token.clone(),
+                    msg: r#"{"balance":{"address":"{principal}"}}"#.to_owned(),
+                    field: Some("balance".to_owned()),
+                    comparator: Comparator::Gte,
+                    value: Uint128::new(100),
+                }),
+                ..allow_msg("alice", "/vault")
+            }),
+            &[],
+        )
+        .unwrap();
+
+        let set_balance = |app: &mut App, amount: u128| {
+            app.execute_contract(
+                Addr::unchecked(OPERATOR),
+                token.clone(),
+                &SetBalanceMsg {
+                    address: "alice".to_owned(),
+                    amount: Uint128::new(amount),
+                },
+                &[],
+            )
+            .unwrap();
+        };
+        set_balance(&mut app, 99);
+        let check = |app: &App, raise: bool| {
+            app.wrap().query_wasm_smart::<bool>(
+                &acl,
+                &QueryMsg::IsAllowed(IsAllowedParams {
+                    raise: Some(raise),
+                    ..is_allowed_params("alice", &["/vault/1"])
+                }),
+            )
+        };
+        assert!(!check(&app, false).unwrap());
+        let err = check(&app, true).unwrap_err();
+        assert!(err.to_string().contains("is conditional"), "{}", err);
+
+        set_balance(&mut app, 100);
+        assert!(check(&app, false).unwrap());
+
+        set_balance(&mut app, 0);
+        assert!(!check(&app, false).unwrap());
+    }
+
+    fn balance_condition(field: Option<&str>) -> Condition {
+        Condition {
+            contract: Addr::unchecked("token"),
+            msg: r#"{"balance":{"address":"{principal}"}}"#.to_owned(),
+            field: field.map(|f| f.to_owned()),
+            comparator: Comparator::Gte,
+            value: Uint128::new(100),
+        }
+    }
+
+    #[test]
+    fn condition_query_escapes_principal() {
+        let principal = r#"alice"},"extra":{"address":"bob"#;
+        let request = condition_request(principal, &balance_condition(None), 0).unwrap();
+        let QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) = request else {
+            panic!("expected a smart query");
+        };
+        match from_json::<Cw20QueryMsg>(&msg).unwrap() {
+            Cw20QueryMsg::Balance { address } => assert_eq!(address, principal),
+            _ => panic!("expected a balance query"),
+        }
+    }
+
+    #[test]
+    fn condition_query_forwards_hops_to_acls() {
+        let mut condition = balance_condition(None);
+        condition.msg = to_json_string(&QueryMsg::IsAllowed(is_allowed_params(
+            "{principal}",
+            &["/x"],
+        )))
+        .unwrap();
+
+        let request = condition_request("alice", &condition, 1).unwrap();
+        let QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) = request else {
+            panic!("expected a smart query");
+        };
+        match from_json::<QueryMsg>(&msg).unwrap() {
+            QueryMsg::IsAllowed(params) => {
+                assert_eq!(params.principal, "alice");
+                assert_eq!(params.hops, Some(2));
+            },
+            _ => panic!("expected an IsAllowed query"),
+        }
+        assert!(condition_request("alice", &condition, MAX_ACL_HOPS).is_err());
+
+        // Other ACL queries can't carry hops, so they're refused
+        condition.msg = to_json_string(&QueryMsg::Acl {}).unwrap();
+        assert!(condition_request("alice", &condition, 0).is_err());
+    }
+
+    #[test]
+    fn condition_field_ignores_other_fields() {
+        let mut fields: BTreeMap<String, ConditionField> =
+            from_json(br#"{"amount":"5","denom":"x","info":{"n":1}}"#).unwrap();
+        assert!(matches!(
+            fields.remove("amount"),
+            Some(ConditionField::Value(v)) if v == Uint128::new(5)
+        ));
+        assert!(matches!(
+            fields.remove("denom"),
+            Some(ConditionField::Other(_))
+        ));
+    }
+
+    fn policy_path(path: &str) -> PolicyExpr {
+        PolicyExpr::Path(path.to_owned())
+    }
+
+    fn check_policy(
+        deps: &MockDeps,
+        principal: &str,
+        expression: PolicyExpr,
+    ) -> Result<bool, ContractError> {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::IsAllowed(IsAllowedParams {
+                expression: Some(expression),
+                ..is_allowed_params(principal, &[])
+            }),
+        )
+    }
+
+    #[test]
+    fn policy_expression_combines_paths() {
+        let mut deps = setup();
+        // alice has A and B, bob only C, carol only A, and dave nothing
+        allow(deps.as_mut(), "alice", "/a");
+        allow(deps.as_mut(), "alice", "/b");
+        allow(deps.as_mut(), "bob", "/c");
+        allow(deps.as_mut(), "carol", "/a");
+
+        let a_and_b_or_c = || {
+            PolicyExpr::Any(vec![
+                PolicyExpr::All(vec![policy_path("/a"), policy_path("/b")]),
+                policy_path("/c"),
+            ])
+        };
+        for (principal, expected) in [
+            ("alice", true),
+            ("bob", true),
+            ("carol", false),
+            ("dave", false),
+        ] {
+            assert_eq!(
+                check_policy(&deps, principal, a_and_b_or_c()).unwrap(),
+                expected,
+                "{}",
+                principal
+            );
+        }
+
+        let a_and_not_b = PolicyExpr::All(vec![
+            policy_path("/a"),
+            PolicyExpr::Not(Box::new(policy_path("/b"))),
+        ]);
+        assert!(check_policy(&deps, "carol", a_and_not_b.clone()).unwrap());
+        assert!(!check_policy(&deps, "alice", a_and_not_b).unwrap());
+    }
+
+    #[test]
+    fn policy_expression_nesting_is_bounded() {
+        let deps = setup();
+        let mut expr = policy_path("/a");
+        for _ in 0..=MAX_POLICY_DEPTH {
+            expr = PolicyExpr::Not(Box::new(expr));
+        }
+        assert!(matches!(
+            check_policy(&deps, "alice", expr),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
 }
@@ -0,0 +1,257 @@
+use crate::{
+    error::ContractError,
+    msg::{IsAllowedParams, TestRequirement},
+    responses::{AuthMatch, AuthSource, IsAllowedExplainResponse, PathExplanation},
+    state::CONFIG,
+    utils::to_configured_path,
+};
+
+use super::{
+    is_allowed::{
+        ensure_valid_requirement, try_authorize_path_at_hop, try_authorize_path_weighted,
+        AuthFailure,
+    },
+    ReadonlyContext,
+};
+
+/// Same authorization logic as IsAllowed, except that it never raises and it
+/// reports, for each path, which ancestor path and source (direct grant or
+/// role) authorized the principal or, if not authorized, why not.
+pub fn query_is_allowed_explain(
+    ctx: ReadonlyContext,
+    msg: IsAllowedParams,
+) -> Result<IsAllowedExplainResponse, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let IsAllowedParams {
+        principal,
+        paths,
+        require,
+        at,
+        negate,
+        expression,
+        hops,
+        ..
+    } = msg;
+
+    // A policy expression has no per-path explanation, so rather than explain
+    // a different check than IsAllowed would make, reject it
+    if expression.is_some() {
+        return Err(ContractError::ValidationError {
+            reason: "policy expressions cannot be explained".to_owned(),
+        });
+    }
+
+    let require = require.unwrap_or(TestRequirement::All);
+    ensure_valid_requirement(&require)?;
+    let config = CONFIG.load(deps.storage)?;
+    let time = at.unwrap_or(env.block.time);
+    let hops = hops.unwrap_or(0);
+    let mut explanations: Vec<PathExplanation> = Vec::with_capacity(paths.len());
+    let mut n_authorized: usize = 0;
+
+    for p in paths.iter() {
+        let path = to_configured_path(&config, p).unwrap_or_else(|_| p.to_owned());
+        let result = match require {
+            TestRequirement::Weighted { threshold } => try_authorize_path_weighted(
+                deps, &config, time, &principal, p, threshold,
+            )
+            .map(|weight| AuthMatch {
+                matched_path: path.to_owned(),
+                via: AuthSource::Weighted(weight),
+            }),
+            _ => try_authorize_path_at_hop(deps, &config, time, &principal, p, hops),
+        };
+        match result {
+            Ok(auth_match) => {
+                n_authorized += 1;
+                explanations.push(PathExplanation {
+                    path,
+                    matched: Some(auth_match),
+                    failure: None,
+                    reason: None,
+                });
+            },
+            Err(AuthFailure { kind, reason }) => explanations.push(PathExplanation {
+                path,
+                matched: None,
+                failure: Some(kind),
+                reason: Some(reason),
+            }),
+        }
+    }
+
+    // Mirror IsAllowed: in ANY mode, at least one path must pass; otherwise,
+    // every path must pass, which holds vacuously when there are none.
+    let allowed = match require {
+        TestRequirement::All | TestRequirement::Weighted { .. } => n_authorized == paths.len(),
+        TestRequirement::Any => n_authorized > 0,
+    };
+
+    // As with IsAllowed, a negated check passes only if the principal is NOT
+    // allowed.
+    let (allowed, reason) = if negate.unwrap_or(false) {
+        let reason = if allowed {
+            Some(format!("{} is allowed to {}", principal, paths.join(", ")))
+        } else {
+            None
+        };
+        (!allowed, reason)
+    } else if allowed {
+        (true, None)
+    } else {
+        let mut reasons = explanations.iter().filter_map(|e| e.reason.to_owned());
+        match require {
+            TestRequirement::All | TestRequirement::Weighted { .. } => (false, reasons.next()),
+            TestRequirement::Any => (false, Some(reasons.collect::<Vec<_>>().join(", "))),
+        }
+    };
+
+    Ok(IsAllowedExplainResponse {
+        allowed,
+        reason,
+        paths: explanations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_env;
+
+    use crate::{
+        error::ContractError,
+        msg::{AllowMsg, ExecuteMsg, IsAllowedParams, PolicyExpr, QueryMsg, TestRequirement},
+        responses::{AuthFailureKind, AuthMatch, AuthSource, IsAllowedExplainResponse},
+        testing::{
+            allow, allow_msg, create_role, exec, grant_role, is_allowed_params, query_as, setup,
+            MockDeps, OPERATOR,
+        },
+    };
+
+    fn explain(
+        deps: &MockDeps,
+        principal: &str,
+        path: &str,
+    ) -> IsAllowedExplainResponse {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::IsAllowedExplain(is_allowed_params(principal, &[path])),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reports_ancestor_path_and_role() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+
+        let resp = explain(&deps, "alice", "/docs/reports/q1");
+        assert!(resp.allowed);
+        assert_eq!(
+            resp.paths[0].matched,
+            Some(AuthMatch {
+                matched_path: "/docs".to_owned(),
+                via: AuthSource::Role("editors".to_owned()),
+            })
+        );
+    }
+
+    #[test]
+    fn reports_direct_grant() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/docs/reports");
+
+        let resp = explain(&deps, "alice", "/docs/reports/q1");
+        assert_eq!(
+            resp.paths[0].matched,
+            Some(AuthMatch {
+                matched_path: "/docs/reports".to_owned(),
+                via: AuthSource::Direct,
+            })
+        );
+    }
+
+    #[test]
+    fn failures_distinguish_expired_from_not_granted() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(60),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+
+        let mut params = is_allowed_params("alice", &["/docs", "/wiki"]);
+        params.at = Some(mock_env().block.time.plus_seconds(120));
+        let resp: IsAllowedExplainResponse =
+            query_as(deps.as_ref(), QueryMsg::IsAllowedExplain(params.clone())).unwrap();
+        let kinds: Vec<Option<AuthFailureKind>> =
+            resp.paths.iter().map(|p| p.failure.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Some(AuthFailureKind::Expired),
+                Some(AuthFailureKind::NotGranted)
+            ]
+        );
+
+        // Raising on the expired path alone gives a distinct error
+        params.paths = vec!["/docs".to_owned()];
+        params.raise = Some(true);
+        let result = query_as::<bool>(deps.as_ref(), QueryMsg::IsAllowed(params));
+        assert!(matches!(result, Err(ContractError::Expired { .. })));
+    }
+
+    #[test]
+    fn rejects_policy_expression() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/a");
+
+        let result = query_as::<IsAllowedExplainResponse>(
+            deps.as_ref(),
+            QueryMsg::IsAllowedExplain(IsAllowedParams {
+                expression: Some(PolicyExpr::Path("/b".to_owned())),
+                ..is_allowed_params("alice", &["/a"])
+            }),
+        );
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn reasons_follow_input_order() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/other");
+        let paths = ["/c", "/a", "/b"];
+
+        let mut params = is_allowed_params("alice", &paths);
+        let all: IsAllowedExplainResponse =
+            query_as(deps.as_ref(), QueryMsg::IsAllowedExplain(params.clone())).unwrap();
+        let reasons: Vec<String> = all
+            .paths
+            .iter()
+            .map(|p| p.reason.clone().unwrap())
+            .collect();
+        for (reason, path) in reasons.iter().zip(paths) {
+            assert!(reason.ends_with(path), "{}", reason);
+        }
+
+        // ALL mode reports the first failure even when not raising
+        assert!(!all.allowed);
+        assert_eq!(all.reason.as_ref(), Some(&reasons[0]));
+
+        params.require = Some(TestRequirement::Any);
+        let any: IsAllowedExplainResponse =
+            query_as(deps.as_ref(), QueryMsg::IsAllowedExplain(params.clone())).unwrap();
+        assert_eq!(any.reason, Some(reasons.join(", ")));
+
+        params.raise = Some(true);
+        let raised = query_as::<bool>(deps.as_ref(), QueryMsg::IsAllowed(params));
+        assert!(matches!(
+            raised,
+            Err(ContractError::NotAuthorized { reason }) if reason == reasons.join(", ")
+        ));
+    }
+}
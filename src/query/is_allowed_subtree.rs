@@ -0,0 +1,143 @@
+use cosmwasm_std::{Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, PRINCIPAL_PATH_AUTHORIZATIONS, ROLE_PATHS},
+    utils::{resolve_principal, to_configured_path},
+};
+
+use super::{
+    is_allowed::{load_principal_roles, try_authorize_path},
+    ReadonlyContext,
+};
+
+/// Maximum number of grants under the prefix read per source, direct or role,
+/// which bounds the cost of the check.
+const MAX_SCAN: usize = 500;
+
+/// Test if a principal is allowed to the given prefix or anything under it.
+/// Access to the prefix itself, including through an ancestor, suffices.
+/// Otherwise, each of the principal's direct and role grants under the prefix
+/// is checked as IsAllowed would, so denials and expiry still apply.
+pub fn query_is_allowed_subtree(
+    ctx: ReadonlyContext,
+    principal: String,
+    prefix: String,
+) -> Result<bool, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let time = env.block.time;
+    let config = CONFIG.load(deps.storage)?;
+    let prefix = to_configured_path(&config, &prefix)?;
+
+    if try_authorize_path(deps, &config, time, &principal, &prefix).is_ok() {
+        return Ok(true);
+    }
+
+    let resolved_principal = resolve_principal(deps.storage, &principal);
+    let is_allowed =
+        |path: &String| try_authorize_path(deps, &config, time, &principal, path).is_ok();
+
+    let direct_paths = PRINCIPAL_PATH_AUTHORIZATIONS
+        .prefix(&resolved_principal)
+        .keys(
+            deps.storage,
+            Some(Bound::inclusive(&prefix)),
+            None,
+            Order::Ascending,
+        );
+    if any_under(direct_paths, &prefix, &is_allowed)? {
+        return Ok(true);
+    }
+
+    for (role, _) in load_principal_roles(deps.storage, &resolved_principal) {
+        let role_paths = ROLE_PATHS.prefix(&role).keys(
+            deps.storage,
+            Some(Bound::inclusive(&prefix)),
+            None,
+            Order::Ascending,
+        );
+        if any_under(role_paths, &prefix, &is_allowed)? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Check whether any of the given paths, ordered and starting from the prefix,
+/// is under the prefix and allowed.
+fn any_under(
+    paths: impl Iterator<Item = StdResult<String>>,
+    prefix: &str,
+    is_allowed: &impl Fn(&String) -> bool,
+) -> Result<bool, ContractError> {
+    for result in paths.take(MAX_SCAN) {
+        let path = result?;
+        if !path.starts_with(prefix) {
+            break;
+        }
+        if is_descendant(prefix, &path) && is_allowed(&path) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Whether a canonical path starting with the prefix is the prefix itself or
+/// lies beneath it, rather than merely sharing its leading characters (e.g.
+/// `/projects2` under `/projects`).
+fn is_descendant(
+    prefix: &str,
+    path: &str,
+) -> bool {
+    prefix.is_empty()
+        || prefix.ends_with('/')
+        || path.len() == prefix.len()
+        || path[prefix.len()..].starts_with('/')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        msg::QueryMsg,
+        testing::{allow, create_role, forbid, grant_role, query_as, setup, MockDeps},
+    };
+
+    fn subtree(
+        deps: &MockDeps,
+        principal: &str,
+        prefix: &str,
+    ) -> bool {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::IsAllowedSubtree {
+                principal: principal.to_owned(),
+                prefix: prefix.to_owned(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn grant_under_prefix_satisfies_subtree_check() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/projects/42/read");
+        create_role(deps.as_mut(), "editors", &["/docs/drafts"]);
+        grant_role(deps.as_mut(), "bob", "editors");
+
+        assert!(subtree(&deps, "alice", "/projects"));
+        assert!(subtree(&deps, "alice", "/projects/42/read/notes"));
+        assert!(!subtree(&deps, "alice", "/projects/4"));
+        assert!(subtree(&deps, "bob", "/docs"));
+        assert!(!subtree(&deps, "carol", "/projects"));
+    }
+
+    #[test]
+    fn denied_grants_under_prefix_do_not_count() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/projects/42/read");
+        forbid(deps.as_mut(), "alice", "/projects/42");
+        assert!(!subtree(&deps, "alice", "/projects"));
+    }
+}
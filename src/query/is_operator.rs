@@ -0,0 +1,78 @@
+use crate::{
+    client::is_operator,
+    error::ContractError,
+    state::{load_control_path, OP},
+};
+
+use super::ReadonlyContext;
+
+pub fn query_is_operator(
+    ctx: ReadonlyContext,
+    address: String,
+) -> Result<bool, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let address = deps.api.addr_validate(&address)?;
+
+    // Apply the same path used to authorize execution of the ACL
+    let control_path = load_control_path(deps.storage, &env.contract.address)?;
+    Ok(is_operator(
+        deps.querier,
+        &address,
+        OP.load(deps.storage)?,
+        || control_path.to_owned(),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Addr;
+    use cw_multi_test::{App, Executor};
+
+    use crate::{
+        client::{to_control_path, Operator, DEFAULT_CONTROL_PREFIX},
+        msg::{ExecuteMsg, QueryMsg},
+        testing::{acl_contract, allow_msg, instantiate_acl, query_as, setup, OPERATOR},
+    };
+
+    fn is_operator_msg(address: &str) -> QueryMsg {
+        QueryMsg::IsOperator {
+            address: address.to_owned(),
+        }
+    }
+
+    #[test]
+    fn operator_address_must_match() {
+        let deps = setup();
+        assert!(query_as::<bool>(deps.as_ref(), is_operator_msg(OPERATOR)).unwrap());
+        assert!(!query_as::<bool>(deps.as_ref(), is_operator_msg("alice")).unwrap());
+    }
+
+    #[test]
+    fn operator_acl_decides_via_control_path() {
+        let mut app = App::default();
+        let code_id = app.store_code(acl_contract());
+        let operator_acl = instantiate_acl(&mut app, code_id, None);
+        let acl = instantiate_acl(&mut app, code_id, Some(Operator::Acl(operator_acl.clone())));
+
+        app.execute_contract(
+            Addr::unchecked(OPERATOR),
+            operator_acl,
+            &ExecuteMsg::Allow(allow_msg(
+                "alice",
+                &to_control_path(DEFAULT_CONTROL_PREFIX, &acl),
+            )),
+            &[],
+        )
+        .unwrap();
+
+        let is_operator = |address: &str| -> bool {
+            app.wrap()
+                .query_wasm_smart(&acl, &is_operator_msg(address))
+                .unwrap()
+        };
+        assert!(is_operator("alice"));
+        assert!(!is_operator("bob"));
+        // Operating the operator ACL isn't operating this one
+        assert!(!is_operator(OPERATOR));
+    }
+}
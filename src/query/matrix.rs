@@ -0,0 +1,85 @@
+use crate::{error::ContractError, msg::ViewerAuth, state::CONFIG, utils::ensure_viewer};
+
+use super::{is_allowed::try_authorize_path, ReadonlyContext};
+
+const MAX_CELLS: usize = 100;
+
+/// Test every combination of the given principals and paths. Row i of the
+/// result holds the results for principal i against each path.
+pub fn query_matrix(
+    ctx: ReadonlyContext,
+    principals: Vec<String>,
+    paths: Vec<String>,
+    viewer: Option<ViewerAuth>,
+) -> Result<Vec<Vec<bool>>, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+
+    ensure_viewer(deps.storage, viewer)?;
+
+    if principals.len().saturating_mul(paths.len()) > MAX_CELLS {
+        return Err(ContractError::ValidationError {
+            reason: format!("cannot test more than {} principal-path pairs", MAX_CELLS),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(principals
+        .iter()
+        .map(|principal| {
+            paths
+                .iter()
+                .map(|path| {
+                    try_authorize_path(deps, &config, env.block.time, principal, path).is_ok()
+                })
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        msg::QueryMsg,
+        testing::{allow, create_role, grant_role, query_as, setup},
+    };
+
+    fn matrix_msg(
+        principals: &[&str],
+        paths: &[&str],
+    ) -> QueryMsg {
+        QueryMsg::Matrix {
+            principals: principals.iter().map(|p| p.to_string()).collect(),
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            viewer: None,
+        }
+    }
+
+    #[test]
+    fn reports_a_row_per_principal() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/docs");
+        create_role(deps.as_mut(), "billing", &["/invoices"]);
+        grant_role(deps.as_mut(), "bob", "billing");
+
+        let matrix: Vec<Vec<bool>> = query_as(
+            deps.as_ref(),
+            matrix_msg(&["alice", "bob"], &["/docs/a", "/invoices/1"]),
+        )
+        .unwrap();
+        assert_eq!(matrix, vec![vec![true, false], vec![false, true]]);
+    }
+
+    #[test]
+    fn rejects_too_many_cells() {
+        let deps = setup();
+        let principals: Vec<String> = (0..11).map(|i| format!("user{}", i)).collect();
+        let principals: Vec<&str> = principals.iter().map(|p| p.as_str()).collect();
+        let paths: Vec<String> = (0..10).map(|i| format!("/docs/{}", i)).collect();
+        let paths: Vec<&str> = paths.iter().map(|p| p.as_str()).collect();
+
+        let result = query_as::<Vec<Vec<bool>>>(deps.as_ref(), matrix_msg(&principals, &paths));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+}
@@ -1,8 +1,30 @@
+pub mod access_source;
 pub mod acl;
+pub mod auth_chain;
+pub mod canonicalize;
+pub mod consistency_check;
+pub mod counts;
+pub mod diff_principals;
+pub mod expired;
+pub mod expiring_soon;
+pub mod export;
+pub mod glob_paths;
 pub mod is_allowed;
+pub mod is_allowed_explain;
+pub mod is_allowed_subtree;
+pub mod is_operator;
+pub mod matrix;
+pub mod operator_chain;
+pub mod path_exists;
+pub mod path_roles;
 pub mod paths;
+pub mod recent_grants;
+pub mod resolve;
 pub mod role;
 pub mod roles;
+pub mod roles_by_name;
+pub mod roles_under;
+pub mod which_allowed;
 
 use cosmwasm_std::{Deps, Env};
 
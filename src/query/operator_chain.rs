@@ -0,0 +1,121 @@
+use cosmwasm_std::Addr;
+
+use crate::{
+    client::Operator,
+    error::ContractError,
+    msg::QueryMsg,
+    responses::{AclResponse, OperatorChainResponse},
+    state::OP,
+};
+
+use super::ReadonlyContext;
+
+const MAX_DEPTH: u8 = 16;
+const DEFAULT_DEPTH: u8 = 8;
+
+/// Follow each operator ACL to its own operator, via Acl queries, until
+/// reaching a non-ACL operator, an ACL already visited, or the maximum depth.
+pub fn query_operator_chain(
+    ctx: ReadonlyContext,
+    max_depth: Option<u8>,
+) -> Result<OperatorChainResponse, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let max_depth = max_depth.unwrap_or(DEFAULT_DEPTH).clamp(1, MAX_DEPTH) as usize;
+
+    let mut visited: Vec<Addr> = Vec::with_capacity(max_depth);
+    let mut chain: Vec<Operator> = vec![OP.load(deps.storage)?];
+    let mut cycle = false;
+    let mut truncated = false;
+
+    while let Some(Operator::Acl(acl_addr)) = chain.last().cloned() {
+        // An ACL operated by itself is a supported configuration, not a loop
+        if chain.len() == 1 && acl_addr == env.contract.address {
+            break;
+        }
+        if visited.contains(&acl_addr) {
+            cycle = true;
+            break;
+        }
+        if chain.len() >= max_depth {
+            truncated = true;
+            break;
+        }
+        let acl: AclResponse = deps
+            .querier
+            .query_wasm_smart(acl_addr.to_owned(), &QueryMsg::Acl {})?;
+        visited.push(acl_addr);
+        chain.push(acl.operator);
+    }
+
+    Ok(OperatorChainResponse {
+        chain,
+        cycle,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Addr;
+    use cw_multi_test::{App, Executor};
+
+    use crate::{
+        client::Operator,
+        msg::{ExecuteMsg, QueryMsg},
+        responses::OperatorChainResponse,
+        testing::{acl_contract, instantiate_acl, OPERATOR},
+    };
+
+    fn operator_chain(
+        app: &App,
+        acl: &Addr,
+    ) -> OperatorChainResponse {
+        app.wrap()
+            .query_wasm_smart(acl, &QueryMsg::OperatorChain { max_depth: None })
+            .unwrap()
+    }
+
+    #[test]
+    fn follows_two_hop_chain() {
+        let mut app = App::default();
+        let code_id = app.store_code(acl_contract());
+        let root = instantiate_acl(&mut app, code_id, None);
+        let middle = instantiate_acl(&mut app, code_id, Some(Operator::Acl(root.clone())));
+        let leaf = instantiate_acl(&mut app, code_id, Some(Operator::Acl(middle.clone())));
+
+        assert_eq!(
+            operator_chain(&app, &leaf),
+            OperatorChainResponse {
+                chain: vec![
+                    Operator::Acl(middle),
+                    Operator::Acl(root),
+                    Operator::Address(Addr::unchecked(OPERATOR)),
+                ],
+                cycle: false,
+                truncated: false,
+            }
+        );
+    }
+
+    #[test]
+    fn flags_cycle() {
+        let mut app = App::default();
+        let code_id = app.store_code(acl_contract());
+        let a = instantiate_acl(&mut app, code_id, None);
+        let b = instantiate_acl(&mut app, code_id, Some(Operator::Acl(a.clone())));
+
+        // Closing the loop leaves neither ACL with a way to be changed
+        app.execute_contract(
+            Addr::unchecked(OPERATOR),
+            a.clone(),
+            &ExecuteMsg::SetOperator(Operator::Acl(b.clone())),
+            &[],
+        )
+        .unwrap();
+
+        let resp = operator_chain(&app, &a);
+        assert!(resp.cycle);
+        assert!(!resp.truncated);
+        assert_eq!(resp.chain[..2], [Operator::Acl(b), Operator::Acl(a)]);
+    }
+}
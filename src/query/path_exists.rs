@@ -0,0 +1,72 @@
+use crate::{error::ContractError, state::PATH_REF_COUNTS, utils::to_acl_path};
+
+use super::ReadonlyContext;
+
+pub fn query_path_exists(
+    ctx: ReadonlyContext,
+    path: String,
+) -> Result<bool, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    Ok(PATH_REF_COUNTS.has(deps.storage, &to_acl_path(deps.storage, &path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        msg::{DenyMsg, ExecuteMsg, QueryMsg, RemoveRoleMsg, RoleExecuteMsg},
+        testing::{allow, create_role, exec, query_as, setup, MockDeps, OPERATOR},
+    };
+
+    fn path_exists(
+        deps: &MockDeps,
+        path: &str,
+    ) -> bool {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::PathExists {
+                path: path.to_owned(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn path_exists_while_referenced_by_role() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        assert!(path_exists(&deps, "/docs"));
+        // Paths are canonicalized, but ancestors aren't referenced themselves
+        assert!(path_exists(&deps, "docs/"));
+        assert!(!path_exists(&deps, "/docs/a"));
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Remove(RemoveRoleMsg {
+                role: "editors".to_owned(),
+                force: None,
+            })),
+        )
+        .unwrap();
+        assert!(!path_exists(&deps, "/docs"));
+    }
+
+    #[test]
+    fn path_exists_while_granted_directly() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/docs");
+        assert!(path_exists(&deps, "/docs"));
+
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Deny(DenyMsg {
+                principal: "alice".to_owned(),
+                path: "/docs".to_owned(),
+                force: None,
+            }),
+        )
+        .unwrap();
+        assert!(!path_exists(&deps, "/docs"));
+    }
+}
@@ -0,0 +1,170 @@
+use cosmwasm_std::{Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::{
+    error::ContractError,
+    msg::PathRolesCursor,
+    responses::{PathRole, PathRolesResponse},
+    state::{CONFIG, PATH_ROLES},
+    utils::{to_configured_path, to_crumbs, to_styled_path_from_crumbs},
+};
+
+use super::ReadonlyContext;
+
+const MAX_LIMIT: u16 = 500;
+const DEFAULT_LIMIT: u16 = 100;
+
+pub fn query_path_roles(
+    ctx: ReadonlyContext,
+    path: String,
+    inherited: Option<bool>,
+    limit: Option<u16>,
+    cursor: Option<PathRolesCursor>,
+) -> Result<PathRolesResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+    let config = CONFIG.load(deps.storage)?;
+    let style = &config.path_style;
+    let cannonical_path = to_configured_path(&config, &path)?;
+
+    // Paths to list roles for, in the same order as the authorization walk
+    let paths: Vec<String> = if inherited.unwrap_or(false) {
+        let mut crumbs = to_crumbs(&cannonical_path);
+        let mut paths: Vec<String> = Vec::with_capacity(crumbs.len());
+        while !crumbs.is_empty() {
+            paths.push(to_styled_path_from_crumbs(style, &crumbs));
+            crumbs.pop();
+        }
+        paths
+    } else {
+        vec![cannonical_path]
+    };
+
+    // Resume from the path and role in the cursor, if any
+    let (paths, mut after): (Vec<String>, Option<String>) = match cursor {
+        Some(PathRolesCursor { path, role }) => (
+            paths.into_iter().skip_while(|p| *p != path).collect(),
+            Some(role),
+        ),
+        None => (paths, None),
+    };
+
+    let mut roles: Vec<PathRole> = Vec::with_capacity(8);
+
+    for path in paths.iter() {
+        let min_bound = after.take();
+        let page: Vec<String> = PATH_ROLES
+            .prefix(path)
+            .keys(
+                deps.storage,
+                min_bound.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit + 1 - roles.len())
+            .collect::<StdResult<Vec<_>>>()?;
+
+        roles.extend(page.into_iter().map(|role| PathRole {
+            path: path.to_owned(),
+            role,
+        }));
+
+        if roles.len() > limit {
+            break;
+        }
+    }
+
+    // The extra role, if read, only tells us there's another page
+    let cursor = if roles.len() > limit {
+        roles.truncate(limit);
+        roles.last().map(|r| PathRolesCursor {
+            path: r.path.to_owned(),
+            role: r.role.to_owned(),
+        })
+    } else {
+        None
+    };
+
+    Ok(PathRolesResponse { roles, cursor })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        msg::{PathRolesCursor, QueryMsg},
+        responses::PathRolesResponse,
+        testing::{create_role, query_as, setup, MockDeps},
+    };
+
+    fn setup_roles() -> MockDeps {
+        let mut deps = setup();
+        for role in ["auditors", "editors", "reviewers"] {
+            create_role(deps.as_mut(), role, &["/docs/a"]);
+        }
+        create_role(deps.as_mut(), "readers", &["/docs"]);
+        deps
+    }
+
+    fn path_roles(
+        deps: &MockDeps,
+        inherited: bool,
+        limit: Option<u16>,
+        cursor: Option<PathRolesCursor>,
+    ) -> PathRolesResponse {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::PathRoles {
+                path: "docs/a".to_owned(),
+                inherited: Some(inherited),
+                limit,
+                cursor,
+            },
+        )
+        .unwrap()
+    }
+
+    fn pairs(resp: &PathRolesResponse) -> Vec<(&str, &str)> {
+        resp.roles
+            .iter()
+            .map(|r| (r.path.as_str(), r.role.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn lists_roles_linked_to_exact_path() {
+        let deps = setup_roles();
+        let resp = path_roles(&deps, false, None, None);
+        assert_eq!(
+            pairs(&resp),
+            vec![
+                ("/docs/a", "auditors"),
+                ("/docs/a", "editors"),
+                ("/docs/a", "reviewers"),
+            ]
+        );
+        assert_eq!(resp.cursor, None);
+    }
+
+    #[test]
+    fn lists_roles_linked_to_ancestors_when_inherited() {
+        let deps = setup_roles();
+        let resp = path_roles(&deps, true, None, None);
+        assert_eq!(
+            pairs(&resp),
+            vec![
+                ("/docs/a", "auditors"),
+                ("/docs/a", "editors"),
+                ("/docs/a", "reviewers"),
+                ("/docs", "readers"),
+            ]
+        );
+
+        // Pages continue from one path to its parent
+        let first = path_roles(&deps, true, Some(3), None);
+        assert_eq!(first.roles.len(), 3);
+        let second = path_roles(&deps, true, Some(3), first.cursor);
+        assert_eq!(pairs(&second), vec![("/docs", "readers")]);
+        assert_eq!(second.cursor, None);
+    }
+}
@@ -1,35 +1,80 @@
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, marker::PhantomData};
 
-use cosmwasm_std::Order;
+use cosmwasm_std::{Order, Storage};
 use cw_storage_plus::Bound;
 
 use crate::{
     error::ContractError,
-    models::AuthRecord,
-    msg::{PathsQueryParams, Subject},
+    models::{AuthRecord, PathStyle},
+    msg::{Expansion, OrderBy, PathsQueryParams, Subject},
     responses::{PathInfo, PathsResponse},
-    state::{PATH_REF_COUNTS, PRINCIPAL_PATH_AUTHORIZATIONS, ROLE_PATHS},
+    state::{
+        CONFIG, N_PATHS, PATH_REF_COUNTS, PRINCIPAL_PATH_AUTHORIZATIONS,
+        PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS, ROLE_PATHS,
+    },
+    utils::{
+        remaining_secs, to_cannonical_role, to_configured_path, to_crumbs,
+        to_styled_path_from_crumbs,
+    },
 };
 
 use super::ReadonlyContext;
 
 const MAX_LIMIT: u16 = 500;
 const DEFAULT_LIMIT: u16 = 100;
+const MAX_DESCENDANTS: usize = 50;
 
 pub fn query_paths(
     ctx: ReadonlyContext,
     params: PathsQueryParams,
 ) -> Result<PathsResponse, ContractError> {
-    let ReadonlyContext { deps, .. } = ctx;
+    let ReadonlyContext { deps, env, .. } = ctx;
     let PathsQueryParams {
         subject,
         limit,
         cursor,
         start,
         stop,
+        with_refs,
+        order_by,
+        include_roles,
+        expand,
     } = params;
 
+    let with_refs = with_refs.unwrap_or(false);
+    let expand = match subject {
+        Subject::Role(_) => expand.unwrap_or(Expansion::None),
+        _ => Expansion::None,
+    };
     let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(0, MAX_LIMIT) as usize;
+    let config = CONFIG.load(deps.storage)?;
+
+    // Bounds may be given as raw paths, so canonicalize them like stored paths.
+    // A cursor, on the other hand, is a path returned by a previous page and
+    // so is already in stored form, which is used as is.
+    let start = start.map(|p| to_configured_path(&config, &p)).transpose()?;
+    let stop = stop.map(|p| to_configured_path(&config, &p)).transpose()?;
+
+    // Reversed bounds would otherwise yield an empty page indistinguishable
+    // from there being no paths in range.
+    if let (Some(start), Some(stop)) = (&start, &stop) {
+        if start > stop {
+            return Err(ContractError::ValidationError {
+                reason: format!("start {} is after stop {}", start, stop),
+            });
+        }
+    }
+
+    // Storage isn't keyed by expiry, so ordering by expiry means reading as
+    // many records as we'll allow and sorting them in memory, reading one more
+    // to tell whether any were left out. Otherwise, read one more than the
+    // limit to tell whether there's another page.
+    let by_expiry = order_by == Some(OrderBy::ExpiryAsc);
+    let fetch_limit = if by_expiry {
+        MAX_LIMIT as usize + 1
+    } else {
+        limit + 1
+    };
     let mut path_infos: Vec<PathInfo> = Vec::with_capacity(8);
     let mut min_bound_path_box: Box<String> = Box::new("".to_owned());
     let mut max_bound_path_box: Box<String> = Box::new("".to_owned());
@@ -39,65 +84,777 @@ pub fn query_paths(
             *min_bound_path_box = cursor_path;
             Some(Bound::Exclusive((min_bound_path_box.as_ref(), PhantomData)))
         },
-        None => start.and_then(|path| {
+        None => start.map(|path| {
             *min_bound_path_box = path;
-            Some(Bound::Inclusive((min_bound_path_box.as_ref(), PhantomData)))
+            Bound::Inclusive((min_bound_path_box.as_ref(), PhantomData))
         }),
     };
 
-    let max_bound = stop.and_then(|path| {
+    let max_bound = stop.map(|path| {
         *max_bound_path_box = path;
-        Some(Bound::Inclusive((max_bound_path_box.as_ref(), PhantomData)))
+        Bound::Inclusive((max_bound_path_box.as_ref(), PhantomData))
     });
 
+    // Totals come from maintained counters rather than a scan, so they include
+    // paths referenced only by expired grants, which remain until pruned.
+    let approx_total = match &subject {
+        Subject::Acl => N_PATHS.may_load(deps.storage)?,
+        Subject::Role(role) => {
+            let role = to_cannonical_role(deps.storage, role.to_owned())?;
+            ROLE_INFOS
+                .may_load(deps.storage, &role)?
+                .map(|info| info.n_paths)
+        },
+        Subject::Principal(_) => None,
+    };
+
     match subject {
         Subject::Acl => {
             for result in PATH_REF_COUNTS
-                .keys(deps.storage, min_bound, max_bound, Order::Ascending)
-                .take(limit)
+                .range(deps.storage, min_bound, max_bound, Order::Ascending)
+                .take(fetch_limit)
             {
-                let path = result?;
+                let (path, refs) = result?;
                 path_infos.push(PathInfo {
                     path,
                     expires_at: None,
+                    remaining_secs: None,
+                    ref_count: if with_refs { Some(refs.total()) } else { None },
+                    refs: if with_refs { Some(refs) } else { None },
+                    granted_by: None,
+                    granted_at: None,
+                    granted_at_height: None,
+                    metadata: None,
+                    ancestors: None,
+                    descendants: None,
                 })
             }
         },
         Subject::Role(role) => {
+            let role = to_cannonical_role(deps.storage, role)?;
             for result in ROLE_PATHS
                 .prefix(&role)
-                .keys(deps.storage, min_bound, max_bound, Order::Ascending)
-                .take(limit)
+                .range(deps.storage, min_bound, max_bound, Order::Ascending)
+                .take(fetch_limit)
             {
-                let path = result?;
+                let (
+                    path,
+                    AuthRecord {
+                        expires_at,
+                        granted_by,
+                        granted_at,
+                        granted_at_height,
+                        metadata,
+                        ..
+                    },
+                ) = result?;
+                let refs = if with_refs {
+                    PATH_REF_COUNTS.may_load(deps.storage, &path)?
+                } else {
+                    None
+                };
                 path_infos.push(PathInfo {
                     path,
-                    expires_at: None,
+                    remaining_secs: remaining_secs(env.block.time, expires_at),
+                    expires_at,
+                    ref_count: refs.as_ref().map(|r| r.total()),
+                    refs,
+                    granted_by: Some(granted_by),
+                    granted_at: Some(granted_at),
+                    granted_at_height: Some(granted_at_height),
+                    metadata,
+                    ancestors: None,
+                    descendants: None,
                 })
             }
         },
         Subject::Principal(principal) => {
             for result in PRINCIPAL_PATH_AUTHORIZATIONS
                 .prefix(&principal)
-                .range(deps.storage, min_bound, max_bound, Order::Ascending)
-                .take(limit)
+                .range(
+                    deps.storage,
+                    min_bound.clone(),
+                    max_bound.clone(),
+                    Order::Ascending,
+                )
+                .take(fetch_limit)
             {
-                let (path, AuthRecord { expires_at }) = result?;
-                path_infos.push(PathInfo { path, expires_at })
+                let (
+                    path,
+                    AuthRecord {
+                        expires_at,
+                        granted_by,
+                        granted_at,
+                        granted_at_height,
+                        metadata,
+                        ..
+                    },
+                ) = result?;
+                path_infos.push(PathInfo {
+                    path,
+                    remaining_secs: remaining_secs(env.block.time, expires_at),
+                    expires_at,
+                    ref_count: None,
+                    refs: None,
+                    granted_by: Some(granted_by),
+                    granted_at: Some(granted_at),
+                    granted_at_height: Some(granted_at_height),
+                    metadata,
+                    ancestors: None,
+                    descendants: None,
+                })
+            }
+
+            // Merge in paths reachable via the principal's roles, which are
+            // ordered by path like direct grants.
+            if include_roles.unwrap_or(false) {
+                let mut merged: BTreeMap<String, PathInfo> = path_infos
+                    .drain(..)
+                    .map(|info| (info.path.to_owned(), info))
+                    .collect();
+
+                for result in PRINCIPAL_ROLE_AUTHORIZATIONS.prefix(&principal).range(
+                    deps.storage,
+                    None,
+                    None,
+                    Order::Ascending,
+                ) {
+                    let (role, grant) = result?;
+                    for result in ROLE_PATHS
+                        .prefix(&role)
+                        .range(
+                            deps.storage,
+                            min_bound.clone(),
+                            max_bound.clone(),
+                            Order::Ascending,
+                        )
+                        .take(fetch_limit)
+                    {
+                        let (path, link) = result?;
+
+                        // Access via a role lasts until either the role grant
+                        // or the role's link to the path expires.
+                        let expires_at = [grant.expires_at, link.expires_at]
+                            .into_iter()
+                            .flatten()
+                            .min();
+
+                        // Where a path is reachable more than once, keep the
+                        // longest-lived access.
+                        if let Some(existing) = merged.get(&path) {
+                            match (existing.expires_at, expires_at) {
+                                (None, _) => continue,
+                                (Some(a), Some(b)) if a >= b => continue,
+                                _ => {},
+                            }
+                        }
+
+                        merged.insert(
+                            path.to_owned(),
+                            PathInfo {
+                                path,
+                                remaining_secs: remaining_secs(env.block.time, expires_at),
+                                expires_at,
+                                ref_count: None,
+                                refs: None,
+                                granted_by: Some(grant.granted_by.to_owned()),
+                                granted_at: Some(grant.granted_at),
+                                granted_at_height: Some(grant.granted_at_height),
+                                metadata: None,
+                                ancestors: None,
+                                descendants: None,
+                            },
+                        );
+                    }
+                }
+
+                path_infos = merged.into_values().take(fetch_limit).collect();
             }
         },
     }
 
-    let next_cursor = if path_infos.len() == limit {
-        path_infos
-            .last()
-            .and_then(|info| Some(info.path.to_owned()))
+    let truncated = by_expiry && path_infos.len() > MAX_LIMIT as usize;
+
+    let next_cursor = if by_expiry {
+        path_infos.truncate(MAX_LIMIT as usize);
+        path_infos.sort_by_key(|info| (info.expires_at.is_none(), info.expires_at));
+        path_infos.truncate(limit);
+        None
+    } else if path_infos.len() > limit {
+        path_infos.truncate(limit);
+        path_infos.last().map(|info| info.path.to_owned())
     } else {
         None
     };
 
+    // Only the paths being returned are expanded
+    if expand != Expansion::None {
+        for info in path_infos.iter_mut() {
+            match expand {
+                Expansion::Ancestors => {
+                    info.ancestors = Some(to_ancestors(&config.path_style, &info.path))
+                },
+                Expansion::Descendants => {
+                    info.descendants = Some(load_descendants(deps.storage, &info.path)?)
+                },
+                Expansion::None => {},
+            }
+        }
+    }
+
     Ok(PathsResponse {
         paths: path_infos,
         cursor: next_cursor,
+        approx_total,
+        truncated,
     })
 }
+
+/// Ancestors of a canonical path, nearest first. As when authorizing, the root
+/// is only an ancestor of itself.
+fn to_ancestors(
+    style: &PathStyle,
+    path: &str,
+) -> Vec<String> {
+    let mut crumbs = to_crumbs(path);
+    let mut ancestors: Vec<String> = Vec::with_capacity(crumbs.len());
+    while crumbs.len() > 1 {
+        crumbs.pop();
+        ancestors.push(to_styled_path_from_crumbs(style, &crumbs));
+    }
+    ancestors
+}
+
+/// Descendants of a canonical path referenced by any grant or role, up to
+/// MAX_DESCENDANTS.
+fn load_descendants(
+    store: &dyn Storage,
+    path: &String,
+) -> Result<Vec<String>, ContractError> {
+    // The root's children share its prefix, "/" when rooted and "" when bare
+    let child_prefix = if path.is_empty() || path == "/" {
+        path.to_owned()
+    } else {
+        format!("{}/", path)
+    };
+    let mut descendants: Vec<String> = Vec::with_capacity(4);
+    for result in PATH_REF_COUNTS.keys(
+        store,
+        Some(Bound::inclusive(&child_prefix)),
+        None,
+        Order::Ascending,
+    ) {
+        let descendant = result?;
+        if !descendant.starts_with(&child_prefix) || descendants.len() == MAX_DESCENDANTS {
+            break;
+        }
+        if descendant != *path {
+            descendants.push(descendant);
+        }
+    }
+    Ok(descendants)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        testing::{mock_env, mock_info},
+        Addr,
+    };
+
+    use super::{query_paths, MAX_LIMIT};
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        models::PathRefCounts,
+        msg::{
+            AllowMsg, DenyMsg, DenyRoleMsg, ExecuteMsg, Expansion, GrantRoleMsg, OrderBy,
+            PathsQueryParams, QueryMsg, RoleExecuteMsg, Subject,
+        },
+        query::ReadonlyContext,
+        responses::{AclResponse, PathsResponse},
+        state::{migrate_grant_heights, PRINCIPAL_PATH_AUTHORIZATIONS},
+        testing::{
+            allow, allow_msg, create_role, exec, grant_role_msg, query_as, setup, MockDeps,
+            OPERATOR,
+        },
+    };
+
+    fn params(subject: Subject) -> PathsQueryParams {
+        PathsQueryParams {
+            subject,
+            limit: None,
+            start: None,
+            stop: None,
+            cursor: None,
+            with_refs: None,
+            order_by: None,
+            include_roles: None,
+            expand: None,
+        }
+    }
+
+    fn paths(
+        deps: &MockDeps,
+        params: PathsQueryParams,
+    ) -> PathsResponse {
+        query_as(deps.as_ref(), QueryMsg::Paths(params)).unwrap()
+    }
+
+    #[test]
+    fn ref_counts_match_referencing_principals_and_roles() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs", "/wiki"]);
+        create_role(deps.as_mut(), "viewers", &["/docs"]);
+        allow(deps.as_mut(), "alice", "/docs");
+
+        for subject in [Subject::Acl, Subject::Role("editors".to_owned())] {
+            let resp = paths(
+                &deps,
+                PathsQueryParams {
+                    with_refs: Some(true),
+                    ..params(subject)
+                },
+            );
+            let counts: Vec<(&str, Option<u32>, Option<PathRefCounts>)> = resp
+                .paths
+                .iter()
+                .map(|p| (p.path.as_str(), p.ref_count, p.refs.clone()))
+                .collect();
+            assert_eq!(
+                counts,
+                vec![
+                    (
+                        "/docs",
+                        Some(3),
+                        Some(PathRefCounts {
+                            principals: 1,
+                            roles: 2
+                        })
+                    ),
+                    (
+                        "/wiki",
+                        Some(1),
+                        Some(PathRefCounts {
+                            principals: 0,
+                            roles: 1
+                        })
+                    ),
+                ]
+            );
+        }
+
+        // Without the flag, no counts are read
+        let resp = paths(&deps, params(Subject::Acl));
+        assert!(resp.paths.iter().all(|p| p.ref_count.is_none()));
+    }
+
+    #[test]
+    fn principal_paths_report_who_granted_them_and_when() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/docs");
+
+        let resp = paths(&deps, params(Subject::Principal("alice".to_owned())));
+        let env = mock_env();
+        assert_eq!(resp.paths.len(), 1);
+        assert_eq!(resp.paths[0].granted_by, Some(Addr::unchecked(OPERATOR)));
+        assert_eq!(resp.paths[0].granted_at, Some(env.block.time));
+    }
+
+    #[test]
+    fn paths_report_remaining_ttl() {
+        let mut deps = setup();
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(7200),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+        allow(deps.as_mut(), "alice", "/wiki");
+
+        let subject = || params(Subject::Principal("alice".to_owned()));
+        let remaining = |resp: PathsResponse| -> Vec<Option<u64>> {
+            resp.paths.iter().map(|p| p.remaining_secs).collect()
+        };
+
+        assert_eq!(remaining(paths(&deps, subject())), vec![Some(7200), None]);
+
+        // Measured from the block time, and never negative once expired
+        let mut env = mock_env();
+        for (elapsed, expected) in [(7000, Some(200)), (7200, Some(0)), (9000, Some(0))] {
+            env.block.time = mock_env().block.time.plus_seconds(elapsed);
+            let ctx = ReadonlyContext {
+                deps: deps.as_ref(),
+                env: env.clone(),
+            };
+            assert_eq!(
+                remaining(query_paths(ctx, subject()).unwrap()),
+                vec![expected, None]
+            );
+        }
+    }
+
+    #[test]
+    fn principal_paths_order_by_expiry_with_permanent_last() {
+        let mut deps = setup();
+        for (path, ttl) in [("/a", None), ("/b", Some(7200)), ("/c", Some(3600))] {
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Allow(AllowMsg {
+                    ttl,
+                    ..allow_msg("alice", path)
+                }),
+            )
+            .unwrap();
+        }
+
+        let resp = paths(
+            &deps,
+            PathsQueryParams {
+                order_by: Some(OrderBy::ExpiryAsc),
+                ..params(Subject::Principal("alice".to_owned()))
+            },
+        );
+        let order: Vec<&str> = resp.paths.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(order, vec!["/c", "/b", "/a"]);
+        assert_eq!(resp.cursor, None);
+        assert!(!resp.truncated);
+    }
+
+    #[test]
+    fn ordering_by_expiry_reports_truncation() {
+        let mut deps = setup();
+        let by_expiry = || PathsQueryParams {
+            order_by: Some(OrderBy::ExpiryAsc),
+            ..params(Subject::Principal("alice".to_owned()))
+        };
+        for i in 0..MAX_LIMIT {
+            allow(deps.as_mut(), "alice", &format!("/p{:03}", i));
+        }
+        assert!(!paths(&deps, by_expiry()).truncated);
+
+        // A grant beyond the paths read is left out, though it expires first
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(60),
+                ..allow_msg("alice", "/z")
+            }),
+        )
+        .unwrap();
+        let resp = paths(&deps, by_expiry());
+        assert!(resp.truncated);
+        assert!(resp.paths.iter().all(|p| p.path != "/z"));
+    }
+
+    #[test]
+    fn principal_paths_optionally_include_role_paths() {
+        let mut deps = setup();
+        let now = mock_env().block.time;
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(1000),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+        create_role(deps.as_mut(), "editors", &["/docs", "/wiki"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(3600),
+                ..grant_role_msg("alice", "editors")
+            })),
+        )
+        .unwrap();
+
+        let reachable = |include_roles: Option<bool>| -> Vec<(String, Option<u64>)> {
+            paths(
+                &deps,
+                PathsQueryParams {
+                    include_roles,
+                    ..params(Subject::Principal("alice".to_owned()))
+                },
+            )
+            .paths
+            .into_iter()
+            .map(|p| (p.path, p.expires_at.map(|t| t.seconds() - now.seconds())))
+            .collect()
+        };
+
+        assert_eq!(reachable(None), vec![("/docs".to_owned(), Some(1000))]);
+        // A path reachable both ways reports the longer-lived access
+        assert_eq!(
+            reachable(Some(true)),
+            vec![
+                ("/docs".to_owned(), Some(3600)),
+                ("/wiki".to_owned(), Some(3600)),
+            ]
+        );
+    }
+
+    #[test]
+    fn last_full_page_has_no_cursor() {
+        let mut deps = setup();
+        for path in ["/a", "/b", "/c", "/d"] {
+            allow(deps.as_mut(), "alice", path);
+        }
+
+        for subject in [Subject::Acl, Subject::Principal("alice".to_owned())] {
+            let first = paths(
+                &deps,
+                PathsQueryParams {
+                    limit: Some(2),
+                    ..params(subject.clone())
+                },
+            );
+            assert_eq!(first.paths.len(), 2);
+            assert!(first.cursor.is_some());
+
+            let second = paths(
+                &deps,
+                PathsQueryParams {
+                    limit: Some(2),
+                    cursor: first.cursor,
+                    ..params(subject)
+                },
+            );
+            assert_eq!(second.paths.len(), 2);
+            assert_eq!(second.cursor, None);
+        }
+    }
+
+    #[test]
+    fn paths_report_grant_height() {
+        let mut deps = setup();
+        let mut env = mock_env();
+        env.block.height += 100;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OPERATOR, &[]),
+            ExecuteMsg::Allow(allow_msg("alice", "/docs")),
+        )
+        .unwrap();
+
+        let height = |deps: &MockDeps| {
+            paths(deps, params(Subject::Principal("alice".to_owned()))).paths[0].granted_at_height
+        };
+        assert_eq!(height(&deps), Some(env.block.height));
+
+        // Grants from before heights were recorded get the ACL's creation height
+        let (principal, path) = ("alice".to_owned(), "/docs".to_owned());
+        let mut record = PRINCIPAL_PATH_AUTHORIZATIONS
+            .load(&deps.storage, (&principal, &path))
+            .unwrap();
+        record.granted_at_height = 0;
+        PRINCIPAL_PATH_AUTHORIZATIONS
+            .save(&mut deps.storage, (&principal, &path), &record)
+            .unwrap();
+        migrate_grant_heights(&mut deps.storage, env.block.height + 100).unwrap();
+        assert_eq!(height(&deps), Some(mock_env().block.height));
+    }
+
+    #[test]
+    fn ref_counts_split_by_source_as_references_leave() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/direct");
+        create_role(deps.as_mut(), "editors", &["/linked", "/mixed"]);
+        allow(deps.as_mut(), "alice", "/mixed");
+
+        let refs = |deps: &MockDeps| -> Vec<(String, PathRefCounts)> {
+            paths(
+                deps,
+                PathsQueryParams {
+                    with_refs: Some(true),
+                    ..params(Subject::Acl)
+                },
+            )
+            .paths
+            .into_iter()
+            .map(|p| (p.path, p.refs.unwrap()))
+            .collect()
+        };
+        let counts = |principals, roles| PathRefCounts { principals, roles };
+
+        assert_eq!(
+            refs(&deps),
+            vec![
+                ("/direct".to_owned(), counts(1, 0)),
+                ("/linked".to_owned(), counts(0, 1)),
+                ("/mixed".to_owned(), counts(1, 1)),
+            ]
+        );
+
+        for path in ["/direct", "/mixed"] {
+            exec(
+                deps.as_mut(),
+                OPERATOR,
+                ExecuteMsg::Deny(DenyMsg {
+                    principal: "alice".to_owned(),
+                    path: path.to_owned(),
+                    force: None,
+                }),
+            )
+            .unwrap();
+        }
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Deny(DenyRoleMsg {
+                role: "editors".to_owned(),
+                path: "/linked".to_owned(),
+                strict: None,
+                force: None,
+            })),
+        )
+        .unwrap();
+
+        // Only the path still linked to a role remains
+        assert_eq!(refs(&deps), vec![("/mixed".to_owned(), counts(0, 1))]);
+    }
+
+    #[test]
+    fn role_paths_expand_to_ancestors_and_referenced_descendants() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs/reports/q1", "/wiki"]);
+        allow(deps.as_mut(), "alice", "/wiki/intro");
+        allow(deps.as_mut(), "alice", "/wikis");
+
+        let expanded = |deps: &MockDeps, expand| {
+            paths(
+                deps,
+                PathsQueryParams {
+                    expand: Some(expand),
+                    ..params(Subject::Role("editors".to_owned()))
+                },
+            )
+            .paths
+        };
+
+        let infos = expanded(&deps, Expansion::Ancestors);
+        assert_eq!(
+            infos
+                .iter()
+                .map(|info| (info.path.as_str(), info.ancestors.clone().unwrap()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/docs/reports/q1",
+                    vec!["/docs/reports".to_owned(), "/docs".to_owned()]
+                ),
+                ("/wiki", vec![]),
+            ]
+        );
+        assert!(infos.iter().all(|info| info.descendants.is_none()));
+
+        // Only referenced paths beneath each path, and not mere prefix matches
+        let infos = expanded(&deps, Expansion::Descendants);
+        assert_eq!(infos[0].descendants, Some(vec![]));
+        assert_eq!(infos[1].descendants, Some(vec!["/wiki/intro".to_owned()]));
+        assert!(infos.iter().all(|info| info.ancestors.is_none()));
+    }
+
+    #[test]
+    fn cursors_round_trip_for_non_canonical_grants() {
+        let mut deps = setup();
+        for path in ["docs a", "/docs  b/", "//docs\u{7}c"] {
+            allow(deps.as_mut(), "alice", path);
+        }
+
+        let subject = || Subject::Principal("alice".to_owned());
+        let mut cursor = None;
+        let mut returned: Vec<String> = vec![];
+        loop {
+            let resp = paths(
+                &deps,
+                PathsQueryParams {
+                    limit: Some(1),
+                    cursor: cursor.take(),
+                    ..params(subject())
+                },
+            );
+            returned.extend(resp.paths.into_iter().map(|info| info.path));
+            match resp.cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(returned, vec!["/docs--b", "/docs-a", "/docsc"]);
+
+        // Raw bounds select the same paths as their stored forms
+        let resp = paths(
+            &deps,
+            PathsQueryParams {
+                start: Some("docs a".to_owned()),
+                ..params(subject())
+            },
+        );
+        assert_eq!(
+            resp.paths
+                .into_iter()
+                .map(|info| info.path)
+                .collect::<Vec<_>>(),
+            vec!["/docs-a", "/docsc"]
+        );
+    }
+
+    #[test]
+    fn approx_total_matches_maintained_counts() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs", "/wiki"]);
+        allow(deps.as_mut(), "alice", "/docs");
+        allow(deps.as_mut(), "alice", "/blog");
+
+        // The total covers every path, not just the page
+        let resp = paths(
+            &deps,
+            PathsQueryParams {
+                limit: Some(1),
+                ..params(Subject::Acl)
+            },
+        );
+        let acl: AclResponse = query_as(deps.as_ref(), QueryMsg::Acl {}).unwrap();
+        assert_eq!(resp.paths.len(), 1);
+        assert_eq!((resp.approx_total, acl.n_paths), (Some(3), 3));
+
+        let resp = paths(&deps, params(Subject::Role("editors".to_owned())));
+        assert_eq!(resp.approx_total, Some(2));
+        let resp = paths(&deps, params(Subject::Principal("alice".to_owned())));
+        assert_eq!(resp.approx_total, None);
+    }
+
+    #[test]
+    fn reversed_bounds_are_rejected() {
+        let mut deps = setup();
+        for path in ["/a", "/b", "/c"] {
+            allow(deps.as_mut(), "alice", path);
+        }
+        let bounded = |start: &str, stop: &str| {
+            query_as::<PathsResponse>(
+                deps.as_ref(),
+                QueryMsg::Paths(PathsQueryParams {
+                    start: Some(start.to_owned()),
+                    stop: Some(stop.to_owned()),
+                    ..params(Subject::Acl)
+                }),
+            )
+        };
+
+        // Bounds are compared in canonical form
+        assert!(matches!(
+            bounded("/c", "b "),
+            Err(ContractError::ValidationError { .. })
+        ));
+        assert_eq!(bounded("/b", "/b").unwrap().paths.len(), 1);
+        assert_eq!(bounded("a", "/c").unwrap().paths.len(), 3);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{
+    error::ContractError,
+    responses::{AuthMatch, AuthSource, ResolveResponse},
+    state::{CONFIG, PRINCIPAL_PATH_AUTHORIZATIONS, ROLE_PATHS},
+    utils::resolve_principal,
+};
+
+use super::{
+    is_allowed::{load_principal_roles, try_authorize_path},
+    ReadonlyContext,
+};
+
+/// Resolve a principal's access to a path in one call: whether it's allowed,
+/// by which grant and at which path, and until when, or why not.
+pub fn query_resolve(
+    ctx: ReadonlyContext,
+    principal: String,
+    path: String,
+) -> Result<ResolveResponse, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+    let config = CONFIG.load(deps.storage)?;
+
+    let AuthMatch { matched_path, via } =
+        match try_authorize_path(deps, &config, env.block.time, &principal, &path) {
+            Ok(auth_match) => auth_match,
+            Err(failure) => {
+                return Ok(ResolveResponse {
+                    allowed: false,
+                    matched_path: None,
+                    via: None,
+                    expires_at: None,
+                    reason: Some(failure.reason),
+                })
+            },
+        };
+
+    let principal = resolve_principal(deps.storage, &principal);
+
+    let expires_at = match &via {
+        AuthSource::Direct => PRINCIPAL_PATH_AUTHORIZATIONS
+            .may_load(deps.storage, (&principal, &matched_path))?
+            .and_then(|record| record.expires_at),
+        // Access via a role lasts until either the role grant or the role's
+        // link to the path expires.
+        AuthSource::Role(role) => {
+            let grant_expires_at = load_principal_roles(deps.storage, &principal)
+                .into_iter()
+                .find(|(r, _)| r == role)
+                .and_then(|(_, grant)| grant.expires_at);
+            let link_expires_at = ROLE_PATHS
+                .may_load(deps.storage, (role, &matched_path))?
+                .and_then(|link| link.expires_at);
+            [grant_expires_at, link_expires_at]
+                .into_iter()
+                .flatten()
+                .min()
+        },
+        _ => None,
+    };
+
+    Ok(ResolveResponse {
+        allowed: true,
+        matched_path: Some(matched_path),
+        via: Some(via),
+        expires_at,
+        reason: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_env;
+
+    use crate::{
+        msg::{AllowMsg, ExecuteMsg, GrantRoleMsg, QueryMsg, RoleExecuteMsg},
+        responses::{AuthSource, ResolveResponse},
+        testing::{
+            allow_msg, create_role, exec, forbid, grant_role, grant_role_msg, query_as, setup,
+            MockDeps, OPERATOR,
+        },
+    };
+
+    fn resolve(
+        deps: &MockDeps,
+        principal: &str,
+        path: &str,
+    ) -> ResolveResponse {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::Resolve {
+                principal: principal.to_owned(),
+                path: path.to_owned(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_source_matched_path_and_expiry() {
+        let mut deps = setup();
+        let now = mock_env().block.time;
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl: Some(60),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+        create_role(deps.as_mut(), "editors", &["/wiki"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(120),
+                ..grant_role_msg("bob", "editors")
+            })),
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolve(&deps, "alice", "/docs/reports"),
+            ResolveResponse {
+                allowed: true,
+                matched_path: Some("/docs".to_owned()),
+                via: Some(AuthSource::Direct),
+                expires_at: Some(now.plus_seconds(60)),
+                reason: None,
+            }
+        );
+        assert_eq!(
+            resolve(&deps, "bob", "/wiki/intro"),
+            ResolveResponse {
+                allowed: true,
+                matched_path: Some("/wiki".to_owned()),
+                via: Some(AuthSource::Role("editors".to_owned())),
+                expires_at: Some(now.plus_seconds(120)),
+                reason: None,
+            }
+        );
+    }
+
+    #[test]
+    fn denied_access_reports_only_a_reason() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/wiki"]);
+        grant_role(deps.as_mut(), "bob", "editors");
+        forbid(deps.as_mut(), "bob", "/wiki");
+
+        for principal in ["alice", "bob"] {
+            let resp = resolve(&deps, principal, "/wiki");
+            assert!(!resp.allowed);
+            assert_eq!(
+                (resp.matched_path, resp.via, resp.expires_at),
+                (None, None, None)
+            );
+            assert!(resp.reason.is_some(), "{}", principal);
+        }
+    }
+}
@@ -1,28 +1,215 @@
+use cosmwasm_std::Order;
+use cw_storage_plus::Bound;
+
 use crate::{
-    error::ContractError, models::AuthRoleInfo, responses::RoleResponse, state::ROLE_INFOS,
+    error::ContractError,
+    models::AuthRoleInfo,
+    responses::RoleResponse,
+    state::{ROLE_INFOS, ROLE_PATHS},
+    utils::to_cannonical_role,
 };
 
 use super::ReadonlyContext;
 
+/// Number of paths included by default, and at most, with a role. Further
+/// paths are paged through from the returned cursor.
+const DEFAULT_LIMIT: usize = 100;
+
 pub fn query_role(
     ctx: ReadonlyContext,
     role: String,
 ) -> Result<RoleResponse, ContractError> {
     let ReadonlyContext { deps, .. } = ctx;
+    let role = to_cannonical_role(deps.storage, role)?;
 
     let AuthRoleInfo {
         description,
         created_at,
         created_by,
         n_principals,
-    } = ROLE_INFOS.load(deps.storage, &role)?;
+        is_template,
+        n_paths,
+        updated_at,
+        weight,
+    } = ROLE_INFOS
+        .may_load(deps.storage, &role)?
+        .ok_or_else(|| ContractError::RoleNotFound { role: role.clone() })?;
 
     Ok(RoleResponse {
         expires_at: None,
+        remaining_secs: None,
         name: role,
         description,
         created_at,
         created_by,
         n_principals,
+        is_template,
+        n_paths,
+        updated_at,
+        weight,
+        granted_by: None,
+        granted_at: None,
+        paths: None,
+        paths_cursor: None,
     })
 }
+
+pub fn query_role_with_paths(
+    ctx: ReadonlyContext,
+    role: String,
+    limit: Option<u16>,
+    cursor: Option<String>,
+) -> Result<RoleResponse, ContractError> {
+    let deps = ctx.deps;
+    let mut resp = query_role(ctx, role)?;
+    let limit = limit
+        .map_or(DEFAULT_LIMIT, |n| n as usize)
+        .clamp(1, DEFAULT_LIMIT);
+
+    let mut paths = ROLE_PATHS
+        .prefix(&resp.name)
+        .keys(
+            deps.storage,
+            cursor.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit + 1)
+        .collect::<Result<Vec<String>, _>>()?;
+
+    if paths.len() > limit {
+        paths.truncate(limit);
+        resp.paths_cursor = paths.last().cloned();
+    }
+    resp.paths = Some(paths);
+
+    Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::from_json;
+
+    use super::DEFAULT_LIMIT;
+    use crate::{
+        error::ContractError,
+        msg::{PathsQueryParams, QueryMsg, Subject},
+        responses::{PathsResponse, RoleResponse},
+        testing::{create_role, query_as, setup, MockDeps},
+    };
+
+    #[test]
+    fn missing_role_is_not_found() {
+        let deps = setup();
+        let result = query_as::<RoleResponse>(deps.as_ref(), QueryMsg::Role("ghosts".to_owned()));
+        assert!(matches!(
+            result,
+            Err(ContractError::RoleNotFound { role }) if role == "ghosts"
+        ));
+    }
+
+    fn role_with_paths(
+        deps: &MockDeps,
+        limit: Option<u16>,
+        cursor: Option<String>,
+    ) -> RoleResponse {
+        query_as(
+            deps.as_ref(),
+            QueryMsg::RoleWithPaths {
+                name: "editors".to_owned(),
+                limit,
+                cursor,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn role_query_still_takes_a_bare_name() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/wiki"]);
+
+        let msg: QueryMsg = from_json(br#"{"role":"editors"}"#).unwrap();
+        assert_eq!(msg, QueryMsg::Role("editors".to_owned()));
+        let resp: RoleResponse = query_as(deps.as_ref(), msg).unwrap();
+        assert_eq!(resp.n_paths, 1);
+    }
+
+    #[test]
+    fn paths_are_included_only_on_request() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/wiki", "/docs", "/blog"]);
+
+        let resp: RoleResponse =
+            query_as(deps.as_ref(), QueryMsg::Role("editors".to_owned())).unwrap();
+        assert_eq!((resp.paths, resp.paths_cursor), (None, None));
+
+        let resp = role_with_paths(&deps, None, None);
+        assert_eq!(
+            resp.paths,
+            Some(vec![
+                "/blog".to_owned(),
+                "/docs".to_owned(),
+                "/wiki".to_owned()
+            ])
+        );
+        assert_eq!(resp.paths_cursor, None);
+    }
+
+    #[test]
+    fn truncated_paths_continue_from_cursor() {
+        let mut deps = setup();
+        let all_paths: Vec<String> = (0..=DEFAULT_LIMIT)
+            .map(|i| format!("/p/{:03}", i))
+            .collect();
+        let refs: Vec<&str> = all_paths.iter().map(|p| p.as_str()).collect();
+        create_role(deps.as_mut(), "editors", &refs);
+
+        let resp = role_with_paths(&deps, None, None);
+        assert_eq!(resp.paths.as_deref(), Some(&all_paths[..DEFAULT_LIMIT]));
+        assert_eq!(
+            resp.paths_cursor,
+            Some(all_paths[DEFAULT_LIMIT - 1].to_owned())
+        );
+
+        let rest: PathsResponse = query_as(
+            deps.as_ref(),
+            QueryMsg::Paths(PathsQueryParams {
+                subject: Subject::Role("editors".to_owned()),
+                limit: None,
+                start: None,
+                stop: None,
+                cursor: resp.paths_cursor,
+                with_refs: None,
+                order_by: None,
+                include_roles: None,
+                expand: None,
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            rest.paths
+                .into_iter()
+                .map(|info| info.path)
+                .collect::<Vec<_>>(),
+            vec![all_paths[DEFAULT_LIMIT].to_owned()]
+        );
+    }
+
+    #[test]
+    fn paths_page_from_cursor() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/wiki", "/docs", "/blog"]);
+
+        let first = role_with_paths(&deps, Some(2), None);
+        assert_eq!(
+            first.paths,
+            Some(vec!["/blog".to_owned(), "/docs".to_owned()])
+        );
+        assert_eq!(first.paths_cursor, Some("/docs".to_owned()));
+
+        let rest = role_with_paths(&deps, Some(2), first.paths_cursor);
+        assert_eq!(rest.paths, Some(vec!["/wiki".to_owned()]));
+        assert_eq!(rest.paths_cursor, None);
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     models::{AuthRecord, AuthRoleInfo},
     responses::{RoleResponse, RolesResponse},
     state::{PRINCIPAL_ROLE_AUTHORIZATIONS, ROLE_INFOS},
+    utils::remaining_secs,
 };
 
 use super::ReadonlyContext;
@@ -13,7 +14,7 @@ pub fn query_roles(
     ctx: ReadonlyContext,
     principal: Option<String>,
 ) -> Result<RolesResponse, ContractError> {
-    let ReadonlyContext { deps, .. } = ctx;
+    let ReadonlyContext { deps, env, .. } = ctx;
 
     let mut role_resps: Vec<RoleResponse> = Vec::with_capacity(2);
 
@@ -25,20 +26,45 @@ pub fn query_roles(
             None,
             Order::Ascending,
         ) {
-            let (name, AuthRecord { expires_at }) = result?;
+            let (
+                name,
+                AuthRecord {
+                    expires_at,
+                    granted_by,
+                    granted_at,
+                    ..
+                },
+            ) = result?;
+            // Skip roles that have since been removed
             let AuthRoleInfo {
                 description,
                 created_at,
                 created_by,
                 n_principals,
-            } = ROLE_INFOS.load(deps.storage, &name)?;
+                is_template,
+                n_paths,
+                updated_at,
+                weight,
+            } = match ROLE_INFOS.may_load(deps.storage, &name)? {
+                Some(info) => info,
+                None => continue,
+            };
             role_resps.push(RoleResponse {
+                paths: None,
+                paths_cursor: None,
+                remaining_secs: remaining_secs(env.block.time, expires_at),
                 expires_at,
                 description,
                 created_at,
                 created_by,
                 n_principals,
+                is_template,
+                n_paths,
+                updated_at,
+                weight,
                 name,
+                granted_by: Some(granted_by),
+                granted_at: Some(granted_at),
             });
         }
     } else {
@@ -51,19 +77,94 @@ pub fn query_roles(
                     created_at,
                     created_by,
                     n_principals,
+                    is_template,
+                    n_paths,
+                    updated_at,
+                    weight,
                 },
             ) = result?;
 
             role_resps.push(RoleResponse {
                 expires_at: None,
+                remaining_secs: None,
                 description,
                 created_at,
                 created_by,
                 n_principals,
+                is_template,
+                n_paths,
+                updated_at,
+                weight,
                 name,
+                granted_by: None,
+                granted_at: None,
+                paths: None,
+                paths_cursor: None,
             });
         }
     }
 
     Ok(RolesResponse(role_resps))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        msg::{ExecuteMsg, GrantRoleMsg, QueryMsg, RoleExecuteMsg},
+        responses::RolesResponse,
+        state::ROLE_INFOS,
+        testing::{create_role, exec, grant_role, grant_role_msg, query_as, setup, OPERATOR},
+    };
+
+    #[test]
+    fn principal_roles_skip_removed_roles() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        create_role(deps.as_mut(), "viewers", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        grant_role(deps.as_mut(), "alice", "viewers");
+
+        // As if the role were removed without its grants being cleaned up
+        ROLE_INFOS.remove(deps.as_mut().storage, &"editors".to_owned());
+
+        let RolesResponse(roles) = query_as(
+            deps.as_ref(),
+            QueryMsg::Roles {
+                principal: Some("alice".to_owned()),
+            },
+        )
+        .unwrap();
+        let names: Vec<&str> = roles.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["viewers"]);
+    }
+
+    #[test]
+    fn principal_roles_report_remaining_ttl() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        create_role(deps.as_mut(), "viewers", &["/docs"]);
+        exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Role(RoleExecuteMsg::Grant(GrantRoleMsg {
+                ttl: Some(3600),
+                ..grant_role_msg("alice", "editors")
+            })),
+        )
+        .unwrap();
+        grant_role(deps.as_mut(), "alice", "viewers");
+
+        let RolesResponse(roles) = query_as(
+            deps.as_ref(),
+            QueryMsg::Roles {
+                principal: Some("alice".to_owned()),
+            },
+        )
+        .unwrap();
+        let remaining: Vec<(&str, Option<u64>)> = roles
+            .iter()
+            .map(|r| (r.name.as_str(), r.remaining_secs))
+            .collect();
+        assert_eq!(remaining, vec![("editors", Some(3600)), ("viewers", None)]);
+    }
+}
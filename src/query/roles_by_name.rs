@@ -0,0 +1,121 @@
+use crate::{
+    error::ContractError,
+    models::AuthRoleInfo,
+    responses::{RoleResponse, RolesResponse},
+    state::ROLE_INFOS,
+    utils::to_cannonical_role,
+};
+
+use super::ReadonlyContext;
+
+const MAX_NAMES: usize = 100;
+
+pub fn query_roles_by_name(
+    ctx: ReadonlyContext,
+    names: Vec<String>,
+    strict: Option<bool>,
+) -> Result<RolesResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let strict = strict.unwrap_or(false);
+
+    if names.len() > MAX_NAMES {
+        return Err(ContractError::ValidationError {
+            reason: format!("cannot query more than {} roles by name", MAX_NAMES),
+        });
+    }
+
+    let mut role_resps: Vec<RoleResponse> = Vec::with_capacity(names.len());
+
+    for name in names {
+        let name = to_cannonical_role(deps.storage, name)?;
+        let AuthRoleInfo {
+            description,
+            created_at,
+            created_by,
+            n_principals,
+            is_template,
+            n_paths,
+            updated_at,
+            weight,
+        } = match ROLE_INFOS.may_load(deps.storage, &name)? {
+            Some(info) => info,
+            None if strict => return Err(ContractError::RoleNotFound { role: name }),
+            None => continue,
+        };
+
+        role_resps.push(RoleResponse {
+            expires_at: None,
+            remaining_secs: None,
+            description,
+            created_at,
+            created_by,
+            n_principals,
+            is_template,
+            n_paths,
+            updated_at,
+            weight,
+            name,
+            granted_by: None,
+            granted_at: None,
+            paths: None,
+            paths_cursor: None,
+        });
+    }
+
+    Ok(RolesResponse(role_resps))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ContractError,
+        msg::QueryMsg,
+        responses::RolesResponse,
+        testing::{create_role, query_as, setup},
+    };
+
+    fn roles_by_name(
+        names: &[&str],
+        strict: Option<bool>,
+    ) -> QueryMsg {
+        QueryMsg::RolesByName {
+            names: names.iter().map(|n| n.to_string()).collect(),
+            strict,
+        }
+    }
+
+    #[test]
+    fn missing_names_are_omitted_unless_strict() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        create_role(deps.as_mut(), "viewers", &[]);
+
+        let names = ["viewers", "missing", "editors"];
+
+        let RolesResponse(roles) = query_as(deps.as_ref(), roles_by_name(&names, None)).unwrap();
+        let found: Vec<&str> = roles.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(found, vec!["viewers", "editors"]);
+        assert_eq!(roles[1].n_paths, 1);
+
+        let result = query_as::<RolesResponse>(deps.as_ref(), roles_by_name(&names, Some(true)));
+        assert!(matches!(
+            result,
+            Err(ContractError::RoleNotFound { role }) if role == "missing"
+        ));
+
+        let RolesResponse(roles) = query_as(
+            deps.as_ref(),
+            roles_by_name(&["editors", "viewers"], Some(true)),
+        )
+        .unwrap();
+        assert_eq!(roles.len(), 2);
+    }
+
+    #[test]
+    fn number_of_names_is_capped() {
+        let deps = setup();
+        let names = vec!["editors"; 101];
+        let result = query_as::<RolesResponse>(deps.as_ref(), roles_by_name(&names, None));
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+}
@@ -0,0 +1,122 @@
+use cosmwasm_std::Order;
+use cw_storage_plus::Bound;
+
+use crate::{
+    error::ContractError,
+    models::AuthRoleInfo,
+    responses::{RoleResponse, RolesResponse},
+    state::ROLE_INFOS,
+    utils::to_cannonical_role,
+};
+
+use super::ReadonlyContext;
+
+/// List roles at or nested under the given namespace prefix. For example,
+/// `/team/payments` matches `/team/payments` and `/team/payments/admin` but not
+/// `/team/payments-x`.
+pub fn query_roles_under(
+    ctx: ReadonlyContext,
+    prefix: String,
+) -> Result<RolesResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+
+    let prefix = to_cannonical_role(deps.storage, prefix)?;
+    let child_prefix = format!("{}/", prefix.trim_end_matches('/'));
+
+    // Every name under the prefix sorts between the prefix itself and the
+    // prefix followed by the character after '/', though so do some siblings
+    // (e.g. "-x" suffixes), which are filtered out below.
+    let max = format!("{}0", prefix.trim_end_matches('/'));
+
+    let mut role_resps: Vec<RoleResponse> = Vec::with_capacity(2);
+
+    for result in ROLE_INFOS.range(
+        deps.storage,
+        Some(Bound::inclusive(&prefix)),
+        Some(Bound::exclusive(&max)),
+        Order::Ascending,
+    ) {
+        let (
+            name,
+            AuthRoleInfo {
+                description,
+                created_at,
+                created_by,
+                n_principals,
+                is_template,
+                n_paths,
+                updated_at,
+                weight,
+            },
+        ) = result?;
+
+        if name != prefix && !name.starts_with(&child_prefix) {
+            continue;
+        }
+
+        role_resps.push(RoleResponse {
+            expires_at: None,
+            remaining_secs: None,
+            description,
+            created_at,
+            created_by,
+            n_principals,
+            is_template,
+            n_paths,
+            updated_at,
+            weight,
+            name,
+            granted_by: None,
+            granted_at: None,
+            paths: None,
+            paths_cursor: None,
+        });
+    }
+
+    Ok(RolesResponse(role_resps))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::Config,
+        msg::QueryMsg,
+        responses::RolesResponse,
+        testing::{create_role, query_as, setup_with_config},
+    };
+
+    #[test]
+    fn lists_roles_nested_under_namespace() {
+        let mut deps = setup_with_config(Config {
+            namespaced_roles: true,
+            ..Config::default()
+        });
+        for role in [
+            "team/payments",
+            "team/payments/admin",
+            "team/payments/ops/oncall",
+            "team/payments-x",
+            "team/payments-x/admin",
+            "team/billing",
+        ] {
+            create_role(deps.as_mut(), role, &[]);
+        }
+
+        let RolesResponse(roles) = query_as(
+            deps.as_ref(),
+            QueryMsg::RolesUnder {
+                prefix: "/team/payments".to_owned(),
+            },
+        )
+        .unwrap();
+        let names: Vec<&str> = roles.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "/team/payments",
+                "/team/payments/admin",
+                "/team/payments/ops/oncall",
+            ]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{error::ContractError, responses::PrincipalAllowed, state::CONFIG};
+
+use super::{is_allowed::try_authorize_path, ReadonlyContext};
+
+const MAX_PRINCIPALS: usize = 50;
+
+/// Test each of the given principals against a single path. This is the dual
+/// of IsAllowed, which tests a single principal against multiple paths.
+pub fn query_which_allowed(
+    ctx: ReadonlyContext,
+    principals: Vec<String>,
+    path: String,
+) -> Result<Vec<PrincipalAllowed>, ContractError> {
+    let ReadonlyContext { deps, env, .. } = ctx;
+
+    if principals.len() > MAX_PRINCIPALS {
+        return Err(ContractError::ValidationError {
+            reason: format!("cannot test more than {} principals", MAX_PRINCIPALS),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(principals
+        .into_iter()
+        .map(|principal| {
+            let allowed =
+                try_authorize_path(deps, &config, env.block.time, &principal, &path).is_ok();
+            PrincipalAllowed { principal, allowed }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        msg::QueryMsg,
+        responses::PrincipalAllowed,
+        testing::{create_role, grant_role, query_as, setup},
+    };
+
+    #[test]
+    fn reports_each_principal_in_order() {
+        let mut deps = setup();
+        create_role(deps.as_mut(), "editors", &["/docs"]);
+        grant_role(deps.as_mut(), "alice", "editors");
+        grant_role(deps.as_mut(), "carol", "editors");
+
+        let results: Vec<PrincipalAllowed> = query_as(
+            deps.as_ref(),
+            QueryMsg::WhichAllowed {
+                principals: vec!["alice".to_owned(), "bob".to_owned(), "carol".to_owned()],
+                path: "/docs/a".to_owned(),
+            },
+        )
+        .unwrap();
+        let allowed: Vec<(&str, bool)> = results
+            .iter()
+            .map(|r| (r.principal.as_str(), r.allowed))
+            .collect();
+        assert_eq!(
+            allowed,
+            vec![("alice", true), ("bob", false), ("carol", true)]
+        );
+    }
+}
@@ -1,7 +1,14 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Timestamp};
 
-use crate::{client::Operator, models::Config};
+use crate::{
+    client::Operator,
+    models::{default_role_weight, Config, PathRefCounts},
+    msg::{
+        ConsistencyCursor, CountKind, ExpiredCursor, ExportCursor, ExportEntry, PathRolesCursor,
+        RecentGrantsCursor,
+    },
+};
 
 #[cw_serde]
 pub struct AclResponse {
@@ -11,6 +18,21 @@ pub struct AclResponse {
     pub name: Option<String>,
     pub description: Option<String>,
     pub config: Config,
+    // Defaults allow responses from older ACLs to be read by newer ones
+    #[serde(default)]
+    pub n_roles: u32,
+    /// Number of distinct paths referenced by grants and roles.
+    #[serde(default)]
+    pub n_paths: u32,
+    /// Number of distinct principals holding grants or roles.
+    #[serde(default)]
+    pub n_principals: u32,
+    /// True if no one can operate the ACL, so it can no longer change.
+    #[serde(default)]
+    pub frozen: bool,
+    /// Address that passes every authorization check, if any.
+    #[serde(default)]
+    pub superuser: Option<Addr>,
 }
 
 #[cw_serde]
@@ -20,7 +42,24 @@ pub struct RoleResponse {
     pub created_at: Timestamp,
     pub created_by: Addr,
     pub n_principals: u32,
+    pub is_template: bool,
+    pub n_paths: u32,
+    /// Time at which the role's paths or description last changed.
+    #[serde(default)]
+    pub updated_at: Timestamp,
+    /// Weight of the role in weighted authorization checks.
+    #[serde(default = "default_role_weight")]
+    pub weight: u32,
     pub expires_at: Option<Timestamp>,
+    /// Seconds until expiry at the current block time, if the grant expires.
+    pub remaining_secs: Option<u64>,
+    pub granted_by: Option<Addr>,
+    pub granted_at: Option<Timestamp>,
+    /// Page of paths linked to the role, if requested with RoleWithPaths.
+    pub paths: Option<Vec<String>>,
+    /// Last path in the page, from which to continue with RoleWithPaths or a
+    /// Paths query, if the role has more.
+    pub paths_cursor: Option<String>,
 }
 
 #[cw_serde]
@@ -30,10 +69,249 @@ pub struct RolesResponse(pub Vec<RoleResponse>);
 pub struct PathsResponse {
     pub cursor: Option<String>,
     pub paths: Vec<PathInfo>,
+    /// Approximate number of paths for the subject as a whole, regardless of
+    /// any bounds (Acl and Role subjects only). This is an estimate, as it
+    /// counts paths whose only grants have expired but not yet been pruned.
+    pub approx_total: Option<u32>,
+    /// Whether ordering by expiry left out paths beyond the first 500 read,
+    /// so that the paths returned may not be the soonest to expire.
+    pub truncated: bool,
 }
 
 #[cw_serde]
 pub struct PathInfo {
     pub path: String,
     pub expires_at: Option<Timestamp>,
+    /// Seconds until expiry at the current block time, if the path expires.
+    pub remaining_secs: Option<u64>,
+    pub ref_count: Option<u32>,
+    /// The path's references by direct grants and by roles, of which
+    /// ref_count is the total.
+    pub refs: Option<PathRefCounts>,
+    pub granted_by: Option<Addr>,
+    pub granted_at: Option<Timestamp>,
+    pub granted_at_height: Option<u64>,
+    pub metadata: Option<String>,
+    /// Ancestors of the path, nearest first, if expanded.
+    pub ancestors: Option<Vec<String>>,
+    /// Referenced descendants of the path, up to a limit, if expanded.
+    pub descendants: Option<Vec<String>>,
+}
+
+#[cw_serde]
+pub enum AuthSource {
+    /// Principal is authorized to the matched path directly.
+    Direct,
+    /// Principal is authorized to the matched path via the named role.
+    Role(String),
+    /// Principal is the ACL's superuser, which bypasses all checks.
+    Superuser,
+    /// Principal's roles granting the path have the given summed weight,
+    /// which meets the required threshold.
+    Weighted(u32),
+}
+
+#[cw_serde]
+pub struct AuthMatch {
+    pub matched_path: String,
+    pub via: AuthSource,
+}
+
+#[cw_serde]
+pub struct PathExplanation {
+    pub path: String,
+    pub matched: Option<AuthMatch>,
+    pub failure: Option<AuthFailureKind>,
+    pub reason: Option<String>,
+}
+
+#[cw_serde]
+pub struct IsAllowedExplainResponse {
+    pub allowed: bool,
+    /// If not allowed, the reason IsAllowed would raise: in ALL mode, the
+    /// first failing path's reason; in ANY mode, every path's reason, in the
+    /// order the paths were given.
+    pub reason: Option<String>,
+    pub paths: Vec<PathExplanation>,
+}
+
+#[cw_serde]
+pub struct PrincipalAllowed {
+    pub principal: String,
+    pub allowed: bool,
+}
+
+#[cw_serde]
+pub struct PathDiff {
+    pub path: String,
+    pub a_allowed: bool,
+    pub b_allowed: bool,
+}
+
+#[cw_serde]
+pub enum AuthFailureKind {
+    /// No grant, direct or via role, covers the path.
+    NotGranted,
+    /// A covering grant exists but has expired and may be renewed.
+    Expired,
+    /// A covering grant exists but isn't active yet.
+    NotYetActive,
+    /// A covering grant exists but has no uses remaining.
+    Exhausted,
+    /// A covering grant exists but its external condition doesn't hold.
+    ConditionFailed,
+    /// The principal has been explicitly denied the path.
+    Denied,
+}
+
+#[cw_serde]
+pub struct CountsResponse {
+    pub n_roles: u32,
+    pub max_roles: Option<u32>,
+}
+
+#[cw_serde]
+pub struct ExportResponse {
+    pub entries: Vec<ExportEntry>,
+    pub cursor: Option<ExportCursor>,
+}
+
+#[cw_serde]
+pub struct AuthChainGrant {
+    pub via: AuthSource,
+    pub expires_at: Option<Timestamp>,
+    /// Whether the grant is in effect at the current block time.
+    pub active: bool,
+}
+
+#[cw_serde]
+pub struct AuthChainLevel {
+    pub path: String,
+    pub grants: Vec<AuthChainGrant>,
+    pub denied: bool,
+}
+
+#[cw_serde]
+pub struct AuthChainResponse {
+    pub levels: Vec<AuthChainLevel>,
+}
+
+#[cw_serde]
+pub struct ResolveResponse {
+    pub allowed: bool,
+    /// Path, the requested one or an ancestor, at which access was granted.
+    pub matched_path: Option<String>,
+    pub via: Option<AuthSource>,
+    /// Time at which the access ends, if it expires.
+    pub expires_at: Option<Timestamp>,
+    /// Reason the principal isn't allowed, if not.
+    pub reason: Option<String>,
+}
+
+#[cw_serde]
+pub enum AccessSourceResponse {
+    /// The principal has no access, or has access through neither a direct
+    /// grant nor a role, as the superuser does.
+    None,
+    /// Access comes only from the principal's most specific direct grant.
+    Direct { expires_at: Option<Timestamp> },
+    /// Access comes only from roles, the most specific of which is given.
+    Role {
+        name: String,
+        expires_at: Option<Timestamp>,
+    },
+    /// Access comes from both a direct grant and a role.
+    Both,
+}
+
+#[cw_serde]
+pub struct Discrepancy {
+    pub kind: CountKind,
+    pub key: String,
+    pub stored: u32,
+    pub computed: u32,
+}
+
+#[cw_serde]
+pub struct ConsistencyCheckResponse {
+    pub discrepancies: Vec<Discrepancy>,
+    pub cursor: Option<ConsistencyCursor>,
+}
+
+#[cw_serde]
+pub struct PathRole {
+    pub path: String,
+    pub role: String,
+}
+
+#[cw_serde]
+pub struct PathRolesResponse {
+    pub roles: Vec<PathRole>,
+    pub cursor: Option<PathRolesCursor>,
+}
+
+#[cw_serde]
+pub struct GrantInfo {
+    pub principal: String,
+    pub path: String,
+    pub granted_at: Timestamp,
+    pub granted_by: Addr,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct RecentGrantsResponse {
+    pub grants: Vec<GrantInfo>,
+    pub cursor: Option<RecentGrantsCursor>,
+}
+
+#[cw_serde]
+pub struct OperatorChainResponse {
+    /// Operators from this ACL's own operator outward.
+    pub chain: Vec<Operator>,
+    /// True if the chain leads back to an ACL already in it.
+    pub cycle: bool,
+    /// True if the chain continues beyond the maximum depth.
+    pub truncated: bool,
+}
+
+#[cw_serde]
+pub struct ExpiringGrant {
+    /// Path of a direct grant, if not a role grant.
+    pub path: Option<String>,
+    /// Role granted, if not a direct grant.
+    pub role: Option<String>,
+    pub expires_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct ExpiredGrant {
+    pub principal: String,
+    /// Path of a direct grant, if not a role grant.
+    pub path: Option<String>,
+    /// Role granted, if not a direct grant.
+    pub role: Option<String>,
+    pub expires_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct GlobPathsResponse {
+    pub paths: Vec<String>,
+    /// Last path scanned, from which to continue.
+    pub cursor: Option<String>,
+}
+
+#[cw_serde]
+pub struct ExpiredResponse {
+    pub grants: Vec<ExpiredGrant>,
+    pub cursor: Option<ExpiredCursor>,
+}
+
+#[cw_serde]
+pub struct ExpiringSoonResponse {
+    pub grants: Vec<ExpiringGrant>,
+    /// True if the principal has more grants than could be scanned, so some
+    /// expiring grants may be missing.
+    #[serde(default)]
+    pub truncated: bool,
 }
@@ -1,61 +1,112 @@
-use cosmwasm_std::{attr, Addr, Response, Timestamp};
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{attr, Addr, Order, Response, StdResult, Storage, Timestamp};
 use cw_storage_plus::{Item, Map};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    client::Operator,
+    client::{to_control_path, Operator, DEFAULT_CONTROL_PREFIX},
     error::ContractError,
-    execute::Context,
-    models::{AuthRecord, AuthRoleInfo},
-    msg::InstantiateMsg,
+    execute::{create_role::exec_create_role, grant_role::exec_grant_role, Context},
+    models::{
+        AuthRecord, AuthRoleInfo, Config, DenyRecord, GroupInfo, PathRefCounts, ScopedPowers,
+    },
+    msg::{InstantiateMsg, QueryMsg},
+    responses::AclResponse,
+    utils::{to_cannonical_path, to_cannonical_role, validate_operator},
 };
 
 type Role = String;
 type Principal = String;
 type Path = String;
+type Group = String;
 
 pub const MAX_NAME_LEN: usize = 100;
 pub const MAX_DESC_LEN: usize = 1000;
+pub const MAX_ROLE_NAME_LEN: usize = 100;
+pub const MAX_GROUP_NAME_LEN: usize = 100;
+pub const MAX_METADATA_LEN: usize = 500;
 
 pub const OP: Item<Operator> = Item::new("op");
+pub const CONFIG: Item<Config> = Item::new("config");
 pub const CREATED_BY: Item<Addr> = Item::new("created_by");
 pub const CREATED_AT: Item<Timestamp> = Item::new("created_at");
+pub const CREATED_AT_HEIGHT: Item<u64> = Item::new("created_at_height");
 pub const NAME: Item<String> = Item::new("name");
 pub const DESCRIPTION: Item<String> = Item::new("desc");
+pub const N_ROLES: Item<u32> = Item::new("n_roles");
+pub const N_PATHS: Item<u32> = Item::new("n_paths");
+pub const N_PRINCIPALS: Item<u32> = Item::new("n_principals");
+pub const IMPORT_IN_PROGRESS: Item<bool> = Item::new("importing");
+pub const CONTROL_PREFIX: Item<String> = Item::new("control_prefix");
+pub const SUPERUSER: Item<Addr> = Item::new("superuser");
 
-pub const PATH_REF_COUNTS: Map<&Path, u32> = Map::new("prc");
+pub const PATH_REF_COUNTS: Map<&Path, PathRefCounts> = Map::new("path_refs");
 pub const PRINCIPAL_PATH_AUTHORIZATIONS: Map<(&Principal, &Path), AuthRecord> = Map::new("ppa");
 pub const PRINCIPAL_ROLE_AUTHORIZATIONS: Map<(&Principal, &Role), AuthRecord> = Map::new("pra");
+pub const GRANTS_BY_TIME: Map<(u64, &Principal, &Path), u8> = Map::new("gbt");
+pub const PRINCIPAL_REF_COUNTS: Map<&Principal, u32> = Map::new("principal_refs");
+pub const PRINCIPAL_PATH_DENIALS: Map<(&Principal, &Path), DenyRecord> = Map::new("ppd");
+pub const PRINCIPAL_ROLE_PATH_DENIALS: Map<(&Principal, &Path, &Role), DenyRecord> =
+    Map::new("pprd");
+pub const ALIASES: Map<&Addr, Principal> = Map::new("al");
+pub const VIEWERS: Map<&Addr, Vec<u8>> = Map::new("viewers");
+pub const SUB_OPERATORS: Map<&Addr, ScopedPowers> = Map::new("sub_ops");
+pub const PRINCIPAL_ALIASES: Map<(&Principal, &Addr), u8> = Map::new("pal");
 
 pub const ROLE_INFOS: Map<&Role, AuthRoleInfo> = Map::new("ri");
-pub const ROLE_PATHS: Map<(&Role, &Path), u8> = Map::new("rp");
+pub const ROLE_PATHS: Map<(&Role, &Path), AuthRecord> = Map::new("rp");
 pub const PATH_ROLES: Map<(&Path, &Role), u8> = Map::new("pr");
+pub const ROLE_PRINCIPALS: Map<(&Role, &Principal), u8> = Map::new("rpr");
+pub const ROLE_PRINCIPAL_PATH_DENIALS: Map<(&Role, &Principal, &Path), u8> = Map::new("rppd");
+pub const ROLE_REQUIRED_GRANTS: Map<(&Role, &Principal, &Path), u8> = Map::new("rrg");
+
+pub const GROUP_INFOS: Map<&Group, GroupInfo> = Map::new("gi");
+pub const GROUP_MEMBERS: Map<(&Group, &Principal), u8> = Map::new("gm");
+pub const PRINCIPAL_GROUPS: Map<(&Principal, &Group), u8> = Map::new("pg");
 
 /// Top-level initialization of contract state
 pub fn init(
     ctx: Context,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, env, info } = ctx;
+    let Context {
+        mut deps,
+        env,
+        info,
+    } = ctx;
     let InstantiateMsg {
         operator,
         name,
         description,
+        config,
+        roles,
+        grants,
+        verify_operator,
+        control_prefix,
+        superuser,
     } = msg;
 
     // Validate operator
     let operator = if let Some(op) = &operator {
-        deps.api.addr_validate(
-            match op {
-                Operator::Address(addr) => addr,
-                Operator::Acl(addr) => addr,
-            }
-            .as_str(),
-        )?;
+        validate_operator(deps.api, op)?;
+        if let (Operator::Acl(acl_addr), true) = (op, verify_operator.unwrap_or(false)) {
+            deps.querier
+                .query_wasm_smart::<AclResponse>(acl_addr, &QueryMsg::Acl {})
+                .map_err(|e| ContractError::ValidationError {
+                    reason: format!("operator {} is not an ACL: {}", acl_addr, e),
+                })?;
+        }
         op.to_owned()
     } else {
         Operator::Address(info.sender.clone())
     };
 
+    // Blank names and descriptions are treated as unset rather than stored,
+    // so they can't be mistaken for set ones.
+    let name = name.filter(|name| !name.trim().is_empty());
+    let description = description.filter(|desc| !desc.trim().is_empty());
+
     // Set ACL name
     if let Some(name) = &name {
         if name.len() > MAX_NAME_LEN {
@@ -79,9 +130,67 @@ pub fn init(
         DESCRIPTION.save(deps.storage, desc)?;
     }
 
+    // Set prefix of the path through which the operator controls the ACL
+    if let Some(prefix) = &control_prefix {
+        if *prefix != to_cannonical_path(prefix) {
+            return Err(ContractError::ValidationError {
+                reason: format!("control prefix {} is not a canonical path", prefix),
+            });
+        }
+        CONTROL_PREFIX.save(deps.storage, prefix)?;
+    }
+
+    // Set the break-glass superuser, only if explicitly given
+    if let Some(superuser) = &superuser {
+        SUPERUSER.save(deps.storage, &deps.api.addr_validate(superuser)?)?;
+    }
+
     OP.save(deps.storage, &operator)?;
+    CONFIG.save(deps.storage, &config.unwrap_or_default())?;
     CREATED_AT.save(deps.storage, &env.block.time)?;
+    CREATED_AT_HEIGHT.save(deps.storage, &env.block.height)?;
     CREATED_BY.save(deps.storage, &info.sender)?;
+    N_ROLES.save(deps.storage, &0)?;
+    N_PATHS.save(deps.storage, &0)?;
+    N_PRINCIPALS.save(deps.storage, &0)?;
+
+    // Create initial roles
+    let roles = roles.unwrap_or_default();
+    let role_names: Vec<String> = roles
+        .iter()
+        .map(|r| to_cannonical_role(deps.storage, r.name.to_owned()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for role_msg in roles {
+        exec_create_role(
+            Context {
+                deps: deps.branch(),
+                env: env.clone(),
+                info: info.clone(),
+            },
+            role_msg,
+        )?;
+    }
+
+    // Grant initial roles, each of which must have been created above
+    for grant_msg in grants.unwrap_or_default() {
+        if !role_names.contains(&to_cannonical_role(
+            deps.storage,
+            grant_msg.role.to_owned(),
+        )?) {
+            return Err(ContractError::ValidationError {
+                reason: format!("granted role {} is not being created", grant_msg.role),
+            });
+        }
+        exec_grant_role(
+            Context {
+                deps: deps.branch(),
+                env: env.clone(),
+                info: info.clone(),
+            },
+            grant_msg,
+        )?;
+    }
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "instantiate"),
@@ -89,3 +198,446 @@ pub fn init(
         attr("acl_name", name.unwrap_or_default()),
     ]))
 }
+
+/// AuthRecord as stored prior to the addition of grant metadata
+#[derive(Serialize, Deserialize)]
+struct LegacyAuthRecord {
+    expires_at: Option<Timestamp>,
+}
+
+/// Backfill auth records stored before the addition of grant metadata, using
+/// the ACL's creator and creation time as placeholders. This also converts
+/// role-path links stored as u8 placeholders, prior to the addition of
+/// per-link TTLs, into permanent auth records.
+pub fn migrate_auth_records(store: &mut dyn Storage) -> StdResult<()> {
+    let granted_by = CREATED_BY.load(store)?;
+    let granted_at = CREATED_AT.load(store)?;
+
+    for namespace in ["ppa", "pra", "rp"] {
+        let legacy_map: Map<(&String, &String), LegacyAuthRecord> = Map::new(namespace);
+        let map: Map<(&String, &String), AuthRecord> = Map::new(namespace);
+
+        let keys: Vec<(String, String)> = legacy_map
+            .keys(store, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for (a, b) in keys.iter() {
+            if map.load(store, (a, b)).is_err() {
+                let expires_at = legacy_map
+                    .load(store, (a, b))
+                    .ok()
+                    .and_then(|r| r.expires_at);
+                map.save(
+                    store,
+                    (a, b),
+                    &AuthRecord {
+                        expires_at,
+                        not_before: None,
+                        max_uses: None,
+                        condition: None,
+                        metadata: None,
+                        no_inherit: false,
+                        require_role: None,
+                        granted_by: granted_by.clone(),
+                        granted_at,
+                        granted_at_height: 0,
+                    },
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Backfill the block height of grants made before heights were recorded,
+/// using the ACL's creation height as a placeholder. ACLs created before the
+/// creation height was recorded use the height at which they're migrated.
+pub fn migrate_grant_heights(
+    store: &mut dyn Storage,
+    height: u64,
+) -> StdResult<()> {
+    let created_at_height = match CREATED_AT_HEIGHT.may_load(store)? {
+        Some(h) => h,
+        None => {
+            CREATED_AT_HEIGHT.save(store, &height)?;
+            height
+        },
+    };
+
+    for map in [
+        PRINCIPAL_PATH_AUTHORIZATIONS,
+        PRINCIPAL_ROLE_AUTHORIZATIONS,
+        ROLE_PATHS,
+    ] {
+        let records: Vec<((String, String), AuthRecord)> = map
+            .range(store, None, None, Order::Ascending)
+            .filter(|r| {
+                r.as_ref()
+                    .map(|(_, rec)| rec.granted_at_height == 0)
+                    .unwrap_or(true)
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for ((a, b), mut record) in records.into_iter() {
+            record.granted_at_height = created_at_height;
+            map.save(store, (&a, &b), &record)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the path through which the operator controls the given ACL.
+pub fn load_control_path(
+    store: &dyn Storage,
+    acl_addr: &Addr,
+) -> StdResult<String> {
+    let prefix = CONTROL_PREFIX
+        .may_load(store)?
+        .unwrap_or_else(|| DEFAULT_CONTROL_PREFIX.to_owned());
+    Ok(to_control_path(&prefix, acl_addr))
+}
+
+/// Build the role-to-principals index from existing principal role grants.
+pub fn migrate_role_principals(store: &mut dyn Storage) -> StdResult<()> {
+    let keys: Vec<(Principal, Role)> = PRINCIPAL_ROLE_AUTHORIZATIONS
+        .keys(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (principal, role) in keys.iter() {
+        ROLE_PRINCIPALS.save(store, (role, principal), &0)?;
+    }
+
+    Ok(())
+}
+
+/// Initialize the role counter from existing roles.
+pub fn migrate_role_count(store: &mut dyn Storage) -> StdResult<()> {
+    if N_ROLES.may_load(store)?.is_none() {
+        let n = ROLE_INFOS.keys(store, None, None, Order::Ascending).count();
+        N_ROLES.save(store, &(n as u32))?;
+    }
+    Ok(())
+}
+
+/// Initialize the path and principal counters, along with the number of
+/// grants held by each principal, from existing paths and grants.
+pub fn migrate_path_and_principal_counts(store: &mut dyn Storage) -> StdResult<()> {
+    if N_PATHS.may_load(store)?.is_none() {
+        let n = PATH_REF_COUNTS
+            .keys(store, None, None, Order::Ascending)
+            .count();
+        N_PATHS.save(store, &(n as u32))?;
+    }
+    if N_PRINCIPALS.may_load(store)?.is_none() {
+        migrate_principal_ref_counts(store)?;
+    }
+    Ok(())
+}
+
+/// Rebuild the number of grants held by each principal, along with the
+/// principal counter.
+fn migrate_principal_ref_counts(store: &mut dyn Storage) -> StdResult<()> {
+    let principals: Vec<Principal> = PRINCIPAL_PATH_AUTHORIZATIONS
+        .keys(store, None, None, Order::Ascending)
+        .chain(PRINCIPAL_ROLE_AUTHORIZATIONS.keys(store, None, None, Order::Ascending))
+        .map(|r| r.map(|(principal, _)| principal))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut ref_counts: BTreeMap<Principal, u32> = BTreeMap::new();
+    for principal in principals.into_iter() {
+        *ref_counts.entry(principal).or_default() += 1;
+    }
+    for (principal, n) in ref_counts.iter() {
+        PRINCIPAL_REF_COUNTS.save(store, principal, n)?;
+    }
+    N_PRINCIPALS.save(store, &(ref_counts.len() as u32))?;
+    Ok(())
+}
+
+/// Path and principal ref counts were once stored as plain numbers under the
+/// same namespace, with each path's count lumping direct grants together with
+/// roles. Rebuild both under their own namespaces, counting a path's direct
+/// grants and roles separately, and clear the old namespace.
+pub fn migrate_split_ref_counts(store: &mut dyn Storage) -> StdResult<()> {
+    let legacy_map: Map<&str, u32> = Map::new("prc");
+    let legacy_keys: Vec<String> = legacy_map
+        .keys(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    if legacy_keys.is_empty() {
+        return Ok(());
+    }
+
+    let mut path_refs: BTreeMap<Path, PathRefCounts> = BTreeMap::new();
+    for result in PRINCIPAL_PATH_AUTHORIZATIONS.keys(store, None, None, Order::Ascending) {
+        let (_, path) = result?;
+        path_refs.entry(path).or_default().principals += 1;
+    }
+    for result in ROLE_PATHS.keys(store, None, None, Order::Ascending) {
+        let (_, path) = result?;
+        path_refs.entry(path).or_default().roles += 1;
+    }
+
+    for key in legacy_keys.iter() {
+        legacy_map.remove(store, key);
+    }
+    for (path, refs) in path_refs.iter() {
+        PATH_REF_COUNTS.save(store, path, refs)?;
+    }
+    N_PATHS.save(store, &(path_refs.len() as u32))?;
+
+    migrate_principal_ref_counts(store)
+}
+
+/// Build the index of direct grants by grant time from existing grants.
+pub fn migrate_grants_by_time(store: &mut dyn Storage) -> StdResult<()> {
+    let grants: Vec<((Principal, Path), AuthRecord)> = PRINCIPAL_PATH_AUTHORIZATIONS
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for ((principal, path), record) in grants.iter() {
+        GRANTS_BY_TIME.save(store, (record.granted_at.nanos(), principal, path), &0)?;
+    }
+
+    Ok(())
+}
+
+/// Build the role-to-denials index from existing role denials, along with the
+/// index of direct grants by the role they require.
+pub fn migrate_role_indexes(store: &mut dyn Storage) -> StdResult<()> {
+    let denials: Vec<(Principal, Path, Role)> = PRINCIPAL_ROLE_PATH_DENIALS
+        .keys(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (principal, path, role) in denials.iter() {
+        ROLE_PRINCIPAL_PATH_DENIALS.save(store, (role, principal, path), &0)?;
+    }
+
+    let grants: Vec<((Principal, Path), AuthRecord)> = PRINCIPAL_PATH_AUTHORIZATIONS
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for ((principal, path), record) in grants.iter() {
+        if let Some(role) = &record.require_role {
+            ROLE_REQUIRED_GRANTS.save(store, (role, principal, path), &0)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Backfill the update time of roles created before it was tracked with their
+/// creation time.
+pub fn migrate_role_update_times(store: &mut dyn Storage) -> StdResult<()> {
+    let roles: Vec<(Role, AuthRoleInfo)> = ROLE_INFOS
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (role, mut info) in roles.into_iter() {
+        if info.updated_at < info.created_at {
+            info.updated_at = info.created_at;
+            ROLE_INFOS.save(store, &role, &info)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Backfill the number of paths linked to each role.
+pub fn migrate_role_path_counts(store: &mut dyn Storage) -> StdResult<()> {
+    let roles: Vec<(Role, AuthRoleInfo)> = ROLE_INFOS
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (role, mut info) in roles.into_iter() {
+        info.n_paths = ROLE_PATHS
+            .prefix(&role)
+            .keys(store, None, None, Order::Ascending)
+            .count() as u32;
+        ROLE_INFOS.save(store, &role, &info)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env, mock_info},
+        to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+    };
+    use cw_multi_test::{App, ContractWrapper, Executor};
+
+    use crate::{
+        client::{to_control_path, Operator, DEFAULT_CONTROL_PREFIX},
+        contract::instantiate,
+        error::ContractError,
+        msg::{ExecuteMsg, InstantiateMsg},
+        testing::{
+            acl_contract, allow_msg, grant_role_msg, instantiate_acl, instantiate_msg, is_allowed,
+            role_msg, setup_with, OPERATOR,
+        },
+    };
+
+    #[test]
+    fn instantiate_with_roles_and_grants() {
+        let deps = setup_with(InstantiateMsg {
+            roles: Some(vec![role_msg("editors", &["/docs"])]),
+            grants: Some(vec![grant_role_msg("alice", "editors")]),
+            ..instantiate_msg()
+        });
+
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs/a"));
+        assert!(!is_allowed(deps.as_ref(), "bob", "/docs/a"));
+    }
+
+    #[test]
+    fn instantiate_rejects_grant_of_role_not_created() {
+        let result = instantiate(
+            mock_dependencies().as_mut(),
+            mock_env(),
+            mock_info(OPERATOR, &[]),
+            InstantiateMsg {
+                grants: Some(vec![grant_role_msg("alice", "editors")]),
+                ..instantiate_msg()
+            },
+        );
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+
+    fn other_execute(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn other_instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn other_query(
+        _deps: Deps,
+        _env: Env,
+        _msg: Empty,
+    ) -> StdResult<Binary> {
+        to_json_binary("not an acl")
+    }
+
+    #[test]
+    fn verify_operator_rejects_non_acl_contract() {
+        let mut app = App::default();
+        let acl_code_id = app.store_code(acl_contract());
+        let other_code_id = app.store_code(Box::new(ContractWrapper::new(
+            other_execute,
+            other_instantiate,
+            other_query,
+        )));
+        let other = app
+            .instantiate_contract(
+                other_code_id,
+                Addr::unchecked(OPERATOR),
+                &Empty {},
+                &[],
+                "other",
+                None,
+            )
+            .unwrap();
+        let operator_acl = instantiate_acl(&mut app, acl_code_id, None);
+
+        let mut instantiate_operated_by = |operator: &Addr, verify: bool| {
+            app.instantiate_contract(
+                acl_code_id,
+                Addr::unchecked(OPERATOR),
+                &InstantiateMsg {
+                    operator: Some(Operator::Acl(operator.clone())),
+                    name: Some("acl".to_owned()),
+                    verify_operator: Some(verify),
+                    ..instantiate_msg()
+                },
+                &[],
+                "acl",
+                None,
+            )
+        };
+
+        let err = instantiate_operated_by(&other, true).unwrap_err();
+        assert!(err.root_cause().to_string().contains("is not an ACL"));
+
+        instantiate_operated_by(&operator_acl, true).unwrap();
+        // Unverified, the mistake goes unnoticed
+        instantiate_operated_by(&other, false).unwrap();
+    }
+
+    #[test]
+    fn control_prefix_decides_who_may_execute() {
+        let mut app = App::default();
+        let code_id = app.store_code(acl_contract());
+        let operator_acl = instantiate_acl(&mut app, code_id, None);
+        let acl = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OPERATOR),
+                &InstantiateMsg {
+                    operator: Some(Operator::Acl(operator_acl.clone())),
+                    name: Some("acl".to_owned()),
+                    control_prefix: Some("/admin/acls".to_owned()),
+                    ..instantiate_msg()
+                },
+                &[],
+                "acl",
+                None,
+            )
+            .unwrap();
+
+        for (principal, prefix) in [("alice", "/admin/acls"), ("bob", DEFAULT_CONTROL_PREFIX)] {
+            app.execute_contract(
+                Addr::unchecked(OPERATOR),
+                operator_acl.clone(),
+                &ExecuteMsg::Allow(allow_msg(principal, &to_control_path(prefix, &acl))),
+                &[],
+            )
+            .unwrap();
+        }
+
+        let mut allow_as = |sender: &str| {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                acl.clone(),
+                &ExecuteMsg::Allow(allow_msg("carol", "/docs")),
+                &[],
+            )
+        };
+        allow_as("alice").unwrap();
+        allow_as("bob").unwrap_err();
+    }
+
+    #[test]
+    fn control_prefix_must_be_canonical() {
+        for prefix in ["admin", "/admin/", "/admin acls"] {
+            let result = instantiate(
+                mock_dependencies().as_mut(),
+                mock_env(),
+                mock_info(OPERATOR, &[]),
+                InstantiateMsg {
+                    control_prefix: Some(prefix.to_owned()),
+                    ..instantiate_msg()
+                },
+            );
+            assert!(
+                matches!(result, Err(ContractError::ValidationError { .. })),
+                "{}",
+                prefix
+            );
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use cosmwasm_std::{
+    from_json,
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    Addr, Deps, DepsMut, Empty, OwnedDeps, Response,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    client::Operator,
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    execute::Context,
+    models::Config,
+    msg::{
+        AllowMsg, CreateRoleMsg, ExecuteMsg, ForbidMsg, GrantRoleMsg, InstantiateMsg,
+        IsAllowedParams, QueryMsg, RoleExecuteMsg,
+    },
+    state::OP,
+};
+
+pub const OPERATOR: &str = "operator";
+
+pub type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+/// Message instantiating an ACL operated by its sender, with default config.
+pub fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        operator: None,
+        name: None,
+        description: None,
+        config: None,
+        roles: None,
+        grants: None,
+        verify_operator: None,
+        control_prefix: None,
+        superuser: None,
+    }
+}
+
+/// Instantiate an ACL operated by OPERATOR, with default config.
+pub fn setup() -> MockDeps {
+    setup_with(instantiate_msg())
+}
+
+/// Instantiate an ACL operated by OPERATOR with the given config.
+pub fn setup_with_config(config: Config) -> MockDeps {
+    setup_with(InstantiateMsg {
+        config: Some(config),
+        ..instantiate_msg()
+    })
+}
+
+/// Instantiate an ACL, as OPERATOR, from the given message.
+pub fn setup_with(msg: InstantiateMsg) -> MockDeps {
+    let mut deps = mock_dependencies();
+    instantiate(deps.as_mut(), mock_env(), mock_info(OPERATOR, &[]), msg).unwrap();
+    deps
+}
+
+pub fn exec(
+    deps: DepsMut,
+    sender: &str,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    execute(deps, mock_env(), mock_info(sender, &[]), msg)
+}
+
+pub fn query_as<T: DeserializeOwned>(
+    deps: Deps,
+    msg: QueryMsg,
+) -> Result<T, ContractError> {
+    Ok(from_json(query(deps, mock_env(), msg)?)?)
+}
+
+/// Message allowing a principal a path directly, with no TTL or other options.
+pub fn allow_msg(
+    principal: &str,
+    path: &str,
+) -> AllowMsg {
+    AllowMsg {
+        principal: principal.to_owned(),
+        path: path.to_owned(),
+        ttl: None,
+        ttl_unit: None,
+        not_before: None,
+        max_uses: None,
+        condition: None,
+        metadata: None,
+        no_inherit: None,
+        require_role: None,
+    }
+}
+
+/// Allow a principal a path directly, with no TTL or other options.
+pub fn allow(
+    deps: DepsMut,
+    principal: &str,
+    path: &str,
+) -> Response {
+    exec(
+        deps,
+        OPERATOR,
+        ExecuteMsg::Allow(allow_msg(principal, path)),
+    )
+    .unwrap()
+}
+
+/// IsAllowed parameters testing a principal against paths in ALL mode.
+pub fn is_allowed_params(
+    principal: &str,
+    paths: &[&str],
+) -> IsAllowedParams {
+    IsAllowedParams {
+        principal: principal.to_owned(),
+        require: None,
+        paths: paths.iter().map(|p| p.to_string()).collect(),
+        raise: None,
+        at: None,
+        negate: None,
+        expression: None,
+        hops: None,
+    }
+}
+
+/// Test a principal against a single path, without raising.
+pub fn is_allowed(
+    deps: Deps,
+    principal: &str,
+    path: &str,
+) -> bool {
+    query_as(
+        deps,
+        QueryMsg::IsAllowed(is_allowed_params(principal, &[path])),
+    )
+    .unwrap()
+}
+
+/// Message explicitly denying a principal a path, with no other options.
+pub fn forbid_msg(
+    principal: &str,
+    path: &str,
+) -> ForbidMsg {
+    ForbidMsg {
+        principal: principal.to_owned(),
+        path: path.to_owned(),
+        ttl: None,
+        role: None,
+        recursive: None,
+        force: None,
+    }
+}
+
+/// Explicitly deny a principal a path.
+pub fn forbid(
+    deps: DepsMut,
+    principal: &str,
+    path: &str,
+) -> Response {
+    exec(
+        deps,
+        OPERATOR,
+        ExecuteMsg::Forbid(forbid_msg(principal, path)),
+    )
+    .unwrap()
+}
+
+/// Context for calling a handler directly as OPERATOR, bypassing the operator
+/// check in execute.
+pub fn ctx(deps: DepsMut) -> Context {
+    Context {
+        deps,
+        env: mock_env(),
+        info: mock_info(OPERATOR, &[]),
+    }
+}
+
+/// Make the ACL its own operator. Handlers must then be called directly via
+/// ctx, as the mock querier can't route the operator check back to the ACL.
+pub fn self_operate(deps: DepsMut) {
+    OP.save(deps.storage, &Operator::Acl(mock_env().contract.address))
+        .unwrap();
+}
+
+/// Control path of a self-operated ACL under the default prefix.
+pub fn control_path() -> String {
+    format!("/acls/{}", mock_env().contract.address)
+}
+
+/// Message creating a role with the given paths and nothing else.
+pub fn role_msg(
+    name: &str,
+    paths: &[&str],
+) -> CreateRoleMsg {
+    CreateRoleMsg {
+        name: name.to_owned(),
+        description: None,
+        paths: Some(paths.iter().map(|p| p.to_string()).collect()),
+        is_template: None,
+        weight: None,
+        members: None,
+        upsert: None,
+    }
+}
+
+/// Create a role with the given paths.
+pub fn create_role(
+    deps: DepsMut,
+    name: &str,
+    paths: &[&str],
+) -> Response {
+    exec(
+        deps,
+        OPERATOR,
+        ExecuteMsg::Role(RoleExecuteMsg::Create(role_msg(name, paths))),
+    )
+    .unwrap()
+}
+
+/// Message granting a role to a principal, with no TTL.
+pub fn grant_role_msg(
+    principal: &str,
+    role: &str,
+) -> GrantRoleMsg {
+    GrantRoleMsg {
+        principal: principal.to_owned(),
+        role: role.to_owned(),
+        ttl: None,
+        ttl_unit: None,
+        not_before: None,
+    }
+}
+
+/// Grant a role to a principal, with no TTL.
+pub fn grant_role(
+    deps: DepsMut,
+    principal: &str,
+    role: &str,
+) -> Response {
+    exec(
+        deps,
+        OPERATOR,
+        ExecuteMsg::Role(RoleExecuteMsg::Grant(grant_role_msg(principal, role))),
+    )
+    .unwrap()
+}
+
+/// The ACL contract, for tests involving other contracts, such as operator
+/// ACLs, which the mock querier can't route queries to.
+pub fn acl_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// Instantiate an ACL in the app, as OPERATOR, with the given operator.
+pub fn instantiate_acl(
+    app: &mut App,
+    code_id: u64,
+    operator: Option<Operator>,
+) -> Addr {
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OPERATOR),
+        &InstantiateMsg {
+            operator,
+            // Chains, like multi-test, reject empty attributes
+            name: Some("acl".to_owned()),
+            ..instantiate_msg()
+        },
+        &[],
+        "acl",
+        None,
+    )
+    .unwrap()
+}
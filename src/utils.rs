@@ -1,18 +1,112 @@
-use cosmwasm_std::Storage;
+use cosmwasm_std::{attr, Addr, Api, Attribute, Storage, Timestamp};
+use sha2::{Digest, Sha256};
 
-use crate::{error::ContractError, math::sub_u32, state::PATH_REF_COUNTS};
+use crate::{
+    client::Operator,
+    error::ContractError,
+    math::{add_u32, sub_u32},
+    models::{AuthRecord, Config, DenyRecord, PathStyle},
+    msg::{Duration, ViewerAuth},
+    state::{
+        load_control_path, ALIASES, CONFIG, GRANTS_BY_TIME, MAX_ROLE_NAME_LEN, N_PATHS,
+        N_PRINCIPALS, OP, PATH_REF_COUNTS, PRINCIPAL_PATH_AUTHORIZATIONS, PRINCIPAL_REF_COUNTS,
+        PRINCIPAL_ROLE_PATH_DENIALS, ROLE_PRINCIPAL_PATH_DENIALS, ROLE_REQUIRED_GRANTS, VIEWERS,
+    },
+};
+use cw_storage_plus::Item;
 
-pub fn to_cannonical_path(raw_path: &String) -> String {
-    let mut path = raw_path.clone();
-    path = path.trim_matches('/').to_owned();
-    path = format!("/{}", path);
-    remove_non_printables(&path.replace(" ", "-"))
+/// Canonicalize a path by replacing spaces with dashes, removing any other
+/// non-printable characters, and dropping empty crumbs, so that the result is
+/// a single leading slash followed by non-empty, slash-separated crumbs. This
+/// is idempotent, so canonicalizing a canonical path has no effect.
+pub fn to_cannonical_path(raw_path: &str) -> String {
+    let path = remove_non_printables(&raw_path.replace(' ', "-"));
+    let crumbs: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    to_cannonical_path_from_crumbs(&crumbs)
 }
 
 pub fn to_cannonical_path_from_crumbs(crumbs: &[&str]) -> String {
     format!("/{}", crumbs.join("/"))
 }
 
+/// Canonicalize a path like to_cannonical_path, except without the leading
+/// slash if the given style is bare.
+pub fn to_styled_path(
+    style: &PathStyle,
+    raw_path: &str,
+) -> String {
+    let path = to_cannonical_path(raw_path);
+    match style {
+        PathStyle::Rooted => path,
+        PathStyle::Bare => path[1..].to_owned(),
+    }
+}
+
+pub fn to_styled_path_from_crumbs(
+    style: &PathStyle,
+    crumbs: &[&str],
+) -> String {
+    match style {
+        PathStyle::Rooted => to_cannonical_path_from_crumbs(crumbs),
+        PathStyle::Bare => crumbs.join("/"),
+    }
+}
+
+/// Canonicalize a path as configured, first percent-decoding it if enabled.
+/// Decoding happens before spaces are replaced, so `%20` becomes a dash.
+pub fn to_configured_path(
+    config: &Config,
+    raw_path: &str,
+) -> Result<String, ContractError> {
+    Ok(if config.decode_percent {
+        to_styled_path(&config.path_style, &percent_decode(raw_path)?)
+    } else {
+        to_styled_path(&config.path_style, raw_path)
+    })
+}
+
+/// Canonicalize a path in the ACL's configured path style, as it's keyed in
+/// storage.
+pub fn to_acl_path(
+    store: &dyn Storage,
+    raw_path: &str,
+) -> Result<String, ContractError> {
+    to_configured_path(&CONFIG.load(store)?, raw_path)
+}
+
+/// Decode %XX sequences in a path, failing on a malformed sequence or a
+/// result that isn't valid UTF-8.
+pub fn percent_decode(raw_path: &str) -> Result<String, ContractError> {
+    let malformed = || ContractError::ValidationError {
+        reason: format!("path {} has a malformed percent encoding", raw_path),
+    };
+    let bytes = raw_path.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            // Checked digit by digit, as from_str_radix also accepts a sign
+            let hex = bytes.get(i + 1..i + 3).ok_or_else(malformed)?;
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return Err(malformed());
+            }
+            let hex = std::str::from_utf8(hex).map_err(|_| malformed())?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| malformed())?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| malformed())
+}
+
+/// Split a canonical path into the crumbs from which it and each of its
+/// ancestors are built by to_cannonical_path_from_crumbs, in either style.
+pub fn to_crumbs(cannonical_path: &str) -> Vec<&str> {
+    cannonical_path.trim_matches('/').split('/').collect()
+}
+
 pub fn remove_non_printables(input: &str) -> String {
     input
         .chars()
@@ -20,18 +114,683 @@ pub fn remove_non_printables(input: &str) -> String {
         .collect::<String>()
 }
 
-/// Remove path from global path lookup table or decrement its ref count
-pub fn decrement_or_remove_path_ref_count(
+/// Source of a reference to a path.
+pub enum PathRef {
+    /// A principal's direct grant to the path.
+    Principal,
+    /// A role's link to the path.
+    Role,
+}
+
+/// Add path to global path lookup table or increment its ref count
+pub fn increment_path_ref_count(
     store: &mut dyn Storage,
     cannonical_path: &String,
+    source: PathRef,
 ) -> Result<(), ContractError> {
+    let mut refs = PATH_REF_COUNTS
+        .may_load(store, cannonical_path)?
+        .unwrap_or_default();
+    if refs.total() == 0 {
+        increment_counter(store, N_PATHS)?;
+    }
+    match source {
+        PathRef::Principal => refs.principals = add_u32(refs.principals, 1)?,
+        PathRef::Role => refs.roles = add_u32(refs.roles, 1)?,
+    }
+    PATH_REF_COUNTS.save(store, cannonical_path, &refs)?;
+    Ok(())
+}
+
+/// Remove path from global path lookup table or decrement its ref count,
+/// removing it once neither principals nor roles reference it. Returns true if
+/// a count saturated at zero. A count that's already zero, due to an earlier
+/// accounting error, is left as is with a warning rather than failing, so that
+/// revoking access to the path can always complete.
+pub fn decrement_or_remove_path_ref_count(
+    store: &mut dyn Storage,
+    cannonical_path: &String,
+    source: PathRef,
+) -> Result<bool, ContractError> {
+    let saturating = CONFIG.load(store)?.saturating_counts;
+    let mut saturated = false;
     // Remove path from global path lookup table or decrement its ref count
-    if let Some(n) = PATH_REF_COUNTS.may_load(store, &cannonical_path)? {
-        if n == 1 {
+    if let Some(mut refs) = PATH_REF_COUNTS.may_load(store, cannonical_path)? {
+        let n = match source {
+            PathRef::Principal => &mut refs.principals,
+            PathRef::Role => &mut refs.roles,
+        };
+        if *n == 0 {
+            saturated = true;
+        } else {
+            (*n, saturated) = sub_count(saturating, *n, 1)?;
+        }
+        if refs.total() == 0 {
             PATH_REF_COUNTS.remove(store, cannonical_path);
+            saturated |= decrement_counter(store, saturating, N_PATHS)?;
         } else {
-            PATH_REF_COUNTS.save(store, cannonical_path, &sub_u32(n, 1)?)?;
+            PATH_REF_COUNTS.save(store, cannonical_path, &refs)?;
         }
     }
+    Ok(saturated)
+}
+
+/// Count a new grant, direct or via a role, held by a principal
+pub fn increment_principal_ref_count(
+    store: &mut dyn Storage,
+    principal: &String,
+) -> Result<(), ContractError> {
+    let n = PRINCIPAL_REF_COUNTS
+        .may_load(store, principal)?
+        .unwrap_or_default();
+    if n == 0 {
+        increment_counter(store, N_PRINCIPALS)?;
+    }
+    PRINCIPAL_REF_COUNTS.save(store, principal, &add_u32(n, 1)?)?;
     Ok(())
 }
+
+/// Uncount a grant held by a principal, forgetting the principal once it
+/// holds no grants at all. Returns true if a count saturated at zero.
+pub fn decrement_or_remove_principal_ref_count(
+    store: &mut dyn Storage,
+    principal: &String,
+) -> Result<bool, ContractError> {
+    let saturating = CONFIG.load(store)?.saturating_counts;
+    let mut saturated = false;
+    if let Some(n) = PRINCIPAL_REF_COUNTS.may_load(store, principal)? {
+        let (n, n_saturated) = sub_count(saturating, n, 1)?;
+        saturated = n_saturated;
+        if n == 0 {
+            PRINCIPAL_REF_COUNTS.remove(store, principal);
+            saturated |= decrement_counter(store, saturating, N_PRINCIPALS)?;
+        } else {
+            PRINCIPAL_REF_COUNTS.save(store, principal, &n)?;
+        }
+    }
+    Ok(saturated)
+}
+
+fn increment_counter(
+    store: &mut dyn Storage,
+    counter: Item<u32>,
+) -> Result<(), ContractError> {
+    let n = counter.may_load(store)?.unwrap_or_default();
+    counter.save(store, &add_u32(n, 1)?)?;
+    Ok(())
+}
+
+fn decrement_counter(
+    store: &mut dyn Storage,
+    saturating: bool,
+    counter: Item<u32>,
+) -> Result<bool, ContractError> {
+    let n = counter.may_load(store)?.unwrap_or_default();
+    let (n, saturated) = sub_count(saturating, n, 1)?;
+    counter.save(store, &n)?;
+    Ok(saturated)
+}
+
+/// Subtract from a count. A count that would fall below zero points to an
+/// earlier accounting error, so when saturating, it stops at zero, returning
+/// true, instead of failing the operation.
+pub fn sub_count(
+    saturating: bool,
+    n: u32,
+    m: u32,
+) -> Result<(u32, bool), ContractError> {
+    match n.checked_sub(m) {
+        Some(n) => Ok((n, false)),
+        None if saturating => Ok((0, true)),
+        None => Ok((sub_u32(n, m)?, false)),
+    }
+}
+
+/// Attributes warning that a count saturated at zero, if one did.
+pub fn saturation_attributes(saturated: bool) -> Vec<Attribute> {
+    if saturated {
+        vec![attr(
+            "warning",
+            "count saturated at zero; run ConsistencyCheck to find the cause",
+        )]
+    } else {
+        vec![]
+    }
+}
+
+/// If the ACL is configured to validate principals, ensure that the given
+/// principal is a valid address. Otherwise, any string is accepted.
+pub fn ensure_valid_principal(
+    store: &dyn Storage,
+    api: &dyn Api,
+    principal: &str,
+) -> Result<(), ContractError> {
+    if CONFIG.load(store)?.validate_principals {
+        api.addr_validate(principal)?;
+    }
+    Ok(())
+}
+
+/// Ensure a use-limited grant allows at least one use, since a grant with
+/// none left would be stored but could never be consumed.
+pub fn ensure_valid_max_uses(max_uses: Option<u32>) -> Result<(), ContractError> {
+    if max_uses == Some(0) {
+        return Err(ContractError::ValidationError {
+            reason: "max_uses must be at least 1".to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Format a timestamp as an RFC 3339 UTC date-time, to the second.
+pub fn to_rfc3339(time: Timestamp) -> String {
+    let secs = time.seconds();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Convert days since the Unix epoch to a civil date. See Howard Hinnant's
+    // "chrono-Compatible Low-Level Date Algorithms".
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+/// Response attributes describing an expiry, both in the original format and
+/// as nanoseconds and RFC 3339 for tooling. Each is "null" if there's no
+/// expiry.
+pub fn expiry_attributes(expires_at: Option<Timestamp>) -> Vec<Attribute> {
+    let format = |f: fn(Timestamp) -> String| expires_at.map(f).unwrap_or(String::from("null"));
+    vec![
+        attr("expires_at", format(|t| t.to_string())),
+        attr("expires_at_nanos", format(|t| t.nanos().to_string())),
+        attr("expires_at_rfc3339", format(to_rfc3339)),
+    ]
+}
+
+/// Seconds remaining until the given expiry, clamped at zero, or None if there
+/// is no expiry.
+pub fn remaining_secs(
+    time: Timestamp,
+    expires_at: Option<Timestamp>,
+) -> Option<u64> {
+    expires_at.map(|t| t.seconds().saturating_sub(time.seconds()))
+}
+
+/// Prefix of the principal that stands for a group in role grants.
+pub const GROUP_PRINCIPAL_PREFIX: &str = "group:";
+
+/// Principal to which roles are granted on behalf of a group's members.
+pub fn to_group_principal(group: &str) -> String {
+    format!("{}{}", GROUP_PRINCIPAL_PREFIX, group)
+}
+
+/// Resolve an address to the principal it's aliased to, if any. Aliases are
+/// never chained, so a single lookup suffices.
+pub fn resolve_principal(
+    store: &dyn Storage,
+    principal: &str,
+) -> String {
+    ALIASES
+        .may_load(store, &Addr::unchecked(principal))
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| principal.to_owned())
+}
+
+/// If the ACL uses namespaced roles, canonicalize the role name like a path.
+/// Otherwise, the name is returned as-is.
+pub fn to_cannonical_role(
+    store: &dyn Storage,
+    role: String,
+) -> Result<String, ContractError> {
+    Ok(if CONFIG.load(store)?.namespaced_roles {
+        to_cannonical_path(&role)
+    } else {
+        role
+    })
+}
+
+/// Ensure a role name, as given rather than canonicalized, is non-empty and not
+/// too long. Unless the ACL uses namespaced roles, a name cannot contain
+/// slashes, so that plain role names are never confusable with paths. A
+/// namespaced name cannot canonicalize to the root.
+pub fn validate_role_name(
+    store: &dyn Storage,
+    role: &str,
+) -> Result<(), ContractError> {
+    if role.is_empty() {
+        return Err(ContractError::ValidationError {
+            reason: "role name cannot be empty".to_owned(),
+        });
+    }
+    if role.len() > MAX_ROLE_NAME_LEN {
+        return Err(ContractError::ValidationError {
+            reason: format!(
+                "role name cannot be longer than {} characters",
+                MAX_ROLE_NAME_LEN
+            ),
+        });
+    }
+    if CONFIG.load(store)?.namespaced_roles {
+        if to_cannonical_path(role) == "/" {
+            return Err(ContractError::ValidationError {
+                reason: format!("role name {} cannot be the root namespace", role),
+            });
+        }
+    } else if role.contains('/') {
+        return Err(ContractError::ValidationError {
+            reason: format!("role name {} cannot contain '/'", role),
+        });
+    }
+    Ok(())
+}
+
+/// Ensure that all addresses in the operator are valid and, for a multi-member
+/// operator, that there's at least one member and the threshold is attainable.
+pub fn validate_operator(
+    api: &dyn Api,
+    operator: &Operator,
+) -> Result<(), ContractError> {
+    match operator {
+        Operator::Address(addr) | Operator::Acl(addr) => {
+            api.addr_validate(addr.as_str())?;
+        },
+        Operator::Multi { addrs, threshold } => {
+            if addrs.is_empty() {
+                return Err(ContractError::ValidationError {
+                    reason: "operator must have at least one address".to_owned(),
+                });
+            }
+            if *threshold == 0 || *threshold as usize > addrs.len() {
+                return Err(ContractError::ValidationError {
+                    reason: format!("operator threshold must be between 1 and {}", addrs.len()),
+                });
+            }
+            for addr in addrs.iter() {
+                api.addr_validate(addr.as_str())?;
+            }
+        },
+        // Freezing the ACL requires confirmation, via RenounceOperator
+        Operator::None => {
+            return Err(ContractError::ValidationError {
+                reason: "operator can only be removed by RenounceOperator".to_owned(),
+            });
+        },
+    }
+    Ok(())
+}
+
+/// When the ACL is its own operator, access to its control path is what
+/// authorizes changes to it, so removing or blocking access at that path or any
+/// of its ancestors could lock out the operator for good. Unless forced, fail
+/// if any of the given canonical paths covers the control path.
+pub fn ensure_control_path_kept<'a>(
+    store: &dyn Storage,
+    acl_addr: &Addr,
+    cannonical_paths: impl IntoIterator<Item = &'a String>,
+    force: Option<bool>,
+) -> Result<(), ContractError> {
+    if force.unwrap_or(false) || OP.load(store)? != Operator::Acl(acl_addr.to_owned()) {
+        return Ok(());
+    }
+    let control_path = to_acl_path(store, &load_control_path(store, acl_addr)?)?;
+    let root = to_acl_path(store, "/")?;
+    for path in cannonical_paths {
+        if *path == root || *path == control_path || control_path.starts_with(&format!("{}/", path))
+        {
+            return Err(ContractError::ValidationError {
+                reason: format!(
+                    "{} covers the ACL's control path {}, so removing access to it could lock out the operator; use force to override",
+                    path, control_path
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Resolve a TTL given either in seconds or in some other unit, but not both,
+/// to seconds.
+pub fn resolve_ttl(
+    ttl: Option<u32>,
+    ttl_unit: Option<Duration>,
+) -> Result<Option<u32>, ContractError> {
+    let (n, secs_per_unit) = match ttl_unit {
+        None => return Ok(ttl),
+        Some(_) if ttl.is_some() => {
+            return Err(ContractError::ValidationError {
+                reason: "ttl and ttl_unit cannot both be set".to_owned(),
+            })
+        },
+        Some(Duration::Secs(n)) => (n, 1),
+        Some(Duration::Mins(n)) => (n, 60),
+        Some(Duration::Hours(n)) => (n, 60 * 60),
+        Some(Duration::Days(n)) => (n, 24 * 60 * 60),
+    };
+    n.checked_mul(secs_per_unit)
+        .map(Some)
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("ttl of {} x {} seconds is too long", n, secs_per_unit),
+        })
+}
+
+/// Apply the configured grant delay, if any, to a grant's requested activation
+/// time, so that the grant activates no sooner than the delay allows.
+pub fn delay_not_before(
+    config: &Config,
+    time: Timestamp,
+    not_before: Option<Timestamp>,
+) -> Option<Timestamp> {
+    match config.grant_delay_secs {
+        Some(secs) => {
+            let earliest = time.plus_seconds(secs.into());
+            Some(not_before.map(|t| t.max(earliest)).unwrap_or(earliest))
+        },
+        None => not_before,
+    }
+}
+
+/// Compute the expiry of a grant with the given TTL in seconds, if any,
+/// returning a validation error instead of overflowing the timestamp.
+pub fn expiry_from_ttl(
+    time: Timestamp,
+    ttl: Option<u32>,
+) -> Result<Option<Timestamp>, ContractError> {
+    ttl.map(|secs| {
+        u64::from(secs)
+            .checked_mul(1_000_000_000)
+            .and_then(|nanos| time.nanos().checked_add(nanos))
+            .map(Timestamp::from_nanos)
+            .ok_or_else(|| ContractError::ValidationError {
+                reason: format!("ttl of {} seconds overflows the expiry time", secs),
+            })
+    })
+    .transpose()
+}
+
+/// Save a principal's direct grant to a path, keeping the indexes of grants by
+/// grant time and by required role in sync. Returns the grant replaced, if any.
+pub fn save_grant(
+    store: &mut dyn Storage,
+    principal: &String,
+    cannonical_path: &String,
+    record: &AuthRecord,
+) -> Result<Option<AuthRecord>, ContractError> {
+    let prev = remove_grant(store, principal, cannonical_path)?;
+    PRINCIPAL_PATH_AUTHORIZATIONS.save(store, (principal, cannonical_path), record)?;
+    increment_principal_ref_count(store, principal)?;
+    GRANTS_BY_TIME.save(
+        store,
+        (record.granted_at.nanos(), principal, cannonical_path),
+        &0,
+    )?;
+    if let Some(role) = &record.require_role {
+        ROLE_REQUIRED_GRANTS.save(store, (role, principal, cannonical_path), &0)?;
+    }
+    Ok(prev)
+}
+
+/// Remove a principal's direct grant to a path, along with its entries in the
+/// indexes of grants by grant time and by required role. Returns the grant
+/// removed, if any.
+pub fn remove_grant(
+    store: &mut dyn Storage,
+    principal: &String,
+    cannonical_path: &String,
+) -> Result<Option<AuthRecord>, ContractError> {
+    let prev = PRINCIPAL_PATH_AUTHORIZATIONS.may_load(store, (principal, cannonical_path))?;
+    if let Some(record) = &prev {
+        PRINCIPAL_PATH_AUTHORIZATIONS.remove(store, (principal, cannonical_path));
+        // Saturation of the principal's count isn't reported for direct grants
+        decrement_or_remove_principal_ref_count(store, principal)?;
+        GRANTS_BY_TIME.remove(
+            store,
+            (record.granted_at.nanos(), principal, cannonical_path),
+        );
+        if let Some(role) = &record.require_role {
+            ROLE_REQUIRED_GRANTS.remove(store, (role, principal, cannonical_path));
+        }
+    }
+    Ok(prev)
+}
+
+/// Save a principal's denial of a path via a role, keeping the index of
+/// denials by role in sync.
+pub fn save_role_denial(
+    store: &mut dyn Storage,
+    principal: &String,
+    cannonical_path: &String,
+    role: &String,
+    record: &DenyRecord,
+) -> Result<(), ContractError> {
+    PRINCIPAL_ROLE_PATH_DENIALS.save(store, (principal, cannonical_path, role), record)?;
+    ROLE_PRINCIPAL_PATH_DENIALS.save(store, (role, principal, cannonical_path), &0)?;
+    Ok(())
+}
+
+/// Remove a principal's denial of a path via a role, along with its entry in
+/// the index of denials by role.
+pub fn remove_role_denial(
+    store: &mut dyn Storage,
+    principal: &String,
+    cannonical_path: &String,
+    role: &String,
+) {
+    PRINCIPAL_ROLE_PATH_DENIALS.remove(store, (principal, cannonical_path, role));
+    ROLE_PRINCIPAL_PATH_DENIALS.remove(store, (role, principal, cannonical_path));
+}
+
+/// Hash a viewing key for storage and comparison.
+pub fn hash_viewing_key(key: &str) -> Vec<u8> {
+    Sha256::digest(key.as_bytes()).to_vec()
+}
+
+/// If the ACL has private queries enabled, ensure the given viewer is an
+/// authorized viewer with a matching viewing key.
+pub fn ensure_viewer(
+    store: &dyn Storage,
+    viewer: Option<ViewerAuth>,
+) -> Result<(), ContractError> {
+    if !CONFIG.load(store)?.private_queries {
+        return Ok(());
+    }
+    let ViewerAuth { addr, key } = viewer.ok_or_else(|| ContractError::NotAuthorized {
+        reason: "query requires a viewer".to_owned(),
+    })?;
+    match VIEWERS.may_load(store, &Addr::unchecked(&addr))? {
+        Some(hash) if hash == hash_viewing_key(&key) => Ok(()),
+        _ => Err(ContractError::NotAuthorized {
+            reason: format!("{} is not an authorized viewer", addr),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::mock_env, Timestamp};
+
+    use super::{
+        expiry_from_ttl, percent_decode, resolve_ttl, to_crumbs, to_styled_path,
+        to_styled_path_from_crumbs,
+    };
+    use crate::{
+        error::ContractError,
+        models::{Config, PathStyle},
+        msg::{AllowMsg, Duration, ExecuteMsg},
+        testing::{allow, allow_msg, exec, is_allowed, setup, setup_with_config, OPERATOR},
+    };
+
+    const MESSY_PATHS: [&str; 8] = [
+        "/docs",
+        "docs/a",
+        "/a b/c",
+        "//a//b//",
+        " /a\tb/ c ",
+        "/caf\u{e9}/\u{7}bell",
+        "/a\nb/c\r",
+        "/--/x y z",
+    ];
+
+    #[test]
+    fn percent_decode_decodes_valid_sequences() {
+        for (raw, decoded) in [
+            ("/docs", "/docs"),
+            ("/my%20docs", "/my docs"),
+            ("/a%2Fb", "/a/b"),
+            ("/a%2fb", "/a/b"),
+            ("/caf%C3%A9", "/café"),
+        ] {
+            assert_eq!(percent_decode(raw).unwrap(), decoded, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn percent_decode_rejects_malformed_sequences() {
+        for raw in ["/a%+f", "/a%-1", "/a%2", "/a%", "/a%zz", "/a%ff", "/a%C3"] {
+            assert!(percent_decode(raw).is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn expiry_from_max_ttl_is_sane() {
+        let now = mock_env().block.time;
+        let expiry = expiry_from_ttl(now, Some(u32::MAX)).unwrap().unwrap();
+        assert_eq!(expiry.seconds() - now.seconds(), u64::from(u32::MAX));
+        assert_eq!(expiry_from_ttl(now, None).unwrap(), None);
+    }
+
+    #[test]
+    fn expiry_past_max_timestamp_is_rejected() {
+        let late = Timestamp::from_nanos(u64::MAX - 1_000_000_000);
+        assert!(expiry_from_ttl(late, Some(1)).unwrap().is_some());
+        assert!(matches!(
+            expiry_from_ttl(late, Some(2)),
+            Err(ContractError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn canonicalization_is_idempotent() {
+        for style in [PathStyle::Rooted, PathStyle::Bare] {
+            for raw in MESSY_PATHS {
+                let once = to_styled_path(&style, raw);
+                assert_eq!(to_styled_path(&style, &once), once, "{:?}", raw);
+            }
+        }
+    }
+
+    #[test]
+    fn crumbs_rebuild_the_keys_written() {
+        for style in [PathStyle::Rooted, PathStyle::Bare] {
+            for raw in MESSY_PATHS {
+                let written = to_styled_path(&style, raw);
+                let mut crumbs = to_crumbs(&written);
+                assert_eq!(to_styled_path_from_crumbs(&style, &crumbs), written);
+
+                // Each ancestor checked is itself a canonical path
+                while !crumbs.is_empty() {
+                    let ancestor = to_styled_path_from_crumbs(&style, &crumbs);
+                    assert_eq!(to_styled_path(&style, &ancestor), ancestor, "{:?}", raw);
+                    crumbs.pop();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn paths_with_spaces_match_when_checked() {
+        let mut deps = setup();
+        allow(deps.as_mut(), "alice", "/a b");
+
+        assert!(is_allowed(deps.as_ref(), "alice", "/a b/c"));
+        assert!(is_allowed(deps.as_ref(), "alice", "/a-b/c"));
+        assert!(is_allowed(deps.as_ref(), "alice", "a b/\u{7}c d"));
+        assert!(!is_allowed(deps.as_ref(), "alice", "/a/b"));
+    }
+
+    #[test]
+    fn ttl_units_convert_to_seconds() {
+        assert_eq!(
+            resolve_ttl(None, Some(Duration::Days(1))).unwrap(),
+            Some(86_400)
+        );
+        assert_eq!(
+            resolve_ttl(None, Some(Duration::Hours(2))).unwrap(),
+            Some(7_200)
+        );
+        assert_eq!(
+            resolve_ttl(None, Some(Duration::Mins(3))).unwrap(),
+            Some(180)
+        );
+        assert_eq!(resolve_ttl(None, Some(Duration::Secs(4))).unwrap(), Some(4));
+        assert_eq!(resolve_ttl(Some(5), None).unwrap(), Some(5));
+        assert_eq!(resolve_ttl(None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn ttl_units_reject_overflow_and_ambiguity() {
+        for (ttl, unit) in [
+            (None, Duration::Days(u32::MAX / 86_400 + 1)),
+            (None, Duration::Mins(u32::MAX)),
+            (Some(60), Duration::Secs(60)),
+        ] {
+            assert!(matches!(
+                resolve_ttl(ttl, Some(unit)),
+                Err(ContractError::ValidationError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn grant_with_ttl_in_days_expires_a_day_out() {
+        let mut deps = setup();
+        let resp = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(AllowMsg {
+                ttl_unit: Some(Duration::Days(1)),
+                ..allow_msg("alice", "/docs")
+            }),
+        )
+        .unwrap();
+        let expected = mock_env().block.time.plus_seconds(86_400);
+        assert!(resp
+            .attributes
+            .iter()
+            .any(|a| a.key == "expires_at_nanos" && a.value == expected.nanos().to_string()));
+    }
+
+    #[test]
+    fn percent_decoding_applies_to_grants_and_checks() {
+        let mut deps = setup_with_config(Config {
+            decode_percent: true,
+            ..Config::default()
+        });
+        allow(deps.as_mut(), "alice", "/docs/%20report");
+
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs/-report"));
+        assert!(is_allowed(deps.as_ref(), "alice", "/docs/ report/%61"));
+
+        let result = exec(
+            deps.as_mut(),
+            OPERATOR,
+            ExecuteMsg::Allow(allow_msg("alice", "/docs/%2")),
+        );
+        assert!(matches!(result, Err(ContractError::ValidationError { .. })));
+    }
+}